@@ -0,0 +1,60 @@
+pub mod music;
+pub mod output;
+
+// Short sounds played over the music, each from assets/audio/sound.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Sound {
+    // Walking into something that blocks the way.
+    Bump,
+    // A menu cursor moving.
+    Cursor,
+    // Moving on to the next line of a dialog.
+    Advance,
+    Door,
+}
+
+impl Sound {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Sound::Bump => "bump",
+            Sound::Cursor => "cursor",
+            Sound::Advance => "advance",
+            Sound::Door => "door",
+        }
+    }
+}
+
+// Sounds asked for during the frame, played by the AudioSystem.
+#[derive(Debug, Default)]
+pub struct SoundQueue {
+    pending: Vec<Sound>,
+}
+
+impl SoundQueue {
+    pub fn play(&mut self, sound: Sound) {
+        self.pending.push(sound);
+    }
+
+    pub fn drain(&mut self) -> Vec<Sound> {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queued_sounds_are_played_once() {
+        let mut queue = SoundQueue::default();
+        queue.play(Sound::Cursor);
+        queue.play(Sound::Advance);
+
+        // The AudioSystem drains the queue every frame, so nothing is played the frame after.
+        assert_eq!(queue.drain(), vec![Sound::Cursor, Sound::Advance]);
+        assert_eq!(queue.drain(), Vec::new());
+
+        queue.play(Sound::Door);
+        assert_eq!(queue.drain(), vec![Sound::Door]);
+    }
+}
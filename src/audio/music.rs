@@ -0,0 +1,135 @@
+// Seconds it takes one track to fade into the next.
+pub const CROSSFADE_TIME: f32 = 1.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Channel {
+    pub track: String,
+    // How far the track has faded in, from 0 to 1.
+    pub level: f32,
+}
+
+// Picks the music to play: the track of the current map, unless something like a battle
+// overrides it. Changing tracks fades the old one out while the new one fades in.
+#[derive(Debug, Default)]
+pub struct Music {
+    map_track: Option<String>,
+    override_track: Option<String>,
+    // The track that is playing or fading in.
+    current: Option<Channel>,
+    // The track fading out.
+    fading: Option<Channel>,
+}
+
+impl Music {
+    pub fn set_map_track(&mut self, track: Option<String>) {
+        self.map_track = track;
+    }
+
+    // Plays `track` over the map's music until the override is cleared with None.
+    pub fn set_override(&mut self, track: Option<String>) {
+        self.override_track = track;
+    }
+
+    fn wanted(&self) -> Option<&String> {
+        self.override_track.as_ref().or(self.map_track.as_ref())
+    }
+
+    // Moves the fades on by `delta` seconds.
+    pub fn advance(&mut self, delta: f32) {
+        let wanted = self.wanted().cloned();
+        if self.current.as_ref().map(|c| &c.track) != wanted.as_ref() {
+            // A track that is fading out fades back in from where it is.
+            let back = self.fading.take().filter(|f| Some(&f.track) == wanted.as_ref());
+            self.fading = self.current.take();
+            self.current = back.or_else(|| wanted.map(|track| Channel { track, level: 0.0 }));
+        }
+
+        let step = delta / CROSSFADE_TIME;
+        if let Some(current) = &mut self.current {
+            current.level = (current.level + step).min(1.0);
+        }
+        if let Some(fading) = &mut self.fading {
+            fading.level -= step;
+        }
+        if self.fading.as_ref().map(|f| f.level <= 0.0).unwrap_or(false) {
+            self.fading = None;
+        }
+    }
+
+    // The tracks to play, with how loud each is within the music volume.
+    pub fn channels(&self) -> Vec<Channel> {
+        self.current.iter().chain(self.fading.iter()).cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel(track: &str, level: f32) -> Channel {
+        Channel { track: track.to_string(), level }
+    }
+
+    // Plays `track` on the map until it has fully faded in.
+    fn playing(track: &str) -> Music {
+        let mut music = Music::default();
+        music.set_map_track(Some(track.to_string()));
+        music.advance(CROSSFADE_TIME);
+        music
+    }
+
+    #[test]
+    fn changing_the_map_track_crossfades_over_the_crossfade_time() {
+        let mut music = playing("route-1");
+        assert_eq!(music.channels(), vec![channel("route-1", 1.0)]);
+
+        music.set_map_track(Some("town".to_string()));
+        music.advance(CROSSFADE_TIME / 4.0);
+        assert_eq!(music.channels(), vec![channel("town", 0.25), channel("route-1", 0.75)]);
+
+        music.advance(CROSSFADE_TIME * 3.0 / 4.0);
+        assert_eq!(music.channels(), vec![channel("town", 1.0)]);
+    }
+
+    #[test]
+    fn an_override_replaces_the_map_track_until_it_is_cleared() {
+        let mut music = playing("route-1");
+
+        music.set_override(Some("battle".to_string()));
+        music.advance(CROSSFADE_TIME);
+        assert_eq!(music.channels(), vec![channel("battle", 1.0)]);
+
+        // The map track changing under the override is not heard until the override ends.
+        music.set_map_track(Some("town".to_string()));
+        music.advance(CROSSFADE_TIME);
+        assert_eq!(music.channels(), vec![channel("battle", 1.0)]);
+
+        music.set_override(None);
+        music.advance(CROSSFADE_TIME);
+        assert_eq!(music.channels(), vec![channel("town", 1.0)]);
+    }
+
+    #[test]
+    fn levels_stay_between_silent_and_full_mid_fade() {
+        let mut music = playing("route-1");
+        let tracks = ["town", "battle", "route-1", "town"];
+        for (i, delta) in [0.3, 0.45, 0.1, 0.7, 0.05, 1.3, 0.2, 0.6].iter().enumerate() {
+            music.set_map_track(Some(tracks[i % tracks.len()].to_string()));
+            music.advance(*delta);
+            for channel in music.channels() {
+                assert!(channel.level >= 0.0 && channel.level <= 1.0, "{:?}", channel);
+            }
+        }
+    }
+
+    #[test]
+    fn a_track_fading_out_fades_back_in_from_where_it_is() {
+        let mut music = playing("route-1");
+
+        music.set_override(Some("battle".to_string()));
+        music.advance(CROSSFADE_TIME / 4.0);
+        music.set_override(None);
+        music.advance(CROSSFADE_TIME / 8.0);
+        assert_eq!(music.channels(), vec![channel("route-1", 0.875), channel("battle", 0.125)]);
+    }
+}
@@ -0,0 +1,56 @@
+use amethyst::audio::{output::Output, AudioSink, Source};
+
+// Where music and sounds end up. The game plays through the audio device when there is one,
+// and through the muted output when there is none, e.g. on headless machines, so that the
+// music keeps its place either way.
+pub trait AudioOutput {
+    // Makes `tracks` the music playing, each looped at its volume. Tracks left out stop.
+    fn play_music(&mut self, tracks: &[(&str, &Source, f32)]);
+    fn play_sound(&mut self, source: &Source, volume: f32);
+}
+
+pub struct MutedOutput;
+
+impl AudioOutput for MutedOutput {
+    fn play_music(&mut self, _tracks: &[(&str, &Source, f32)]) {}
+
+    fn play_sound(&mut self, _source: &Source, _volume: f32) {}
+}
+
+// One sink per track playing, so that two tracks can overlap while they crossfade.
+pub struct DeviceOutput<'a> {
+    pub output: &'a Output,
+    pub sinks: &'a mut Vec<(String, AudioSink)>,
+}
+
+impl<'a> AudioOutput for DeviceOutput<'a> {
+    fn play_music(&mut self, tracks: &[(&str, &Source, f32)]) {
+        self.sinks.retain(|(track, sink)| {
+            let keep = tracks.iter().any(|(t, _, _)| t == track);
+            if !keep {
+                sink.stop();
+            }
+            keep
+        });
+
+        for (track, source, volume) in tracks {
+            if !self.sinks.iter().any(|(t, _)| t == track) {
+                self.sinks.push((track.to_string(), AudioSink::new(self.output)));
+            }
+
+            if let Some((_, sink)) = self.sinks.iter_mut().find(|(t, _)| t == track) {
+                // Queued again whenever it runs out, to loop.
+                if sink.empty() {
+                    if let Err(e) = sink.append(source) {
+                        eprintln!("failed to play {}: {}", track, e);
+                    }
+                }
+                sink.set_volume(*volume);
+            }
+        }
+    }
+
+    fn play_sound(&mut self, source: &Source, volume: f32) {
+        self.output.play_once(source, volume);
+    }
+}
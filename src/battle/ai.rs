@@ -0,0 +1,364 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::battle::{Battle, BattleAction};
+use crate::pokemon::{
+    library::Library,
+    moves::{MoveCategory, MoveEffect},
+};
+
+// Chooses the action of one side of a battle. Implementations own their randomness so that a
+// battle played with the same seeds always plays out the same way.
+pub trait BattleAi {
+    fn choose(&mut self, battle: &Battle, side: usize, library: &Library) -> BattleAction;
+}
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum AiTier {
+    // Any move with PP left.
+    #[default]
+    Random,
+    // The move with the highest expected damage.
+    Greedy,
+    // Weighs knock outs, status and stat moves, and switches out of bad matchups.
+    Smart,
+}
+
+impl AiTier {
+    pub fn build(self, seed: u64) -> Box<dyn BattleAi + Send + Sync> {
+        match self {
+            AiTier::Random => Box::new(RandomAi::new(seed)),
+            AiTier::Greedy => Box::new(GreedyAi::new(seed)),
+            AiTier::Smart => Box::new(SmartAi::new(seed)),
+        }
+    }
+}
+
+fn usable_moves(battle: &Battle, side: usize) -> Vec<usize> {
+    battle.sides[side].active().moves.iter()
+        .enumerate()
+        .filter(|(_, slot)| slot.pp > 0)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+// Picks the index with the best score. Scores within `margin` of the best are treated as ties
+// and broken randomly.
+fn pick_best<R: Rng>(scores: &[(usize, f32)], margin: f32, rng: &mut R) -> Option<usize> {
+    let best = scores.iter().map(|(_, s)| *s).fold(f32::MIN, f32::max);
+    let candidates: Vec<usize> = scores.iter()
+        .filter(|(_, s)| best - *s <= margin)
+        .map(|(i, _)| *i)
+        .collect();
+
+    if candidates.is_empty() {
+        None
+    } else {
+        Some(candidates[rng.gen_range(0, candidates.len())])
+    }
+}
+
+pub struct RandomAi {
+    rng: StdRng,
+}
+
+impl RandomAi {
+    pub fn new(seed: u64) -> Self {
+        RandomAi { rng: StdRng::seed_from_u64(seed) }
+    }
+}
+
+impl BattleAi for RandomAi {
+    fn choose(&mut self, battle: &Battle, side: usize, _library: &Library) -> BattleAction {
+        let usable = usable_moves(battle, side);
+        if usable.is_empty() {
+            BattleAction::Struggle
+        } else {
+            BattleAction::Fight(usable[self.rng.gen_range(0, usable.len())])
+        }
+    }
+}
+
+pub struct GreedyAi {
+    rng: StdRng,
+}
+
+impl GreedyAi {
+    pub fn new(seed: u64) -> Self {
+        GreedyAi { rng: StdRng::seed_from_u64(seed) }
+    }
+}
+
+impl BattleAi for GreedyAi {
+    fn choose(&mut self, battle: &Battle, side: usize, library: &Library) -> BattleAction {
+        let scores: Vec<(usize, f32)> = usable_moves(battle, side).into_iter()
+            .map(|i| (i, battle.expected_damage(library, side, i)))
+            .collect();
+
+        pick_best(&scores, 0.0, &mut self.rng).map(BattleAction::Fight).unwrap_or(BattleAction::Struggle)
+    }
+}
+
+pub struct SmartAi {
+    rng: StdRng,
+    // Set after switching, so the AI doesn't bounce between two pokemon every turn.
+    switched: bool,
+}
+
+impl SmartAi {
+    pub fn new(seed: u64) -> Self {
+        SmartAi { rng: StdRng::seed_from_u64(seed), switched: false }
+    }
+
+    fn score_move(battle: &Battle, side: usize, index: usize, library: &Library) -> f32 {
+        let attacker = battle.sides[side].active();
+        let target = battle.sides[1 - side].active();
+
+        let mv = match library.get_move(&attacker.moves[index].id) {
+            Some(mv) => mv,
+            None => return 0.0,
+        };
+
+        let hp = target.hp.max(1) as f32;
+        let damage = battle.expected_damage(library, side, index);
+
+        // Damage is worth the fraction of the remaining HP it takes, with a bonus for a knock out.
+        let mut score = (damage / hp).min(1.0);
+        if damage >= hp {
+            score += 1.0;
+        }
+
+        let accuracy = mv.accuracy.map(|a| a as f32 / 100.0 * battle.accuracy_multiplier()).unwrap_or(1.0);
+        match mv.effect {
+            Some(MoveEffect::Inflict { status, chance }) if battle.can_inflict(library, 1 - side, status) => {
+                let value = if mv.category == MoveCategory::Status { 0.6 } else { 0.3 };
+                score += value * accuracy * chance as f32 / 100.0;
+            }
+            Some(MoveEffect::Stat { stat, stages, on_user }) => {
+                let affected = if on_user { side } else { 1 - side };
+                let current = battle.sides[affected].stages.get(stat);
+                let room = if stages > 0 { current < 6 } else { current > -6 };
+
+                // Stat changes are worth less the more they have already been used.
+                if room {
+                    score += 0.3 * accuracy / (1.0 + current.abs() as f32);
+                }
+            }
+            _ => {}
+        }
+
+        score
+    }
+
+    // The best damage the opponent's active pokemon can expect to deal to a member of `side`.
+    fn incoming(battle: &Battle, side: usize, member: usize, library: &Library) -> f32 {
+        let foe = 1 - side;
+        (0..battle.sides[foe].active().moves.len())
+            .map(|i| battle.expected_damage_to(library, foe, i, member))
+            .fold(0.0, f32::max)
+    }
+
+    fn switch_target(&self, battle: &Battle, side: usize, library: &Library) -> Option<usize> {
+        let own = &battle.sides[side];
+        if self.switched {
+            return None;
+        }
+
+        // Stay in if the foe can be knocked out this turn.
+        let foe_hp = battle.sides[1 - side].active().hp as f32;
+        if (0..own.active().moves.len()).any(|i| battle.expected_damage(library, side, i) >= foe_hp) {
+            return None;
+        }
+
+        // Matchups are compared against max HP, so a pokemon isn't swapped out only for being hurt.
+        let ratio = |i: usize| SmartAi::incoming(battle, side, i, library) / own.team[i].stats.hp.max(1) as f32;
+        let threat = ratio(own.active);
+        if threat < 0.5 {
+            return None;
+        }
+
+        // Switch to whoever takes the least damage, as long as it halves the threat and survives
+        // the hit it comes in on comfortably.
+        (0..own.team.len())
+            .filter(|i| *i != own.active && !own.team[*i].is_fainted())
+            .filter(|i| SmartAi::incoming(battle, side, *i, library) < own.team[*i].hp as f32 * 0.5)
+            .map(|i| (i, ratio(i)))
+            .filter(|(_, r)| *r <= threat * 0.5)
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(i, _)| i)
+    }
+}
+
+impl BattleAi for SmartAi {
+    fn choose(&mut self, battle: &Battle, side: usize, library: &Library) -> BattleAction {
+        if let Some(index) = self.switch_target(battle, side, library) {
+            self.switched = true;
+            return BattleAction::Switch(index);
+        }
+        self.switched = false;
+
+        let scores: Vec<(usize, f32)> = usable_moves(battle, side).into_iter()
+            .map(|i| (i, SmartAi::score_move(battle, side, i, library)))
+            .collect();
+
+        pick_best(&scores, 0.05, &mut self.rng).map(BattleAction::Fight).unwrap_or(BattleAction::Struggle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::battle::{fixtures::pokemon, BattleEvent, OPPONENT, PLAYER};
+
+    fn battle(library: &Library) -> Battle {
+        Battle::trainer(
+            vec![pokemon(library, "charmander", 12, &["scratch", "growl", "ember"])],
+            vec![
+                pokemon(library, "bulbasaur", 12, &["tackle", "growl", "sleep-powder", "vine-whip"]),
+                pokemon(library, "squirtle", 12, &["tackle", "tail-whip", "bubble"]),
+            ],
+        )
+    }
+
+    // Plays a whole battle between two AIs of the same tier and returns everything that happened.
+    fn play(library: &Library, tier: AiTier, seed: u64) -> Vec<BattleEvent> {
+        let mut battle = battle(library);
+        let mut ais = [tier.build(seed), tier.build(seed.wrapping_add(1))];
+        let mut rng = StdRng::seed_from_u64(seed.wrapping_add(2));
+
+        let mut events = Vec::new();
+        while !battle.is_over() && battle.turn < 200 {
+            let actions = [ais[PLAYER].choose(&battle, PLAYER, library), ais[OPPONENT].choose(&battle, OPPONENT, library)];
+            events.extend(battle.play_turn(library, actions, &mut rng));
+        }
+        events
+    }
+
+    #[test]
+    fn every_tier_plays_the_same_battle_from_the_same_seeds() {
+        let library = Library::bundled();
+        for tier in [AiTier::Random, AiTier::Greedy, AiTier::Smart].iter() {
+            let events = play(&library, *tier, 29);
+            assert!(!events.is_empty());
+            assert_eq!(events, play(&library, *tier, 29), "{:?}", tier);
+        }
+    }
+
+    #[test]
+    fn greedy_picks_the_most_damaging_move() {
+        let library = Library::bundled();
+        let battle = battle(&library);
+
+        // Ember is super effective against the grass type and gets the same type bonus.
+        for seed in 0..10 {
+            assert_eq!(GreedyAi::new(seed).choose(&battle, PLAYER, &library), BattleAction::Fight(2));
+        }
+    }
+
+    #[test]
+    fn smart_prefers_status_over_chip_damage() {
+        let library = Library::bundled();
+        let battle = Battle::wild(
+            vec![pokemon(&library, "bulbasaur", 8, &["tackle", "growl", "sleep-powder"])],
+            pokemon(&library, "noctowl", 30, &["peck"]),
+        );
+
+        // Tackle barely scratches the noctowl, so greedy still goes for it while smart puts it
+        // to sleep.
+        assert_eq!(GreedyAi::new(0).choose(&battle, PLAYER, &library), BattleAction::Fight(0));
+        for seed in 0..10 {
+            assert_eq!(SmartAi::new(seed).choose(&battle, PLAYER, &library), BattleAction::Fight(2));
+        }
+    }
+
+    #[test]
+    fn smart_goes_for_the_knock_out() {
+        let library = Library::bundled();
+        let mut battle = battle(&library);
+        battle.sides[OPPONENT].active_mut().hp = 1;
+
+        // Any damaging move finishes the foe off, which beats lowering its attack.
+        for seed in 0..10 {
+            let action = SmartAi::new(seed).choose(&battle, PLAYER, &library);
+            assert!(action == BattleAction::Fight(0) || action == BattleAction::Fight(2), "{:?}", action);
+        }
+    }
+
+    #[test]
+    fn every_tier_struggles_without_pp() {
+        let library = Library::bundled();
+        let mut battle = battle(&library);
+        for slot in battle.sides[PLAYER].active_mut().moves.iter_mut() {
+            slot.pp = 0;
+        }
+
+        assert!(battle.must_struggle(PLAYER));
+        for tier in [AiTier::Random, AiTier::Greedy, AiTier::Smart].iter() {
+            assert_eq!(tier.build(0).choose(&battle, PLAYER, &library), BattleAction::Struggle, "{:?}", tier);
+        }
+    }
+
+    #[test]
+    fn struggling_hurts_both_sides() {
+        let library = Library::bundled();
+        let mut battle = battle(&library);
+        let mut rng = StdRng::seed_from_u64(29);
+        let (hp, foe_hp) = (battle.sides[PLAYER].active().hp, battle.sides[OPPONENT].active().hp);
+
+        let events = battle.play_turn(&library, [BattleAction::Struggle, BattleAction::Fight(1)], &mut rng);
+
+        assert!(events.contains(&BattleEvent::Recoil { side: PLAYER, name: "CHARMANDER".to_string() }));
+        assert!(battle.sides[OPPONENT].active().hp < foe_hp);
+        assert_eq!(battle.sides[PLAYER].active().hp, hp - battle.sides[PLAYER].active().stats.hp / 4);
+    }
+
+    // A bulbasaur in front of a much stronger charmander, with a squirtle that resists its fire
+    // waiting on the bench.
+    fn bad_matchup(library: &Library) -> Battle {
+        Battle::trainer(
+            vec![
+                pokemon(library, "bulbasaur", 10, &["tackle", "growl"]),
+                pokemon(library, "squirtle", 30, &["tackle", "bubble"]),
+            ],
+            vec![pokemon(library, "charmander", 30, &["ember"])],
+        )
+    }
+
+    #[test]
+    fn smart_switches_out_of_a_bad_matchup() {
+        let library = Library::bundled();
+        let battle = bad_matchup(&library);
+
+        // Greedy never switches.
+        assert_eq!(GreedyAi::new(0).choose(&battle, PLAYER, &library), BattleAction::Fight(0));
+        for seed in 0..10 {
+            assert_eq!(SmartAi::new(seed).choose(&battle, PLAYER, &library), BattleAction::Switch(1));
+        }
+    }
+
+    #[test]
+    fn smart_does_not_switch_twice_in_a_row() {
+        let library = Library::bundled();
+        let battle = bad_matchup(&library);
+        let mut ai = SmartAi::new(0);
+
+        assert_eq!(ai.choose(&battle, PLAYER, &library), BattleAction::Switch(1));
+        assert!(matches!(ai.choose(&battle, PLAYER, &library), BattleAction::Fight(_)));
+        // Having fought a turn, it may switch again.
+        assert_eq!(ai.choose(&battle, PLAYER, &library), BattleAction::Switch(1));
+    }
+
+    #[test]
+    fn smart_never_switches_to_a_fainted_member() {
+        let library = Library::bundled();
+        let mut battle = bad_matchup(&library);
+        battle.sides[PLAYER].team[1].hp = 0;
+
+        for seed in 0..10 {
+            let action = SmartAi::new(seed).choose(&battle, PLAYER, &library);
+            assert!(matches!(action, BattleAction::Fight(_)), "{:?}", action);
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use rand::Rng;
+
+use crate::pokemon::{library::Library, Pokemon, Status};
+
+// A ball shakes at most this many times before the pokemon is caught.
+pub const SHAKES: u8 = 4;
+
+// The result of throwing a ball. `shakes` counts the checks passed before the pokemon broke
+// free, so a caught pokemon always has all four.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct CatchResult {
+    pub shakes: u8,
+    pub caught: bool,
+}
+
+pub fn status_bonus(status: Option<Status>) -> f32 {
+    match status {
+        Some(Status::Sleep) => 2.0,
+        Some(Status::Poison) | Some(Status::Burn) | Some(Status::Paralysis) => 1.5,
+        None => 1.0,
+    }
+}
+
+// The modified catch rate: higher the weaker the pokemon, and 255 or more is a sure catch.
+pub fn catch_value(library: &Library, pokemon: &Pokemon, ball: f32) -> u32 {
+    let rate = library.get_species(&pokemon.species).map(|s| s.catch_rate).unwrap_or(255) as f32;
+    let max_hp = pokemon.stats.hp.max(1) as f32;
+    let hp = pokemon.hp.min(pokemon.stats.hp) as f32;
+
+    let value = (3.0 * max_hp - 2.0 * hp) * rate * ball / (3.0 * max_hp) * status_bonus(pokemon.status);
+    (value as u32).max(1)
+}
+
+// The chance out of 65536 that a single shake check passes.
+pub fn shake_threshold(value: u32) -> u32 {
+    if value >= 255 {
+        return 65536;
+    }
+
+    (1_048_560.0 / (16_711_680.0 / value as f64).sqrt().sqrt()) as u32
+}
+
+pub fn attempt<R: Rng>(library: &Library, pokemon: &Pokemon, ball: f32, rng: &mut R) -> CatchResult {
+    let value = catch_value(library, pokemon, ball);
+    if value >= 255 {
+        return CatchResult { shakes: SHAKES, caught: true };
+    }
+
+    let threshold = shake_threshold(value);
+    let shakes = (0..SHAKES)
+        .take_while(|_| rng.gen_range(0, 65536) < threshold)
+        .count() as u8;
+
+    CatchResult { shakes, caught: shakes == SHAKES }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    // A bulbasaur, which has a catch rate of 45, with 30 max HP and `hp` left.
+    fn bulbasaur(library: &Library, hp: u16, status: Option<Status>) -> Pokemon {
+        let mut pokemon = Pokemon::new(library, "bulbasaur", 10).unwrap();
+        pokemon.stats.hp = 30;
+        pokemon.hp = hp;
+        pokemon.status = status;
+        pokemon
+    }
+
+    #[test]
+    fn catch_value_grows_as_hp_drops() {
+        let library = Library::bundled();
+        assert_eq!(catch_value(&library, &bulbasaur(&library, 30, None), 1.0), 15);
+        assert_eq!(catch_value(&library, &bulbasaur(&library, 15, None), 1.0), 30);
+        assert_eq!(catch_value(&library, &bulbasaur(&library, 1, None), 1.0), 44);
+    }
+
+    #[test]
+    fn status_and_ball_multiply_the_catch_value() {
+        let library = Library::bundled();
+        assert_eq!(catch_value(&library, &bulbasaur(&library, 1, Some(Status::Sleep)), 1.0), 88);
+        assert_eq!(catch_value(&library, &bulbasaur(&library, 1, Some(Status::Paralysis)), 1.0), 66);
+        assert_eq!(catch_value(&library, &bulbasaur(&library, 1, Some(Status::Burn)), 1.0), 66);
+        assert_eq!(catch_value(&library, &bulbasaur(&library, 1, None), 1.5), 66);
+        assert_eq!(catch_value(&library, &bulbasaur(&library, 30, None), 1.5), 22);
+        assert_eq!(catch_value(&library, &bulbasaur(&library, 30, None), 2.0), 30);
+        assert_eq!(catch_value(&library, &bulbasaur(&library, 1, Some(Status::Sleep)), 2.0), 176);
+    }
+
+    #[test]
+    fn shake_threshold_matches_the_gen_3_table() {
+        assert_eq!(shake_threshold(1), 16399);
+        assert_eq!(shake_threshold(15), 32274);
+        assert_eq!(shake_threshold(44), 42237);
+        assert_eq!(shake_threshold(88), 50229);
+        assert_eq!(shake_threshold(254), 65470);
+        assert_eq!(shake_threshold(255), 65536);
+    }
+
+    #[test]
+    fn a_catch_value_of_255_always_catches() {
+        let library = Library::bundled();
+        // Rattata has a catch rate of 255, so a sleeping one with 1 HP left is a sure catch.
+        let mut rattata = Pokemon::new(&library, "rattata", 5).unwrap();
+        rattata.hp = 1;
+        rattata.status = Some(Status::Sleep);
+        assert!(catch_value(&library, &rattata, 1.0) >= 255);
+
+        for seed in 0..20 {
+            let result = attempt(&library, &rattata, 1.0, &mut StdRng::seed_from_u64(seed));
+            assert_eq!(result, CatchResult { shakes: SHAKES, caught: true });
+        }
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_result() {
+        let library = Library::bundled();
+        let pokemon = bulbasaur(&library, 10, None);
+        for seed in 0..20 {
+            let first = attempt(&library, &pokemon, 1.0, &mut StdRng::seed_from_u64(seed));
+            let second = attempt(&library, &pokemon, 1.0, &mut StdRng::seed_from_u64(seed));
+            assert_eq!(first, second);
+            assert_eq!(first.caught, first.shakes == SHAKES);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use rand::Rng;
+
+use crate::battle::weather::Weather;
+use crate::pokemon::{
+    library::Library,
+    moves::{Move, MoveCategory},
+    Pokemon,
+};
+
+pub struct Damage {
+    pub amount: u16,
+    pub effectiveness: f32,
+    pub critical: bool,
+}
+
+pub fn type_multiplier(library: &Library, mv: &Move, defender: &Pokemon) -> f32 {
+    library.get_species(&defender.species)
+        .map(|s| mv.kind.effectiveness_against(&s.types))
+        .unwrap_or(1.0)
+}
+
+pub fn weather_multiplier(mv: &Move, weather: Option<Weather>) -> f32 {
+    weather.map(|w| w.power_multiplier(mv.kind)).unwrap_or(1.0)
+}
+
+pub fn stab_multiplier(library: &Library, mv: &Move, attacker: &Pokemon) -> f32 {
+    let stab = library.get_species(&attacker.species)
+        .map(|s| s.types.contains(&mv.kind))
+        .unwrap_or(false);
+
+    if stab { 1.5 } else { 1.0 }
+}
+
+// Damage before the random factor and critical hits, used by both the engine and the AI.
+// `attack` and `defense` are the effective stats for the move's category, after stages and status.
+pub fn base_damage(library: &Library, attacker: &Pokemon, defender: &Pokemon, mv: &Move,
+                   attack: f32, defense: f32, weather: Option<Weather>) -> f32 {
+    if mv.category == MoveCategory::Status {
+        return 0.0;
+    }
+
+    let level = attacker.level as f32;
+    let damage = ((2.0 * level / 5.0 + 2.0) * mv.power as f32 * attack / defense.max(1.0)) / 50.0 + 2.0;
+
+    damage * stab_multiplier(library, mv, attacker) * type_multiplier(library, mv, defender) *
+        weather_multiplier(mv, weather)
+}
+
+// `stats` are the effective attack and defense, as for `base_damage`.
+pub fn calculate<R: Rng>(library: &Library, attacker: &Pokemon, defender: &Pokemon, mv: &Move,
+                         (attack, defense): (f32, f32), weather: Option<Weather>, rng: &mut R) -> Damage {
+    let effectiveness = type_multiplier(library, mv, defender);
+    if mv.category == MoveCategory::Status || effectiveness == 0.0 {
+        return Damage { amount: 0, effectiveness, critical: false };
+    }
+
+    let critical = rng.gen_range(0, 16) == 0;
+    let mut damage = base_damage(library, attacker, defender, mv, attack, defense, weather);
+    if critical {
+        damage *= 2.0;
+    }
+    damage = damage * rng.gen_range(85, 101) as f32 / 100.0;
+
+    Damage {
+        amount: (damage as u16).max(1),
+        effectiveness,
+        critical,
+    }
+}
@@ -0,0 +1,10 @@
+use crate::pokemon::{library::Library, MoveSlot, Pokemon};
+
+// A pokemon that knows exactly the given moves, with full PP.
+pub fn pokemon(library: &Library, species: &str, level: u8, moves: &[&str]) -> Pokemon {
+    let mut pokemon = Pokemon::new(library, species, level).unwrap();
+    pokemon.moves = moves.iter()
+        .map(|id| MoveSlot { id: id.to_string(), pp: library.get_move(id).unwrap().pp })
+        .collect();
+    pokemon
+}
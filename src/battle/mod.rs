@@ -0,0 +1,899 @@
+pub mod damage;
+pub mod ai;
+pub mod catch;
+pub mod weather;
+#[cfg(test)]
+pub mod fixtures;
+
+use rand::Rng;
+
+use crate::battle::weather::Weather;
+use crate::item::{self, ItemEffect, ItemOutcome};
+use crate::pokemon::{
+    library::Library,
+    moves::{BattleStat, Move, MoveCategory, MoveEffect},
+    types::PokemonType,
+    Pokemon, Status,
+};
+
+pub const PLAYER: usize = 0;
+pub const OPPONENT: usize = 1;
+
+// The move used when none of the active pokemon's moves have PP left.
+pub const STRUGGLE: &str = "struggle";
+
+// Weather that goes on is only mentioned again every few turns, not after every one.
+const WEATHER_REMINDER_TURNS: u32 = 5;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum BattleKind {
+    Wild,
+    Trainer,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum BattleOutcome {
+    Won,
+    Lost,
+    Fled,
+    Caught,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum BattleAction {
+    Fight(usize),
+    // Attacks without using a move slot, for when no move has PP left.
+    Struggle,
+    Switch(usize),
+    // Uses an item from the bag on the team member at `target`.
+    Item { item: String, target: usize },
+    // Throws a ball from the bag at the opposing pokemon.
+    Throw(String),
+    Run,
+}
+
+impl BattleAction {
+    // Escapes resolve first, then switches and items, then moves.
+    fn priority(&self) -> u8 {
+        match self {
+            BattleAction::Run => 2,
+            BattleAction::Switch(_) | BattleAction::Item { .. } | BattleAction::Throw(_) => 1,
+            BattleAction::Fight(_) | BattleAction::Struggle => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BattleEvent {
+    SwitchedIn { side: usize, name: String, species: String },
+    MoveUsed { side: usize, name: String, move_id: String },
+    NoPp { side: usize },
+    Missed { side: usize, name: String },
+    Damaged { side: usize, amount: u16 },
+    Recoil { side: usize, name: String },
+    Critical,
+    Effectiveness(f32),
+    StatChanged { side: usize, name: String, stat: BattleStat, stages: i8 },
+    StatusInflicted { side: usize, name: String, status: Status },
+    StatusDamage { side: usize, name: String, status: Status },
+    WeatherContinues(Weather),
+    WeatherEnded(Weather),
+    WeatherDamage { side: usize, name: String, weather: Weather },
+    FullyParalyzed { side: usize, name: String },
+    FastAsleep { side: usize, name: String },
+    WokeUp { side: usize, name: String },
+    Failed,
+    ItemUsed { side: usize, item: String },
+    Healed { side: usize, name: String },
+    StatusCured { side: usize, name: String },
+    Revived { side: usize, name: String },
+    PpRestored { side: usize, name: String },
+    NoEffect,
+    BallThrown { item: String },
+    // The ball shook the given number of times, up to three, before the result.
+    BallShook(u8),
+    Caught { name: String, species: String },
+    BrokeFree { shakes: u8 },
+    BallBlocked,
+    Fainted { side: usize, name: String },
+    Fled,
+    FleeFailed,
+    NoRunning,
+    Ended(BattleOutcome),
+}
+
+// Stat stages of the active pokemon, from -6 to +6. They are reset when it leaves the field.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Stages {
+    pub attack: i8,
+    pub defense: i8,
+    pub sp_attack: i8,
+    pub sp_defense: i8,
+    pub speed: i8,
+}
+
+impl Stages {
+    pub fn get(&self, stat: BattleStat) -> i8 {
+        match stat {
+            BattleStat::Attack => self.attack,
+            BattleStat::Defense => self.defense,
+            BattleStat::SpAttack => self.sp_attack,
+            BattleStat::SpDefense => self.sp_defense,
+            BattleStat::Speed => self.speed,
+        }
+    }
+
+    // Applies a change and returns how many stages actually changed.
+    pub fn apply(&mut self, stat: BattleStat, stages: i8) -> i8 {
+        let stage = match stat {
+            BattleStat::Attack => &mut self.attack,
+            BattleStat::Defense => &mut self.defense,
+            BattleStat::SpAttack => &mut self.sp_attack,
+            BattleStat::SpDefense => &mut self.sp_defense,
+            BattleStat::Speed => &mut self.speed,
+        };
+
+        let old = *stage;
+        *stage = (old + stages).clamp(-6, 6);
+        *stage - old
+    }
+
+    pub fn multiplier(stage: i8) -> f32 {
+        if stage >= 0 {
+            (2 + stage) as f32 / 2.0
+        } else {
+            2.0 / (2 - stage) as f32
+        }
+    }
+}
+
+pub struct Side {
+    pub team: Vec<Pokemon>,
+    pub active: usize,
+    pub stages: Stages,
+}
+
+impl Side {
+    pub fn new(team: Vec<Pokemon>) -> Self {
+        let active = team.iter().position(|p| !p.is_fainted()).unwrap_or(0);
+        Side { team, active, stages: Stages::default() }
+    }
+
+    pub fn active(&self) -> &Pokemon {
+        &self.team[self.active]
+    }
+
+    pub fn active_mut(&mut self) -> &mut Pokemon {
+        &mut self.team[self.active]
+    }
+
+    pub fn next_usable(&self) -> Option<usize> {
+        (0..self.team.len()).find(|i| *i != self.active && !self.team[*i].is_fainted())
+    }
+}
+
+// The battle engine. It knows nothing about rendering or input: callers choose an action
+// for each side and get back the events that happened during the turn.
+pub struct Battle {
+    pub kind: BattleKind,
+    pub sides: [Side; 2],
+    pub turn: u32,
+    pub outcome: Option<BattleOutcome>,
+    // The weather of the map the battle is fought on.
+    pub weather: Option<Weather>,
+    // Turns left before the weather clears, or None when it lasts the whole battle.
+    pub weather_turns: Option<u32>,
+    flee_attempts: u32,
+}
+
+impl Battle {
+    pub fn wild(player_team: Vec<Pokemon>, wild: Pokemon) -> Self {
+        Battle::new(BattleKind::Wild, player_team, vec![wild])
+    }
+
+    pub fn trainer(player_team: Vec<Pokemon>, opponent_team: Vec<Pokemon>) -> Self {
+        Battle::new(BattleKind::Trainer, player_team, opponent_team)
+    }
+
+    fn new(kind: BattleKind, player_team: Vec<Pokemon>, opponent_team: Vec<Pokemon>) -> Self {
+        Battle {
+            kind,
+            sides: [Side::new(player_team), Side::new(opponent_team)],
+            turn: 0,
+            outcome: None,
+            weather: None,
+            weather_turns: None,
+            flee_attempts: 0,
+        }
+    }
+
+    pub fn with_weather(mut self, weather: Option<Weather>) -> Self {
+        self.weather = weather;
+        self
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.outcome.is_some()
+    }
+
+    pub fn play_turn<R: Rng>(&mut self, library: &Library, actions: [BattleAction; 2], rng: &mut R) -> Vec<BattleEvent> {
+        let mut events = Vec::new();
+        if self.is_over() {
+            return events;
+        }
+
+        self.turn += 1;
+
+        // A side whose pokemon faints loses the rest of its turn, even if a replacement is sent in.
+        let mut cancelled = [false; 2];
+
+        for side in self.turn_order(&actions, rng).iter().cloned() {
+            if self.is_over() {
+                break;
+            }
+
+            if cancelled[side] {
+                continue;
+            }
+
+            match &actions[side] {
+                BattleAction::Run => self.run(side, rng, &mut events),
+                BattleAction::Switch(index) => self.switch(side, *index, library, &mut events),
+                BattleAction::Item { item, target } => self.use_item(side, item, *target, library, &mut events),
+                BattleAction::Throw(item) => self.throw_ball(side, item, library, rng, &mut events),
+                BattleAction::Fight(index) => {
+                    if let Some(fainted) = self.use_move(side, *index, library, rng, &mut events) {
+                        cancelled[fainted] = true;
+                    }
+                }
+                BattleAction::Struggle => {
+                    for fainted in self.struggle(side, library, rng, &mut events) {
+                        cancelled[fainted] = true;
+                    }
+                }
+            }
+        }
+
+        for side in [PLAYER, OPPONENT].iter().cloned() {
+            if !self.is_over() && !cancelled[side] {
+                self.end_of_turn(side, library, &mut events);
+            }
+        }
+
+        if !self.is_over() {
+            self.weather_turn(&cancelled, library, &mut events);
+        }
+
+        events
+    }
+
+    pub fn speed(&self, side: usize) -> f32 {
+        let pokemon = self.sides[side].active();
+        let mut speed = pokemon.stats.speed as f32 * Stages::multiplier(self.sides[side].stages.speed);
+        if pokemon.status == Some(Status::Paralysis) {
+            speed /= 4.0;
+        }
+        speed
+    }
+
+    // Effective attacking and defending stats of a move used by `side` against the other side.
+    fn stats_for(&self, side: usize, category: MoveCategory) -> (f32, f32) {
+        let (attacker, defender) = (&self.sides[side], &self.sides[1 - side]);
+        let (attack, attack_stage, defense, defense_stage) = match category {
+            MoveCategory::Special => (
+                attacker.active().stats.sp_attack, attacker.stages.sp_attack,
+                defender.active().stats.sp_defense, defender.stages.sp_defense,
+            ),
+            _ => (
+                attacker.active().stats.attack, attacker.stages.attack,
+                defender.active().stats.defense, defender.stages.defense,
+            ),
+        };
+
+        let mut attack = attack as f32 * Stages::multiplier(attack_stage);
+        if category == MoveCategory::Physical && attacker.active().status == Some(Status::Burn) {
+            attack /= 2.0;
+        }
+
+        (attack, defense as f32 * Stages::multiplier(defense_stage))
+    }
+
+    // Average damage the move would deal to the opposing active pokemon, weighted by accuracy.
+    pub fn expected_damage(&self, library: &Library, side: usize, move_index: usize) -> f32 {
+        self.expected_damage_to(library, side, move_index, self.sides[1 - side].active)
+    }
+
+    // Same as `expected_damage`, against any member of the opposing team. Stat stages only
+    // count for the pokemon that is on the field.
+    pub fn expected_damage_to(&self, library: &Library, side: usize, move_index: usize, target: usize) -> f32 {
+        let attacker = self.sides[side].active();
+        let mv = match attacker.moves.get(move_index).and_then(|slot| library.get_move(&slot.id)) {
+            Some(mv) => mv,
+            None => return 0.0,
+        };
+
+        let defender = &self.sides[1 - side];
+        let (attack, mut defense) = self.stats_for(side, mv.category);
+        if target != defender.active {
+            let stats = &defender.team[target].stats;
+            defense = if mv.category == MoveCategory::Special { stats.sp_defense } else { stats.defense } as f32;
+        }
+
+        // The random factor averages out to 92.5%.
+        let damage = damage::base_damage(library, attacker, &defender.team[target], mv, attack, defense, self.weather) * 0.925;
+        let accuracy = mv.accuracy.map(|a| a as f32 / 100.0 * self.accuracy_multiplier()).unwrap_or(1.0);
+
+        damage * accuracy
+    }
+
+    // Whether none of the active pokemon's moves have PP left.
+    pub fn must_struggle(&self, side: usize) -> bool {
+        self.sides[side].active().moves.iter().all(|slot| slot.pp == 0)
+    }
+
+    // Chances to hit are multiplied by this, e.g. in fog.
+    pub fn accuracy_multiplier(&self) -> f32 {
+        self.weather.map(|w| w.accuracy_multiplier()).unwrap_or(1.0)
+    }
+
+    pub fn can_inflict(&self, library: &Library, side: usize, status: Status) -> bool {
+        let target = self.sides[side].active();
+        if target.status.is_some() || target.is_fainted() {
+            return false;
+        }
+
+        let types = library.get_species(&target.species).map(|s| s.types.clone()).unwrap_or_default();
+        match status {
+            Status::Poison => !types.contains(&PokemonType::Poison) && !types.contains(&PokemonType::Steel),
+            Status::Burn => !types.contains(&PokemonType::Fire),
+            _ => true,
+        }
+    }
+
+    fn turn_order<R: Rng>(&self, actions: &[BattleAction; 2], rng: &mut R) -> [usize; 2] {
+        let (p0, p1) = (actions[PLAYER].priority(), actions[OPPONENT].priority());
+        let (s0, s1) = (self.speed(PLAYER), self.speed(OPPONENT));
+
+        let player_first = if p0 != p1 {
+            p0 > p1
+        } else if s0 != s1 {
+            s0 > s1
+        } else {
+            rng.gen_bool(0.5)
+        };
+
+        if player_first { [PLAYER, OPPONENT] } else { [OPPONENT, PLAYER] }
+    }
+
+    fn run<R: Rng>(&mut self, side: usize, rng: &mut R, events: &mut Vec<BattleEvent>) {
+        if self.kind == BattleKind::Trainer || side != PLAYER {
+            events.push(BattleEvent::NoRunning);
+            return;
+        }
+
+        self.flee_attempts += 1;
+
+        let speed = self.sides[PLAYER].active().stats.speed as u32;
+        let foe_speed = self.sides[OPPONENT].active().stats.speed.max(1) as u32;
+        let odds = (speed * 128 / foe_speed + 30 * self.flee_attempts) % 256;
+
+        if speed >= foe_speed || rng.gen_range(0, 256) < odds {
+            events.push(BattleEvent::Fled);
+            self.end(BattleOutcome::Fled, events);
+        } else {
+            events.push(BattleEvent::FleeFailed);
+        }
+    }
+
+    fn switch(&mut self, side: usize, index: usize, library: &Library, events: &mut Vec<BattleEvent>) {
+        let valid = self.sides[side].team.get(index)
+            .map(|p| !p.is_fainted())
+            .unwrap_or(false);
+
+        if valid && index != self.sides[side].active {
+            self.sides[side].active = index;
+            self.sides[side].stages = Stages::default();
+            events.push(BattleEvent::SwitchedIn {
+                side,
+                name: self.sides[side].active().name(library),
+                species: self.sides[side].active().species.clone(),
+            });
+        }
+    }
+
+    fn use_item(&mut self, side: usize, id: &str, target: usize, library: &Library, events: &mut Vec<BattleEvent>) {
+        let item = match library.get_item(id) {
+            Some(item) => item,
+            None => return,
+        };
+
+        events.push(BattleEvent::ItemUsed { side, item: item.name.clone() });
+
+        let pokemon = match self.sides[side].team.get_mut(target) {
+            Some(pokemon) => pokemon,
+            None => return,
+        };
+
+        let name = pokemon.name(library);
+        events.push(match item::use_on(item, pokemon, library) {
+            Ok(ItemOutcome::Healed(_)) => BattleEvent::Healed { side, name },
+            Ok(ItemOutcome::Cured) => BattleEvent::StatusCured { side, name },
+            Ok(ItemOutcome::Revived) => BattleEvent::Revived { side, name },
+            Ok(ItemOutcome::PpRestored) => BattleEvent::PpRestored { side, name },
+            // Not usable in battle.
+            Ok(ItemOutcome::Learned(_)) | Ok(ItemOutcome::LevelUp { .. }) | Ok(ItemOutcome::Evolves(_)) | Err(_) => {
+                BattleEvent::NoEffect
+            }
+        });
+    }
+
+    fn throw_ball<R: Rng>(&mut self, side: usize, id: &str, library: &Library, rng: &mut R, events: &mut Vec<BattleEvent>) {
+        let ball = match library.get_item(id).and_then(|item| item.effect.map(|e| (item, e))) {
+            Some((item, ItemEffect::Ball(modifier))) => {
+                events.push(BattleEvent::BallThrown { item: item.name.clone() });
+                modifier
+            }
+            _ => return,
+        };
+
+        if self.kind == BattleKind::Trainer || side != PLAYER {
+            events.push(BattleEvent::BallBlocked);
+            return;
+        }
+
+        let target = self.sides[OPPONENT].active();
+        let result = catch::attempt(library, target, ball, rng);
+        events.push(BattleEvent::BallShook(result.shakes.min(catch::SHAKES - 1)));
+
+        if result.caught {
+            events.push(BattleEvent::Caught { name: target.name(library), species: target.species.clone() });
+            self.end(BattleOutcome::Caught, events);
+        } else {
+            events.push(BattleEvent::BrokeFree { shakes: result.shakes });
+        }
+    }
+
+    // Returns false if the pokemon cannot act this turn because of its status.
+    fn check_status<R: Rng>(&mut self, side: usize, library: &Library, rng: &mut R, events: &mut Vec<BattleEvent>) -> bool {
+        let name = self.sides[side].active().name(library);
+        let pokemon = self.sides[side].active_mut();
+
+        match pokemon.status {
+            Some(Status::Sleep) => {
+                if pokemon.sleep_turns == 0 {
+                    pokemon.status = None;
+                    events.push(BattleEvent::WokeUp { side, name });
+                    true
+                } else {
+                    pokemon.sleep_turns -= 1;
+                    events.push(BattleEvent::FastAsleep { side, name });
+                    false
+                }
+            }
+            Some(Status::Paralysis) if rng.gen_range(0, 4) == 0 => {
+                events.push(BattleEvent::FullyParalyzed { side, name });
+                false
+            }
+            _ => true,
+        }
+    }
+
+    // Returns the side whose pokemon fainted, if any.
+    fn use_move<R: Rng>(&mut self, side: usize, index: usize, library: &Library, rng: &mut R,
+                        events: &mut Vec<BattleEvent>) -> Option<usize> {
+        let slot = self.sides[side].active().moves.get(index)?.clone();
+        let mv = library.get_move(&slot.id)?;
+
+        if slot.pp == 0 {
+            events.push(BattleEvent::NoPp { side });
+            return None;
+        }
+
+        if !self.check_status(side, library, rng, events) {
+            return None;
+        }
+
+        self.sides[side].active_mut().moves[index].pp -= 1;
+        self.hit(side, mv, library, rng, events)
+    }
+
+    // Struggling never runs out, but the user takes a quarter of its max HP as recoil.
+    // Returns the sides whose pokemon fainted.
+    fn struggle<R: Rng>(&mut self, side: usize, library: &Library, rng: &mut R,
+                        events: &mut Vec<BattleEvent>) -> Vec<usize> {
+        let mv = match library.get_move(STRUGGLE) {
+            Some(mv) => mv,
+            None => return Vec::new(),
+        };
+
+        if !self.check_status(side, library, rng, events) {
+            return Vec::new();
+        }
+
+        let mut fainted: Vec<usize> = self.hit(side, mv, library, rng, events).into_iter().collect();
+        if self.is_over() || self.sides[side].active().is_fainted() {
+            return fainted;
+        }
+
+        let name = self.sides[side].active().name(library);
+        let amount = (self.sides[side].active().stats.hp / 4).max(1);
+        self.sides[side].active_mut().take_damage(amount);
+        events.push(BattleEvent::Recoil { side, name });
+        events.push(BattleEvent::Damaged { side, amount });
+
+        if self.sides[side].active().is_fainted() {
+            self.faint(side, library, events);
+            fainted.push(side);
+        }
+
+        fainted
+    }
+
+    // Carries out a move once it is known the user can act. Returns the side whose pokemon
+    // fainted, if any.
+    fn hit<R: Rng>(&mut self, side: usize, mv: &Move, library: &Library, rng: &mut R,
+                   events: &mut Vec<BattleEvent>) -> Option<usize> {
+        let target = 1 - side;
+
+        let name = self.sides[side].active().name(library);
+        events.push(BattleEvent::MoveUsed { side, name: name.clone(), move_id: mv.id.clone() });
+
+        if let Some(accuracy) = mv.accuracy {
+            if rng.gen_range(0, 100) as f32 >= accuracy as f32 * self.accuracy_multiplier() {
+                events.push(BattleEvent::Missed { side, name });
+                return None;
+            }
+        }
+
+        if mv.category == MoveCategory::Status {
+            if !self.apply_effect(side, mv, library, rng, events) {
+                events.push(BattleEvent::Failed);
+            }
+            return None;
+        }
+
+        let stats = self.stats_for(side, mv.category);
+        let damage = damage::calculate(library, self.sides[side].active(), self.sides[target].active(),
+                                       mv, stats, self.weather, rng);
+        if damage.effectiveness == 0.0 {
+            events.push(BattleEvent::Effectiveness(0.0));
+            return None;
+        }
+
+        self.sides[target].active_mut().take_damage(damage.amount);
+        events.push(BattleEvent::Damaged { side: target, amount: damage.amount });
+
+        if damage.critical {
+            events.push(BattleEvent::Critical);
+        }
+
+        if damage.effectiveness != 1.0 {
+            events.push(BattleEvent::Effectiveness(damage.effectiveness));
+        }
+
+        if self.sides[target].active().is_fainted() {
+            self.faint(target, library, events);
+            return Some(target);
+        }
+
+        self.apply_effect(side, mv, library, rng, events);
+        None
+    }
+
+    // Applies the secondary effect of a move. Returns false if it had nothing to do.
+    fn apply_effect<R: Rng>(&mut self, side: usize, mv: &Move, library: &Library, rng: &mut R,
+                            events: &mut Vec<BattleEvent>) -> bool {
+        match mv.effect {
+            Some(MoveEffect::Stat { stat, stages, on_user }) => {
+                let affected = if on_user { side } else { 1 - side };
+                let changed = self.sides[affected].stages.apply(stat, stages);
+                if changed == 0 {
+                    return false;
+                }
+
+                events.push(BattleEvent::StatChanged {
+                    side: affected,
+                    name: self.sides[affected].active().name(library),
+                    stat,
+                    stages: changed,
+                });
+                true
+            }
+            Some(MoveEffect::Inflict { status, chance }) => {
+                let target = 1 - side;
+                if !self.can_inflict(library, target, status) || rng.gen_range(0, 100) >= chance as u32 {
+                    return false;
+                }
+
+                let pokemon = self.sides[target].active_mut();
+                pokemon.status = Some(status);
+                if status == Status::Sleep {
+                    pokemon.sleep_turns = rng.gen_range(1, 4);
+                }
+
+                events.push(BattleEvent::StatusInflicted {
+                    side: target,
+                    name: self.sides[target].active().name(library),
+                    status,
+                });
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn end_of_turn(&mut self, side: usize, library: &Library, events: &mut Vec<BattleEvent>) {
+        let pokemon = self.sides[side].active();
+        let status = match pokemon.status {
+            Some(status) if status == Status::Poison || status == Status::Burn => status,
+            _ => return,
+        };
+
+        if pokemon.is_fainted() {
+            return;
+        }
+
+        let name = pokemon.name(library);
+        let amount = (pokemon.stats.hp / 8).max(1);
+        self.sides[side].active_mut().take_damage(amount);
+        events.push(BattleEvent::StatusDamage { side, name, status });
+        events.push(BattleEvent::Damaged { side, amount });
+
+        if self.sides[side].active().is_fainted() {
+            self.faint(side, library, events);
+        }
+    }
+
+    // Counts the weather down and clears it when it runs out, or hurts the active pokemon.
+    fn weather_turn(&mut self, cancelled: &[bool; 2], library: &Library, events: &mut Vec<BattleEvent>) {
+        let weather = match self.weather {
+            Some(weather) => weather,
+            None => return,
+        };
+
+        if let Some(turns) = self.weather_turns {
+            if turns <= 1 {
+                self.weather = None;
+                self.weather_turns = None;
+                events.push(BattleEvent::WeatherEnded(weather));
+                return;
+            }
+            self.weather_turns = Some(turns - 1);
+        }
+
+        if self.turn % WEATHER_REMINDER_TURNS == 0 {
+            events.push(BattleEvent::WeatherContinues(weather));
+        }
+
+        for side in [PLAYER, OPPONENT].iter().cloned() {
+            if !self.is_over() && !cancelled[side] {
+                self.weather_damage(side, weather, library, events);
+            }
+        }
+    }
+
+    // Sandstorms and snow take 1/16 of the max HP of every pokemon they hurt.
+    fn weather_damage(&mut self, side: usize, weather: Weather, library: &Library, events: &mut Vec<BattleEvent>) {
+        let pokemon = self.sides[side].active();
+        let types = library.get_species(&pokemon.species).map(|s| s.types.clone()).unwrap_or_default();
+        if pokemon.is_fainted() || !weather.hurts(&types) {
+            return;
+        }
+
+        let name = pokemon.name(library);
+        let amount = (pokemon.stats.hp / 16).max(1);
+        self.sides[side].active_mut().take_damage(amount);
+        events.push(BattleEvent::WeatherDamage { side, name, weather });
+        events.push(BattleEvent::Damaged { side, amount });
+
+        if self.sides[side].active().is_fainted() {
+            self.faint(side, library, events);
+        }
+    }
+
+    fn faint(&mut self, side: usize, library: &Library, events: &mut Vec<BattleEvent>) {
+        events.push(BattleEvent::Fainted { side, name: self.sides[side].active().name(library) });
+
+        match self.sides[side].next_usable() {
+            Some(index) => self.switch(side, index, library, events),
+            None => {
+                let outcome = if side == PLAYER { BattleOutcome::Lost } else { BattleOutcome::Won };
+                self.end(outcome, events);
+            }
+        }
+    }
+
+    fn end(&mut self, outcome: BattleOutcome, events: &mut Vec<BattleEvent>) {
+        self.outcome = Some(outcome);
+        events.push(BattleEvent::Ended(outcome));
+    }
+
+    fn prefix(&self, side: usize) -> &'static str {
+        match (side, self.kind) {
+            (PLAYER, _) => "",
+            (_, BattleKind::Wild) => "Wild ",
+            (_, BattleKind::Trainer) => "Foe ",
+        }
+    }
+
+    fn stat_name(stat: BattleStat) -> &'static str {
+        match stat {
+            BattleStat::Attack => "ATTACK",
+            BattleStat::Defense => "DEFENSE",
+            BattleStat::SpAttack => "SP. ATK",
+            BattleStat::SpDefense => "SP. DEF",
+            BattleStat::Speed => "SPEED",
+        }
+    }
+
+    // Text shown in the battle dialog for an event, if the event has any.
+    pub fn describe(&self, event: &BattleEvent, library: &Library) -> Option<String> {
+        match event {
+            BattleEvent::SwitchedIn { side, name, .. } => Some(if *side == PLAYER {
+                format!("Go! {}!", name)
+            } else {
+                format!("Foe sent out {}!", name)
+            }),
+            BattleEvent::MoveUsed { side, name, move_id } => {
+                let move_name = library.get_move(move_id).map(|m| m.name.clone()).unwrap_or_else(|| move_id.clone());
+                Some(format!("{}{} used {}!", self.prefix(*side), name, move_name))
+            }
+            BattleEvent::NoPp { .. } => Some("There's no PP left for this move!".to_string()),
+            BattleEvent::Missed { side, name } => Some(format!("{}{}'s attack missed!", self.prefix(*side), name)),
+            BattleEvent::Damaged { .. } => None,
+            BattleEvent::Recoil { side, name } => Some(format!("{}{} is hit with recoil!", self.prefix(*side), name)),
+            BattleEvent::Critical => Some("A critical hit!".to_string()),
+            BattleEvent::Effectiveness(e) => Some(if *e == 0.0 {
+                "It doesn't affect the target...".to_string()
+            } else if *e > 1.0 {
+                "It's super effective!".to_string()
+            } else {
+                "It's not very effective...".to_string()
+            }),
+            BattleEvent::StatChanged { side, name, stat, stages } => {
+                let change = match *stages {
+                    s if s >= 2 => "sharply rose",
+                    s if s > 0 => "rose",
+                    s if s <= -2 => "harshly fell",
+                    _ => "fell",
+                };
+                Some(format!("{}{}'s {} {}!", self.prefix(*side), name, Battle::stat_name(*stat), change))
+            }
+            BattleEvent::StatusInflicted { side, name, status } => {
+                let text = match status {
+                    Status::Poison => "was poisoned",
+                    Status::Burn => "was burned",
+                    Status::Paralysis => "is paralyzed! It may be unable to move",
+                    Status::Sleep => "fell asleep",
+                };
+                Some(format!("{}{} {}!", self.prefix(*side), name, text))
+            }
+            BattleEvent::StatusDamage { side, name, status } => {
+                let text = if *status == Status::Burn { "is hurt by its burn" } else { "is hurt by poison" };
+                Some(format!("{}{} {}!", self.prefix(*side), name, text))
+            }
+            BattleEvent::WeatherContinues(weather) => Some(weather.continue_message().to_string()),
+            BattleEvent::WeatherEnded(weather) => Some(weather.end_message().to_string()),
+            BattleEvent::WeatherDamage { side, name, weather } => {
+                let text = if *weather == Weather::Snow { "is pelted by the snow" } else { "is buffeted by the sandstorm" };
+                Some(format!("{}{} {}!", self.prefix(*side), name, text))
+            }
+            BattleEvent::FullyParalyzed { side, name } => {
+                Some(format!("{}{} is paralyzed! It can't move!", self.prefix(*side), name))
+            }
+            BattleEvent::FastAsleep { side, name } => Some(format!("{}{} is fast asleep.", self.prefix(*side), name)),
+            BattleEvent::WokeUp { side, name } => Some(format!("{}{} woke up!", self.prefix(*side), name)),
+            BattleEvent::Failed => Some("But it failed!".to_string()),
+            BattleEvent::ItemUsed { side, item } => Some(if *side == PLAYER {
+                format!("You used {}!", item)
+            } else {
+                format!("The foe used {}!", item)
+            }),
+            BattleEvent::Healed { side, name } => Some(format!("{}{}'s HP was restored.", self.prefix(*side), name)),
+            BattleEvent::StatusCured { side, name } => Some(format!("{}{} was cured!", self.prefix(*side), name)),
+            BattleEvent::Revived { side, name } => Some(format!("{}{} was revived!", self.prefix(*side), name)),
+            BattleEvent::PpRestored { side, name } => Some(format!("{}{}'s PP was restored.", self.prefix(*side), name)),
+            BattleEvent::NoEffect => Some("It won't have any effect.".to_string()),
+            BattleEvent::BallThrown { item } => Some(format!("You threw a {}!", item)),
+            BattleEvent::BallShook(_) => None,
+            BattleEvent::Caught { name, .. } => Some(format!("Gotcha!\n{} was caught!", name)),
+            BattleEvent::BrokeFree { shakes } => Some(match shakes {
+                0 => "Oh, no!\nThe POKEMON broke free!",
+                1 => "Aww!\nIt appeared to be caught!",
+                2 => "Aargh!\nAlmost had it!",
+                _ => "Shoot!\nIt was so close, too!",
+            }.to_string()),
+            BattleEvent::BallBlocked => Some("The trainer blocked the BALL!\nDon't be a thief!".to_string()),
+            BattleEvent::Fainted { side, name } => Some(format!("{}{} fainted!", self.prefix(*side), name)),
+            BattleEvent::Fled => Some("Got away safely!".to_string()),
+            BattleEvent::FleeFailed => Some("Can't escape!".to_string()),
+            BattleEvent::NoRunning => Some("No! There's no running from a trainer battle!".to_string()),
+            BattleEvent::Ended(BattleOutcome::Lost) => Some("You are out of usable Pokemon!".to_string()),
+            BattleEvent::Ended(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::battle::fixtures::pokemon;
+
+    // Two pokemon that only lower each other's stats, so any damage comes from the weather.
+    fn battle(library: &Library, weather: Weather) -> Battle {
+        Battle::trainer(
+            vec![pokemon(library, "charmander", 12, &["growl"])],
+            vec![pokemon(library, "squirtle", 12, &["tail-whip"])],
+        ).with_weather(Some(weather))
+    }
+
+    fn turn(battle: &mut Battle, library: &Library, rng: &mut StdRng) -> Vec<BattleEvent> {
+        battle.play_turn(library, [BattleAction::Fight(0), BattleAction::Fight(0)], rng)
+    }
+
+    #[test]
+    fn sandstorms_and_snow_take_a_sixteenth_of_max_hp() {
+        let library = Library::bundled();
+        let mut rng = StdRng::seed_from_u64(3);
+        for weather in [Weather::Sandstorm, Weather::Snow].iter() {
+            let mut battle = battle(&library, *weather);
+            let events = turn(&mut battle, &library, &mut rng);
+
+            for side in [PLAYER, OPPONENT].iter().cloned() {
+                let pokemon = battle.sides[side].active();
+                let amount = pokemon.stats.hp / 16;
+                assert_eq!(pokemon.hp, pokemon.stats.hp - amount, "{:?}", weather);
+                assert!(events.contains(&BattleEvent::Damaged { side, amount }), "{:?}", weather);
+            }
+        }
+    }
+
+    #[test]
+    fn rain_does_no_damage() {
+        let library = Library::bundled();
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut battle = battle(&library, Weather::Rain);
+        let events = turn(&mut battle, &library, &mut rng);
+
+        assert!(!events.iter().any(|e| matches!(e, BattleEvent::WeatherDamage { .. } | BattleEvent::Damaged { .. })));
+    }
+
+    #[test]
+    fn weather_ends_after_its_turns() {
+        let library = Library::bundled();
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut battle = battle(&library, Weather::Sandstorm);
+        battle.weather_turns = Some(3);
+
+        for _ in 0..2 {
+            let events = turn(&mut battle, &library, &mut rng);
+            assert!(events.iter().any(|e| matches!(e, BattleEvent::WeatherDamage { .. })));
+        }
+
+        let events = turn(&mut battle, &library, &mut rng);
+        assert!(events.contains(&BattleEvent::WeatherEnded(Weather::Sandstorm)));
+        assert!(!events.iter().any(|e| matches!(e, BattleEvent::WeatherDamage { .. })));
+        assert_eq!(battle.weather, None);
+
+        let events = turn(&mut battle, &library, &mut rng);
+        assert!(!events.iter().any(|e| matches!(e, BattleEvent::WeatherDamage { .. } | BattleEvent::WeatherEnded(_))));
+    }
+
+    #[test]
+    fn lasting_weather_is_only_mentioned_every_few_turns() {
+        let library = Library::bundled();
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut battle = battle(&library, Weather::Rain);
+
+        let mut mentioned = Vec::new();
+        for _ in 0..2 * WEATHER_REMINDER_TURNS {
+            if turn(&mut battle, &library, &mut rng).contains(&BattleEvent::WeatherContinues(Weather::Rain)) {
+                mentioned.push(battle.turn);
+            }
+        }
+        assert_eq!(mentioned, vec![WEATHER_REMINDER_TURNS, 2 * WEATHER_REMINDER_TURNS]);
+        assert_eq!(battle.weather, Some(Weather::Rain));
+    }
+}
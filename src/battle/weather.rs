@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pokemon::types::PokemonType;
+
+// Weather of a map. Battles fought on the map carry it over as a field condition.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Weather {
+    Rain,
+    Snow,
+    Sandstorm,
+    Fog,
+    HarshSun,
+}
+
+impl Weather {
+    // Rain powers up water moves and weakens fire moves, harsh sun the other way around.
+    pub fn power_multiplier(&self, kind: PokemonType) -> f32 {
+        match (self, kind) {
+            (Weather::Rain, PokemonType::Water) | (Weather::HarshSun, PokemonType::Fire) => 1.5,
+            (Weather::Rain, PokemonType::Fire) | (Weather::HarshSun, PokemonType::Water) => 0.5,
+            _ => 1.0,
+        }
+    }
+
+    // Fog makes every move that can miss miss more often.
+    pub fn accuracy_multiplier(&self) -> f32 {
+        match self {
+            Weather::Fog => 0.6,
+            _ => 1.0,
+        }
+    }
+
+    // Whether a pokemon of the given types is hurt at the end of every turn. Rock, ground and
+    // steel types shrug off sandstorms, and ice types snow.
+    pub fn hurts(&self, types: &[PokemonType]) -> bool {
+        match self {
+            Weather::Sandstorm => !types.iter().any(|t| {
+                *t == PokemonType::Rock || *t == PokemonType::Ground || *t == PokemonType::Steel
+            }),
+            Weather::Snow => !types.contains(&PokemonType::Ice),
+            _ => false,
+        }
+    }
+
+    pub fn start_message(&self) -> &'static str {
+        match self {
+            Weather::Rain => "It is raining!",
+            Weather::Snow => "It is snowing!",
+            Weather::Sandstorm => "A sandstorm is raging!",
+            Weather::Fog => "The fog is deep...",
+            Weather::HarshSun => "The sunlight is harsh!",
+        }
+    }
+
+    pub fn continue_message(&self) -> &'static str {
+        match self {
+            Weather::Rain => "Rain continues to fall.",
+            Weather::Snow => "Snow continues to fall.",
+            Weather::Sandstorm => "The sandstorm rages.",
+            Weather::Fog => "The fog is deep...",
+            Weather::HarshSun => "The sunlight is strong.",
+        }
+    }
+
+    pub fn end_message(&self) -> &'static str {
+        match self {
+            Weather::Rain => "The rain stopped.",
+            Weather::Snow => "The snow stopped.",
+            Weather::Sandstorm => "The sandstorm subsided.",
+            Weather::Fog => "The fog lifted.",
+            Weather::HarshSun => "The sunlight faded.",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rain_and_sun_power_up_and_weaken_water_and_fire() {
+        assert_eq!(Weather::Rain.power_multiplier(PokemonType::Water), 1.5);
+        assert_eq!(Weather::Rain.power_multiplier(PokemonType::Fire), 0.5);
+        assert_eq!(Weather::HarshSun.power_multiplier(PokemonType::Fire), 1.5);
+        assert_eq!(Weather::HarshSun.power_multiplier(PokemonType::Water), 0.5);
+        assert_eq!(Weather::Rain.power_multiplier(PokemonType::Grass), 1.0);
+        assert_eq!(Weather::Sandstorm.power_multiplier(PokemonType::Water), 1.0);
+        assert_eq!(Weather::Fog.power_multiplier(PokemonType::Fire), 1.0);
+    }
+
+    #[test]
+    fn only_fog_lowers_accuracy() {
+        assert_eq!(Weather::Fog.accuracy_multiplier(), 0.6);
+        for weather in [Weather::Rain, Weather::Snow, Weather::Sandstorm, Weather::HarshSun].iter() {
+            assert_eq!(weather.accuracy_multiplier(), 1.0, "{:?}", weather);
+        }
+    }
+
+    #[test]
+    fn sandstorms_spare_rock_ground_and_steel_types() {
+        assert!(Weather::Sandstorm.hurts(&[PokemonType::Normal]));
+        assert!(Weather::Sandstorm.hurts(&[PokemonType::Grass, PokemonType::Poison]));
+        assert!(!Weather::Sandstorm.hurts(&[PokemonType::Rock]));
+        assert!(!Weather::Sandstorm.hurts(&[PokemonType::Ground]));
+        assert!(!Weather::Sandstorm.hurts(&[PokemonType::Bug, PokemonType::Steel]));
+    }
+
+    #[test]
+    fn snow_spares_ice_types() {
+        assert!(Weather::Snow.hurts(&[PokemonType::Fire]));
+        assert!(Weather::Snow.hurts(&[PokemonType::Rock]));
+        assert!(!Weather::Snow.hurts(&[PokemonType::Ice]));
+        assert!(!Weather::Snow.hurts(&[PokemonType::Water, PokemonType::Ice]));
+    }
+
+    #[test]
+    fn other_weather_never_hurts() {
+        for weather in [Weather::Rain, Weather::Fog, Weather::HarshSun].iter() {
+            assert!(!weather.hurts(&[PokemonType::Normal]), "{:?}", weather);
+        }
+    }
+}
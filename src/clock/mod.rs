@@ -0,0 +1,232 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{data, resolve};
+
+pub const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum TimeOfDay {
+    Morning,
+    #[default]
+    Day,
+    Evening,
+    Night,
+}
+
+impl TimeOfDay {
+    // The period a time falls in, in seconds since midnight. Morning runs from 4:00 to 10:00,
+    // day to 17:00, evening to 20:00 and night until 4:00. Each period includes its start.
+    pub fn at(seconds: u32) -> TimeOfDay {
+        match (seconds % SECONDS_PER_DAY) / 3600 {
+            4..=9 => TimeOfDay::Morning,
+            10..=16 => TimeOfDay::Day,
+            17..=19 => TimeOfDay::Evening,
+            _ => TimeOfDay::Night,
+        }
+    }
+
+    // Whether the time is one of `times`. No times at all means at any time, which is how
+    // encounter slots and npc placements leave them out.
+    pub fn is_any_of(&self, times: &[TimeOfDay]) -> bool {
+        times.is_empty() || times.contains(self)
+    }
+
+    // The colour laid over the overworld, alpha included.
+    pub fn tint(&self) -> [f32; 4] {
+        match self {
+            TimeOfDay::Morning => [1.0, 0.85, 0.6, 0.12],
+            TimeOfDay::Day => [0.0, 0.0, 0.0, 0.0],
+            TimeOfDay::Evening => [1.0, 0.45, 0.15, 0.2],
+            TimeOfDay::Night => [0.05, 0.05, 0.3, 0.45],
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ClockMode {
+    // Follows the computer's clock, shifted from UTC by `utc_offset` hours.
+    RealTime { utc_offset: i32 },
+    // Runs `scale` in-game seconds for every real second, from the time of the save.
+    Accelerated { scale: f32 },
+}
+
+// Loaded from config/clock.ron.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockConfig {
+    pub mode: ClockMode,
+    // The time a new game starts at on an accelerated clock, as (hour, minute).
+    pub start: (u32, u32),
+    // Debug override: holds the clock at the given (hour, minute), for trying out periods.
+    #[serde(default)]
+    pub debug_time: Option<(u32, u32)>,
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        ClockConfig {
+            mode: ClockMode::Accelerated { scale: 60.0 },
+            start: (10, 0),
+            debug_time: None,
+        }
+    }
+}
+
+fn to_seconds((hour, minute): (u32, u32)) -> u32 {
+    (hour * 3600 + minute * 60) % SECONDS_PER_DAY
+}
+
+// The in-game time of day. Encounter tables, npc schedules and the overworld tint go by the
+// period it is in.
+#[derive(Debug, Clone)]
+pub struct Clock {
+    mode: ClockMode,
+    // Seconds since midnight on the accelerated clock.
+    elapsed: f64,
+    // Seconds since midnight that the clock is held at, if any.
+    fixed: Option<u32>,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Clock::new(&ClockConfig::default())
+    }
+}
+
+impl Clock {
+    pub fn new(config: &ClockConfig) -> Self {
+        Clock {
+            mode: config.mode,
+            elapsed: to_seconds(config.start) as f64,
+            fixed: config.debug_time.map(to_seconds),
+        }
+    }
+
+    // Falls back to the default clock when the config can't be read.
+    pub fn load() -> Clock {
+        let path = resolve::get_config_path("clock.ron");
+        let config = data::load_ron(&path).unwrap_or_else(|e| {
+            eprintln!("failed to load {}: {}", path.display(), e);
+            ClockConfig::default()
+        });
+        Clock::new(&config)
+    }
+
+    pub fn advance(&mut self, delta: f32) {
+        if let ClockMode::Accelerated { scale } = self.mode {
+            self.elapsed = (self.elapsed + (delta * scale) as f64) % SECONDS_PER_DAY as f64;
+        }
+    }
+
+    // Carries on from a saved time. Real-time clocks ignore it.
+    pub fn resume(&mut self, seconds: u32) {
+        self.elapsed = (seconds % SECONDS_PER_DAY) as f64;
+    }
+
+    // Seconds since midnight.
+    pub fn seconds(&self) -> u32 {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        self.seconds_at(now)
+    }
+
+    // Seconds since midnight when the computer's clock reads `now`, in seconds since the Unix
+    // epoch.
+    fn seconds_at(&self, now: u64) -> u32 {
+        if let Some(fixed) = self.fixed {
+            return fixed;
+        }
+
+        match self.mode {
+            ClockMode::RealTime { utc_offset } => {
+                (now as i64 + utc_offset as i64 * 3600).rem_euclid(SECONDS_PER_DAY as i64) as u32
+            }
+            ClockMode::Accelerated { .. } => self.elapsed as u32,
+        }
+    }
+
+    pub fn time_of_day(&self) -> TimeOfDay {
+        TimeOfDay::at(self.seconds())
+    }
+
+    pub fn is_any_of(&self, times: &[TimeOfDay]) -> bool {
+        self.time_of_day().is_any_of(times)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The last minute of each period and the first minute of the next.
+    const BOUNDARIES: [((u32, u32), TimeOfDay, TimeOfDay); 4] = [
+        ((3, 59), TimeOfDay::Night, TimeOfDay::Morning),
+        ((9, 59), TimeOfDay::Morning, TimeOfDay::Day),
+        ((16, 59), TimeOfDay::Day, TimeOfDay::Evening),
+        ((19, 59), TimeOfDay::Evening, TimeOfDay::Night),
+    ];
+
+    // A day well after the epoch, at midnight UTC.
+    const MIDNIGHT_UTC: u64 = 20_000 * SECONDS_PER_DAY as u64;
+
+    fn config(mode: ClockMode, start: (u32, u32)) -> ClockConfig {
+        ClockConfig {
+            mode,
+            start,
+            debug_time: None,
+        }
+    }
+
+    #[test]
+    fn periods_change_on_the_hour() {
+        for (last_minute, before, after) in &BOUNDARIES {
+            let seconds = to_seconds(*last_minute);
+            assert_eq!(TimeOfDay::at(seconds + 59), *before);
+            assert_eq!(TimeOfDay::at(seconds + 60), *after);
+        }
+    }
+
+    #[test]
+    fn an_accelerated_clock_crosses_each_boundary() {
+        for (last_minute, before, after) in &BOUNDARIES {
+            let mut clock = Clock::new(&config(ClockMode::Accelerated { scale: 60.0 }, *last_minute));
+            assert_eq!(clock.time_of_day(), *before, "at {:?}", last_minute);
+
+            // A real second is an in-game minute.
+            clock.advance(1.0);
+            assert_eq!(clock.time_of_day(), *after, "a minute after {:?}", last_minute);
+        }
+    }
+
+    #[test]
+    fn a_real_time_clock_crosses_each_boundary() {
+        for utc_offset in &[0, 2, -5] {
+            let clock = Clock::new(&config(ClockMode::RealTime { utc_offset: *utc_offset }, (0, 0)));
+
+            for (last_minute, before, after) in &BOUNDARIES {
+                // The computer's clock in UTC when the local time is `last_minute`.
+                let local = MIDNIGHT_UTC + to_seconds(*last_minute) as u64;
+                let now = (local as i64 - *utc_offset as i64 * 3600) as u64;
+
+                assert_eq!(TimeOfDay::at(clock.seconds_at(now + 59)), *before, "at {:?} UTC{:+}", last_minute, utc_offset);
+                assert_eq!(TimeOfDay::at(clock.seconds_at(now + 60)), *after, "after {:?} UTC{:+}", last_minute, utc_offset);
+            }
+        }
+    }
+
+    #[test]
+    fn debug_time_holds_the_clock() {
+        let modes = [ClockMode::Accelerated { scale: 60.0 }, ClockMode::RealTime { utc_offset: 0 }];
+        for mode in &modes {
+            let mut clock = Clock::new(&ClockConfig {
+                debug_time: Some((21, 0)),
+                ..config(*mode, (10, 0))
+            });
+
+            assert_eq!(clock.seconds_at(MIDNIGHT_UTC + 12 * 3600), to_seconds((21, 0)));
+            clock.advance(600.0);
+            clock.resume(to_seconds((12, 0)));
+            assert_eq!(clock.time_of_day(), TimeOfDay::Night);
+        }
+    }
+}
@@ -1,6 +1,13 @@
 pub mod player;
+pub mod npc;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+use amethyst::{
+    core::math::Vector2,
+    ecs::Entity,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum ActorDirection {
     North,
     South,
@@ -9,6 +16,29 @@ pub enum ActorDirection {
     None
 }
 
+impl ActorDirection {
+    // Grid offset of a single step in this direction. North is +y.
+    pub fn to_delta(self) -> (i32, i32) {
+        match self {
+            ActorDirection::North => (0, 1),
+            ActorDirection::South => (0, -1),
+            ActorDirection::East => (1, 0),
+            ActorDirection::West => (-1, 0),
+            ActorDirection::None => (0, 0)
+        }
+    }
+
+    pub fn opposite(&self) -> ActorDirection {
+        match self {
+            ActorDirection::North => ActorDirection::South,
+            ActorDirection::South => ActorDirection::North,
+            ActorDirection::East => ActorDirection::West,
+            ActorDirection::West => ActorDirection::East,
+            ActorDirection::None => ActorDirection::None
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum ActorAction {
     Stand,
@@ -30,7 +60,7 @@ impl ActorAttrs {
         }
     }
 
-    pub fn to_sprite_index(&self) -> usize {
+    pub fn to_sprite_index(self) -> usize {
         if self.action == ActorAction::Stand ||
             self.action == ActorAction::Walk1 ||
             self.action == ActorAction::Walk2 {
@@ -50,4 +80,19 @@ impl ActorAttrs {
             0
         }
     }
-}
\ No newline at end of file
+}
+
+// Grid cells are this many pixels wide on screen.
+pub const CELL_SIZE: f32 = 16.0;
+
+// Where an actor standing on a cell is drawn. Cell (0, 0) is at the origin of the world.
+pub fn world_position(grid_pos: Vector2<i32>) -> (f32, f32) {
+    (CELL_SIZE * grid_pos[0] as f32, CELL_SIZE * grid_pos[1] as f32)
+}
+
+// Written whenever an actor completes a step onto a new grid cell.
+#[derive(Debug, Clone)]
+pub struct StepEvent {
+    pub entity: Entity,
+    pub grid_pos: Vector2<i32>,
+}
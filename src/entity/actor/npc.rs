@@ -0,0 +1,91 @@
+use amethyst::{
+    prelude::*,
+    core::{
+        transform::Transform,
+        math::Vector2,
+    },
+    ecs::{Component, DenseVecStorage, Entity},
+    renderer::SpriteRender,
+};
+
+use crate::utils::resolve;
+use crate::entity::actor::{world_position, ActorAttrs, ActorDirection};
+
+#[derive(Clone)]
+pub struct Npc {
+    pub attrs: ActorAttrs,
+    pub grid_pos: Vector2<i32>,
+    // Number of cells the npc can see in front of it. Zero for npcs that never spot the player.
+    pub sight_range: u32,
+    pub trainer: Option<String>,
+    // The shop the npc runs, opened by talking to it.
+    pub shop: Option<String>,
+    // Pending steps in the facing direction, consumed by the NpcSystem.
+    pub steps: usize,
+    // Seconds into the step in progress.
+    pub elapsed: f32,
+}
+
+impl Npc {
+    pub fn create(world: &mut World, sprite: String, grid_pos: Vector2<i32>, direction: ActorDirection,
+                  sight_range: u32, trainer: Option<String>) -> Entity {
+        let mut attrs = ActorAttrs::new();
+        attrs.direction = direction;
+
+        let mut npc = Npc {
+            attrs,
+            grid_pos,
+            sight_range,
+            trainer,
+            shop: None,
+            steps: 0,
+            elapsed: 0.0,
+        };
+
+        npc.init(world, sprite)
+    }
+
+    pub fn create_clerk(world: &mut World, sprite: String, grid_pos: Vector2<i32>, direction: ActorDirection,
+                        shop: String) -> Entity {
+        let mut attrs = ActorAttrs::new();
+        attrs.direction = direction;
+
+        let mut npc = Npc {
+            attrs,
+            grid_pos,
+            sight_range: 0,
+            trainer: None,
+            shop: Some(shop),
+            steps: 0,
+            elapsed: 0.0,
+        };
+
+        npc.init(world, sprite)
+    }
+
+    fn init(&mut self, world: &mut World, sprite: String) -> Entity {
+        let sprite_sheet = resolve::load_spritesheet_handle(world, "players/".to_string() + sprite.as_str());
+        let sprite = SpriteRender {
+            sprite_sheet,
+            sprite_number: self.attrs.to_sprite_index(),
+        };
+
+        let (x, y) = world_position(self.grid_pos);
+        let mut transform = Transform::default();
+        transform.set_translation_xyz(x, y, 2.0);
+
+        world.create_entity()
+            .with(sprite)
+            .with(self.clone())
+            .with(transform)
+            .build()
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.steps == 0
+    }
+}
+
+impl Component for Npc {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -2,7 +2,7 @@ use amethyst::{
     prelude::*,
     core::{
         transform::Transform,
-        math::{Vector2, Vector3},
+        math::Vector2,
     },
     ecs::{Component, DenseVecStorage},
     renderer::{SpriteSheet, SpriteRender},
@@ -10,23 +10,71 @@ use amethyst::{
 };
 
 use crate::utils::resolve;
-use crate::entity::actor::ActorAttrs;
+use crate::entity::actor::{world_position, ActorAttrs};
+use crate::item::bag::Bag;
+use crate::pokemon::{library::Library, party::Party, Pokemon};
+use crate::save::PlayerSave;
+
+const STARTING_MONEY: u32 = 3000;
 
 
 #[derive(Clone)]
 pub struct Player {
+    pub name: String,
+    // Trainer ID, shown on the trainer card.
+    pub id: u16,
+    pub money: u32,
     pub attrs: ActorAttrs,
     pub sprite_sheet_handle: Option<Handle<SpriteSheet>>,
-    pub grid_pos: Vector2<i32>
+    pub grid_pos: Vector2<i32>,
+    // How far the player has moved towards the next cell during a step, in pixels.
+    pub step_offset: Vector2<f32>,
+    pub party: Party,
+    pub bag: Bag
 }
 
 impl Player {
-    pub fn create(world: &mut World, name: String) {
-        let sprite_sheet_handle = resolve::load_spritesheet_handle(world, "players/".to_string() + name.as_str());
+    // Creates the player from a save, or with a new game's starter and bag.
+    pub fn create(world: &mut World, sprite: String, save: Option<PlayerSave>) {
+        let sprite_sheet_handle = resolve::load_spritesheet_handle(world, "players/".to_string() + sprite.as_str());
+
+        if let Some(save) = save {
+            let mut player = Player {
+                name: save.name,
+                id: save.id,
+                money: save.money,
+                attrs: ActorAttrs::new(),
+                sprite_sheet_handle: Some(sprite_sheet_handle),
+                grid_pos: Vector2::new(save.grid_pos.0, save.grid_pos.1),
+                step_offset: Vector2::zeros(),
+                party: save.party,
+                bag: save.bag
+            };
+            player.attrs.direction = save.direction;
+            player.init(world);
+            return;
+        }
+
+        let (starter, bag) = {
+            let library = world.read_resource::<Library>();
+            let mut bag = Bag::default();
+            for (item, quantity) in &[("potion", 3), ("poke-ball", 5)] {
+                bag.add(&library, item, *quantity).expect("failed to fill the starting bag");
+            }
+
+            (Pokemon::new(&library, "charmander", 5), bag)
+        };
+
         let mut player = Player {
+            name: sprite.to_uppercase(),
+            id: rand::random(),
+            money: STARTING_MONEY,
             attrs: ActorAttrs::new(),
             sprite_sheet_handle: Some(sprite_sheet_handle),
-            grid_pos: Vector2::new(0, 0)
+            grid_pos: Vector2::new(0, 0),
+            step_offset: Vector2::zeros(),
+            party: Party::new(starter.into_iter().collect()),
+            bag
         };
 
         player.init(world);
@@ -39,9 +87,9 @@ impl Player {
                 sprite_number: self.attrs.to_sprite_index(),
             };
 
+            let (x, y) = world_position(self.grid_pos);
             let mut transform = Transform::default();
-            transform.set_translation_xyz(320.0, 320.0, 2.0);
-            transform.set_scale(Vector3::new(2.0, 2.0, 1.0));
+            transform.set_translation_xyz(x, y, 2.0);
 
             world.create_entity()
                 .with(sprite)
@@ -59,9 +107,15 @@ impl Component for Player {
 impl Default for Player {
     fn default() -> Self {
         Player {
+            name: String::new(),
+            id: 0,
+            money: 0,
             attrs: ActorAttrs::new(),
             sprite_sheet_handle: None,
-            grid_pos: Vector2::new(0, 0)
+            grid_pos: Vector2::new(0, 0),
+            step_offset: Vector2::zeros(),
+            party: Party::default(),
+            bag: Bag::default()
         }
     }
 }
@@ -0,0 +1,69 @@
+use amethyst::{
+    prelude::*,
+    ecs::{Component, DenseVecStorage},
+    ui::{UiTransform, UiImage, Anchor},
+};
+
+const BALL_X: f32 = -60.;
+const BALL_Y: f32 = -45.;
+// How far the ball rocks to each side while shaking.
+const SHAKE_OFFSET: f32 = 4.;
+// Seconds for one rock, and for the pause at each end.
+const ROCK_TIME: f32 = 0.5;
+
+// A thrown ball sitting where the wild pokemon was. It rocks once per passed shake check,
+// with a short pause before and after, then the BallShakeSystem removes it and reports
+// `Trigger::BallShakeEnd` to the battle.
+#[derive(Clone)]
+pub struct BallShake {
+    pub shakes: u8,
+    // Seconds since the ball landed.
+    pub elapsed: f32,
+}
+
+impl BallShake {
+    pub fn create(world: &mut World, shakes: u8) {
+        let shake = BallShake {
+            shakes,
+            elapsed: 0.0,
+        };
+
+        world.create_entity()
+            .with(UiTransform::new(
+                "battle_ball".to_string(),
+                Anchor::TopRight,
+                Anchor::Middle,
+                BALL_X,
+                BALL_Y,
+                12.,
+                9.,
+                9.,
+            ))
+            .with(UiImage::SolidColor([0.85, 0.15, 0.15, 1.]))
+            .with(shake)
+            .build();
+    }
+
+    pub fn duration(&self) -> f32 {
+        ROCK_TIME * (self.shakes as f32 + 2.)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration()
+    }
+
+    // Horizontal position of the ball for the current frame.
+    pub fn x(&self) -> f32 {
+        let shake = (self.elapsed / ROCK_TIME) as usize;
+        if shake == 0 || shake > self.shakes as usize {
+            return BALL_X;
+        }
+
+        let progress = (self.elapsed % ROCK_TIME) / ROCK_TIME;
+        BALL_X + (progress * std::f32::consts::PI * 2.).sin() * SHAKE_OFFSET
+    }
+}
+
+impl Component for BallShake {
+    type Storage = DenseVecStorage<Self>;
+}
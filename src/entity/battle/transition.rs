@@ -0,0 +1,70 @@
+use amethyst::{
+    prelude::*,
+    ecs::{Component, DenseVecStorage},
+    ui::{UiTransform, UiImage, Anchor, Stretch},
+};
+
+use crate::utils::screen::VirtualScreen;
+
+// Seconds from the first flash to full black.
+const DURATION: f32 = 1.0;
+
+// Full-screen overlay that flashes a few times and then fades to black before a battle.
+#[derive(Clone)]
+pub struct BattleTransition {
+    // Seconds since the transition started.
+    pub elapsed: f32,
+}
+
+impl BattleTransition {
+    pub fn create(world: &mut World) {
+        let transition = BattleTransition {
+            elapsed: 0.0,
+        };
+
+        let (width, height) = {
+            let dim = world.read_resource::<VirtualScreen>();
+            (dim.width, dim.height)
+        };
+        let transform = UiTransform::new(
+            "battle_transition".to_string(),
+            Anchor::Middle,
+            Anchor::Middle,
+            0.,
+            0.,
+            10.,
+            width,
+            height,
+        ).with_stretch(Stretch::XY {
+            x_margin: 0.,
+            y_margin: 0.,
+            keep_aspect_ratio: false,
+        });
+
+        world.create_entity()
+            .with(transform)
+            .with(UiImage::SolidColor([0., 0., 0., 0.]))
+            .with(transition)
+            .build();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= DURATION
+    }
+
+    pub fn alpha(&self) -> f32 {
+        let progress = self.elapsed / DURATION;
+
+        // 0..0.6 :- three flashes
+        // 0.6..1 :- fade to black
+        if progress < 0.6 {
+            if (progress / 0.2).fract() < 0.5 { 1.0 } else { 0.0 }
+        } else {
+            ((progress - 0.6) / 0.4).min(1.0)
+        }
+    }
+}
+
+impl Component for BattleTransition {
+    type Storage = DenseVecStorage<Self>;
+}
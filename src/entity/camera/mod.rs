@@ -0,0 +1,158 @@
+use amethyst::{
+    core::math::Vector2,
+    ecs::{Component, DenseVecStorage, Entity},
+};
+
+struct CameraPan {
+    // The point to center on, or None to go back to the target.
+    to: Option<Vector2<f32>>,
+    // Seconds left until it gets there.
+    remaining: f32,
+}
+
+// Keeps the camera centered on an entity, within the bounds of the map. Cutscenes can pan it
+// to a point and release it back to the entity.
+pub struct CameraFollow {
+    pub target: Option<Entity>,
+    pan: Option<CameraPan>,
+}
+
+impl CameraFollow {
+    pub fn new(target: Option<Entity>) -> Self {
+        CameraFollow {
+            target,
+            pan: None,
+        }
+    }
+
+    // Moves the camera to center on `to` over `duration` seconds, and keeps it there until it
+    // is released.
+    pub fn pan_to(&mut self, to: (f32, f32), duration: f32) {
+        self.pan = Some(CameraPan {
+            to: Some(Vector2::new(to.0, to.1)),
+            remaining: duration,
+        });
+    }
+
+    // Moves the camera back to the target over `duration` seconds, then follows it again.
+    pub fn release(&mut self, duration: f32) {
+        self.pan = Some(CameraPan {
+            to: None,
+            remaining: duration,
+        });
+    }
+
+    // The point to center on given where the target is, and how far to move there in the
+    // `delta` seconds of this frame, as a fraction of the distance.
+    pub fn advance(&mut self, target: Vector2<f32>, delta: f32) -> (Vector2<f32>, f32) {
+        let pan = match &mut self.pan {
+            Some(pan) => pan,
+            None => return (target, 1.0),
+        };
+
+        let to = pan.to.unwrap_or(target);
+        let fraction = if pan.remaining > delta { delta / pan.remaining } else { 1.0 };
+        pan.remaining = (pan.remaining - delta).max(0.0);
+
+        if pan.remaining <= 0.0 && pan.to.is_none() {
+            self.pan = None;
+        }
+        (to, fraction)
+    }
+}
+
+impl Component for CameraFollow {
+    type Storage = DenseVecStorage<Self>;
+}
+
+// Where to put the camera for `center` to be in the middle of a `width` by `height` screen.
+// The camera's translation is the top left corner of what it shows.
+pub fn framing(center: Vector2<f32>, width: f32, height: f32) -> Vector2<f32> {
+    Vector2::new(center[0] - width / 2.0, center[1] + height / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::trainer::PAN_TIME;
+
+    // Runs `frames` frames of `delta` seconds from `from` the way the follow system does, and
+    // returns where the camera ends up.
+    fn run(
+        follow: &mut CameraFollow,
+        from: Vector2<f32>,
+        target: Vector2<f32>,
+        delta: f32,
+        frames: usize,
+    ) -> Vector2<f32> {
+        let mut current = from;
+        for _ in 0..frames {
+            let (to, fraction) = follow.advance(target, delta);
+            current += (to - current) * fraction;
+        }
+        current
+    }
+
+    fn frames(duration: f32, delta: f32) -> usize {
+        (duration / delta).ceil() as usize
+    }
+
+    #[test]
+    fn without_a_pan_the_target_is_reached_at_once() {
+        let mut follow = CameraFollow::new(None);
+        let target = Vector2::new(10.0, -20.0);
+        assert_eq!(follow.advance(target, 0.016), (target, 1.0));
+    }
+
+    #[test]
+    fn pans_finish_after_pan_time_whatever_the_frame_delta() {
+        let from = Vector2::new(0.0, 0.0);
+        let point = Vector2::new(64.0, 32.0);
+        for &delta in &[0.001, 1.0 / 60.0, 1.0 / 30.0, 0.1, 0.3, 2.0] {
+            let mut follow = CameraFollow::new(None);
+            follow.pan_to((point[0], point[1]), PAN_TIME);
+
+            let before = frames(PAN_TIME, delta) - 1;
+            if before > 0 {
+                let partway = run(&mut follow, from, from, delta, before);
+                assert!(partway != point, "delta {} arrived early", delta);
+            }
+            let arrived = run(&mut follow, from, from, delta, frames(PAN_TIME, delta) - before);
+            assert!((arrived - point).norm() < 1e-3, "delta {} ended at {:?}", delta, arrived);
+
+            // It holds there while the target moves on.
+            assert_eq!(follow.advance(Vector2::new(-100.0, 0.0), delta), (point, 1.0));
+        }
+    }
+
+    #[test]
+    fn pans_move_evenly() {
+        let mut follow = CameraFollow::new(None);
+        follow.pan_to((100.0, 0.0), 1.0);
+        let halfway = run(&mut follow, Vector2::new(0.0, 0.0), Vector2::new(0.0, 0.0), 0.25, 2);
+        assert!((halfway[0] - 50.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn releases_return_to_the_target_then_follow_it() {
+        let from = Vector2::new(64.0, 32.0);
+        let target = Vector2::new(0.0, 0.0);
+        let mut follow = CameraFollow::new(None);
+        follow.pan_to((from[0], from[1]), PAN_TIME);
+        run(&mut follow, from, target, PAN_TIME, 1);
+
+        follow.release(PAN_TIME);
+        let delta = 1.0 / 60.0;
+        let back = run(&mut follow, from, target, delta, frames(PAN_TIME, delta));
+        assert!((back - target).norm() < 1e-3);
+
+        let moved = Vector2::new(16.0, 0.0);
+        assert_eq!(follow.advance(moved, delta), (moved, 1.0));
+    }
+
+    #[test]
+    fn framing_puts_the_center_in_the_middle_of_the_screen() {
+        assert_eq!(framing(Vector2::new(100.0, 50.0), 160.0, 144.0), Vector2::new(20.0, 122.0));
+        assert_eq!(framing(Vector2::new(0.0, 0.0), 160.0, 144.0), Vector2::new(-80.0, 72.0));
+    }
+}
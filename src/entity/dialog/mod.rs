@@ -1,12 +1,13 @@
 pub mod talk_dialog;
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum DialogSpritetype {
     Bottom,
     OptionBox
 }
 
 impl DialogSpritetype {
-    pub fn to_sprite_index(&self) -> usize {
+    pub fn to_sprite_index(self) -> usize {
         match self {
             DialogSpritetype::Bottom => 0,
             DialogSpritetype::OptionBox => 1
@@ -9,9 +9,16 @@ use amethyst::{
     renderer::{SpriteRender, Transparent},
 };
 
-use crate::utils::resolve;
+use crate::state::Game;
+use crate::utils::{resolve, screen::VirtualScreen};
 use crate::entity::dialog::DialogSpritetype;
 
+// Size of the box sprite in the dialog sheet.
+const BOX_SPRITE_SIZE: (f32, f32) = (584., 145.);
+// The box spans the width of the screen along its bottom. Its height is in virtual pixels.
+const BOX_HEIGHT: f32 = 48.0;
+// How far above the bottom of the screen the box is centered, in virtual pixels.
+pub const BOX_CENTER_Y: f32 = BOX_HEIGHT / 2.0;
 
 #[derive(Clone)]
 pub struct TalkDialog {
@@ -22,17 +29,14 @@ pub struct TalkDialog {
 }
 
 impl TalkDialog {
-    pub fn create(world: &mut World) {
+    pub fn create(world: &mut World, text: Vec<String>) {
         let mut dialog = TalkDialog {
-            text: Vec::new(),
+            text,
             index: 0,
             char_index: 0,
             mesh: None,
         };
 
-        dialog.text.push("Hello, I'm Professor Oak.\n\nWelcome to the world of Pokemon!".to_string());
-        dialog.text.push("Your objective is simple.\n\nScrew your rival and the elite four and get to the hall of fame. Screw your rival and the elite four and get to the hall of fame.".to_string());
-
         dialog.init(world);
     }
 
@@ -43,7 +47,7 @@ impl TalkDialog {
             font,
             "".to_string(),
             [1., 1., 1., 1.],
-            32.,
+            12.,
         );
 
         text.line_mode = LineMode::Wrap;
@@ -55,19 +59,25 @@ impl TalkDialog {
             sprite_number: DialogSpritetype::Bottom.to_sprite_index()
         };
 
+        // The talk dialog system keeps the box along the bottom of the screen.
+        let camera = world.read_resource::<Game>().camera_trans;
+        let (width, height) = {
+            let dim = world.read_resource::<VirtualScreen>();
+            (dim.width, dim.height)
+        };
         let mut transform = Transform::default();
-        transform.set_translation_xyz(320.0, 80.0, 3.0);
-        transform.set_scale(Vector3::new(1.2,1.1,1.0));
+        transform.set_translation_xyz(camera[0] + width / 2.0, camera[1] - height + BOX_CENTER_Y, 3.0);
+        transform.set_scale(Vector3::new(width / BOX_SPRITE_SIZE.0, BOX_HEIGHT / BOX_SPRITE_SIZE.1, 1.0));
 
         let text_transform = UiTransform::new(
             "dialog_bottom_text".to_string(),
             Anchor::BottomLeft,
             Anchor::MiddleLeft,
+            10.,
             24.,
-            64.,
             4.,
-            592.,
-            144.
+            width - 20.,
+            40.
         );
 
         self.mesh = Some(world.create_entity()
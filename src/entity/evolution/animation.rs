@@ -0,0 +1,69 @@
+use amethyst::{
+    prelude::*,
+    ecs::{Component, DenseVecStorage, Entity},
+    renderer::SpriteRender,
+    ui::{UiTransform, UiImage, Anchor},
+};
+
+use crate::utils::resolve;
+
+// Seconds the sequence runs for before the pokemon has evolved.
+const DURATION: f32 = 5.0;
+// The shortest the icon shows for, in seconds, once the flickers are at their fastest.
+const MIN_FLICKER: f32 = 1.0 / 30.0;
+
+// The icon of an evolving pokemon, flickering between its species and the one it evolves
+// into, faster and faster until the EvolutionAnimationSystem settles on the new one and
+// reports `Trigger::EvolutionEnd`.
+#[derive(Clone)]
+pub struct EvolutionAnimation {
+    pub from: usize,
+    pub into: usize,
+    // Seconds since the sequence started.
+    pub elapsed: f32,
+}
+
+impl EvolutionAnimation {
+    pub fn create(world: &mut World, from: usize, into: usize) -> Entity {
+        let animation = EvolutionAnimation {
+            from,
+            into,
+            elapsed: 0.0,
+        };
+        let sprite_sheet = resolve::load_spritesheet_handle(world, "pokemon/icons".to_string());
+
+        world.create_entity()
+            .with(UiTransform::new(
+                "evolution_icon".to_string(),
+                Anchor::Middle,
+                Anchor::Middle,
+                0.,
+                24.,
+                28.,
+                48.,
+                48.,
+            ))
+            .with(UiImage::Sprite(SpriteRender { sprite_sheet, sprite_number: from }))
+            .with(animation)
+            .build()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= DURATION
+    }
+
+    // The icon shown for the current frame. Each flicker is shorter than the last.
+    pub fn sprite(&self) -> usize {
+        if self.is_finished() {
+            return self.into;
+        }
+
+        // Each flicker lasts as many seconds as the fraction of the sequence still to go.
+        let period = ((DURATION - self.elapsed) / DURATION).max(MIN_FLICKER);
+        if (self.elapsed / period) as usize % 2 == 0 { self.from } else { self.into }
+    }
+}
+
+impl Component for EvolutionAnimation {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,69 @@
+use amethyst::{
+    prelude::*,
+    ecs::{Component, DenseVecStorage, Entity},
+    ui::{UiTransform, UiImage, Anchor, Stretch},
+};
+
+use crate::utils::screen::VirtualScreen;
+
+// Seconds a fade takes.
+const DURATION: f32 = 1.0 / 3.0;
+
+// Full-screen overlay that fades to black, or from black, when the player changes maps. The
+// MapFadeSystem reports `Trigger::FadeEnd` once it is done and leaves the entity to the state
+// that created it.
+#[derive(Clone)]
+pub struct MapFade {
+    // Seconds since the fade started.
+    pub elapsed: f32,
+    // Fading from black instead of to it.
+    pub reveal: bool,
+}
+
+impl MapFade {
+    pub fn create(world: &mut World, reveal: bool) -> Entity {
+        let fade = MapFade {
+            elapsed: 0.0,
+            reveal,
+        };
+
+        let (width, height) = {
+            let dim = world.read_resource::<VirtualScreen>();
+            (dim.width, dim.height)
+        };
+        let transform = UiTransform::new(
+            "map_fade".to_string(),
+            Anchor::Middle,
+            Anchor::Middle,
+            0.,
+            0.,
+            30.,
+            width,
+            height,
+        ).with_stretch(Stretch::XY {
+            x_margin: 0.,
+            y_margin: 0.,
+            keep_aspect_ratio: false,
+        });
+
+        let alpha = fade.alpha();
+        world.create_entity()
+            .with(transform)
+            .with(UiImage::SolidColor([0., 0., 0., alpha]))
+            .with(fade)
+            .build()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= DURATION
+    }
+
+    pub fn alpha(&self) -> f32 {
+        let progress = (self.elapsed / DURATION).min(1.0);
+        if self.reveal { 1.0 - progress } else { progress }
+    }
+}
+
+impl Component for MapFade {
+    type Storage = DenseVecStorage<Self>;
+}
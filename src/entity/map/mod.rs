@@ -0,0 +1,3 @@
+pub mod fade;
+pub mod tint;
+pub mod weather;
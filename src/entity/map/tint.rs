@@ -0,0 +1,62 @@
+use amethyst::{
+    prelude::*,
+    ecs::{Component, DenseVecStorage, Entity},
+    ui::{UiTransform, UiImage, Anchor, Stretch},
+};
+
+use crate::utils::screen::VirtualScreen;
+
+// Seconds the tint takes to blend into the colour of a new period.
+pub const TINT_BLEND_TIME: f32 = 2.0;
+
+// Full-screen overlay that tints the overworld by the time of day. It sits under every other
+// UI element, so that dialogs and menus keep their colours. The DayTintSystem blends it
+// towards the colour of the current period.
+#[derive(Clone)]
+pub struct DayTint {
+    pub color: [f32; 4],
+}
+
+impl DayTint {
+    pub fn create(world: &mut World) -> Entity {
+        let (width, height) = {
+            let dim = world.read_resource::<VirtualScreen>();
+            (dim.width, dim.height)
+        };
+        let transform = UiTransform::new(
+            "day_tint".to_string(),
+            Anchor::Middle,
+            Anchor::Middle,
+            0.,
+            0.,
+            0.5,
+            width,
+            height,
+        ).with_stretch(Stretch::XY {
+            x_margin: 0.,
+            y_margin: 0.,
+            keep_aspect_ratio: false,
+        });
+
+        let tint = DayTint {
+            color: [0., 0., 0., 0.],
+        };
+
+        world.create_entity()
+            .with(transform)
+            .with(UiImage::SolidColor(tint.color))
+            .with(tint)
+            .build()
+    }
+
+    // Moves the colour `t` of the way towards `target`.
+    pub fn blend(&mut self, target: [f32; 4], t: f32) {
+        for (channel, goal) in self.color.iter_mut().zip(target.iter()) {
+            *channel += (goal - *channel) * t.min(1.0);
+        }
+    }
+}
+
+impl Component for DayTint {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,65 @@
+use amethyst::ecs::{Component, DenseVecStorage};
+
+use crate::battle::weather::Weather;
+
+// Under the day tint, so that the weather darkens at night with the rest of the map.
+pub const WEATHER_OVERLAY_Z: f32 = 0.3;
+pub const WEATHER_PARTICLE_Z: f32 = 0.4;
+
+// How a kind of particle looks and moves. Velocities are in virtual pixels per second, and
+// every particle gets a random share of it so that they don't move in lockstep.
+pub struct ParticleStyle {
+    pub count: usize,
+    pub size: (f32, f32),
+    pub color: [f32; 4],
+    pub velocity: (f32, f32),
+}
+
+// The colour laid over the map in the weather, if any.
+pub fn overlay(weather: Weather) -> Option<[f32; 4]> {
+    Some(match weather {
+        Weather::Rain => [0.2, 0.25, 0.4, 0.2],
+        Weather::Snow => [0.9, 0.95, 1.0, 0.15],
+        Weather::Sandstorm => [0.8, 0.65, 0.4, 0.35],
+        Weather::Fog => [0.85, 0.85, 0.9, 0.45],
+        Weather::HarshSun => [1.0, 0.9, 0.5, 0.15],
+    })
+}
+
+// The particles falling or blowing over the map in the weather, if any.
+pub fn particles(weather: Weather) -> Option<ParticleStyle> {
+    match weather {
+        Weather::Rain => Some(ParticleStyle {
+            count: 80,
+            size: (1., 5.),
+            color: [0.7, 0.8, 1.0, 0.6],
+            velocity: (-22., -225.),
+        }),
+        Weather::Snow => Some(ParticleStyle {
+            count: 60,
+            size: (2., 2.),
+            color: [1.0, 1.0, 1.0, 0.9],
+            velocity: (-8., -30.),
+        }),
+        Weather::Sandstorm => Some(ParticleStyle {
+            count: 70,
+            size: (2., 1.),
+            color: [0.9, 0.8, 0.55, 0.8],
+            velocity: (190., -22.),
+        }),
+        Weather::Fog | Weather::HarshSun => None,
+    }
+}
+
+// A UI element of the weather shown over the map. The WeatherSystem spawns them when the
+// weather of the current map changes, and moves the particles across the virtual screen.
+#[derive(Clone)]
+pub struct WeatherLayer {
+    // Where a particle is, in virtual pixels from the bottom left corner. Overlays don't move.
+    pub position: Option<(f32, f32)>,
+    pub velocity: (f32, f32),
+}
+
+impl Component for WeatherLayer {
+    type Storage = DenseVecStorage<Self>;
+}
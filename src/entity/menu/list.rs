@@ -0,0 +1,277 @@
+use amethyst::{
+    prelude::*,
+    ecs::{Component, DenseVecStorage, Entity},
+    renderer::SpriteRender,
+    shrev::{EventChannel, ReaderId},
+    ui::{UiTransform, UiText, UiImage, Anchor, LineMode},
+};
+
+use crate::entity::dialog::DialogSpritetype;
+use crate::entity::menu::selection::ListSelection;
+use crate::utils::resolve;
+
+const PADDING: f32 = 8.;
+const DISABLED_COLOR: [f32; 4] = [0.55, 0.55, 0.55, 1.];
+
+#[derive(Debug, Clone)]
+pub struct ListEntry {
+    pub label: String,
+    pub disabled: bool,
+}
+
+impl ListEntry {
+    pub fn new<S: Into<String>>(label: S) -> Self {
+        ListEntry {
+            label: label.into(),
+            disabled: false,
+        }
+    }
+
+    pub fn disabled<S: Into<String>>(label: S) -> Self {
+        ListEntry {
+            label: label.into(),
+            disabled: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ListEventKind {
+    Moved(usize),
+    Confirmed(usize),
+    Cancelled,
+}
+
+// Written by the ListWidgetSystem for the list entity that had the input.
+#[derive(Debug, Clone)]
+pub struct ListEvent {
+    pub list: Entity,
+    pub kind: ListEventKind,
+}
+
+// Where and how a list is drawn. The position is that of the frame, pivoted on its anchor.
+#[derive(Clone)]
+pub struct ListLayout {
+    pub id: String,
+    pub anchor: Anchor,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub width: f32,
+    // How many entries are shown at once. 0 shows all of them.
+    pub rows: usize,
+    pub line_height: f32,
+    pub font_size: f32,
+    pub frame: Option<DialogSpritetype>,
+    pub color: [f32; 4],
+    pub wrap: bool,
+}
+
+impl ListLayout {
+    pub fn new(id: &str, anchor: Anchor, x: f32, y: f32, z: f32, width: f32) -> Self {
+        ListLayout {
+            id: id.to_string(),
+            anchor,
+            x,
+            y,
+            z,
+            width,
+            rows: 0,
+            line_height: 14.,
+            font_size: 12.,
+            frame: Some(DialogSpritetype::OptionBox),
+            color: [0.1, 0.1, 0.1, 1.],
+            wrap: false,
+        }
+    }
+
+    pub fn with_rows(mut self, rows: usize) -> Self {
+        self.rows = rows;
+        self
+    }
+
+    pub fn with_frame(mut self, frame: Option<DialogSpritetype>) -> Self {
+        self.frame = frame;
+        self
+    }
+
+    pub fn with_color(mut self, color: [f32; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_wrap(mut self) -> Self {
+        self.wrap = true;
+        self
+    }
+}
+
+// A scrolling list with a cursor, drawn as a frame from the dialog sheet with one UiText per
+// visible row. The ListWidgetSystem moves the cursor of the active list and reports
+// confirm/cancel as ListEvents; states turn the list off while something is stacked on them.
+pub struct ListWidget {
+    pub selection: ListSelection,
+    labels: Vec<String>,
+    active: bool,
+    // Set once confirm and cancel have been released since the list was activated, so the
+    // key press that opened it does not also confirm it.
+    pub armed: bool,
+    color: [f32; 4],
+    parts: Vec<Entity>,
+    pub rows: Vec<Entity>,
+    pub arrows: (Entity, Entity),
+}
+
+impl ListWidget {
+    pub fn create(world: &mut World, layout: &ListLayout, entries: Vec<ListEntry>) -> Entity {
+        let rows = if layout.rows == 0 { entries.len() } else { layout.rows };
+        let height = rows as f32 * layout.line_height + 2. * PADDING;
+
+        // Rows and arrows are placed from the frame's top left corner, whatever its anchor.
+        let (anchor_x, anchor_y) = layout.anchor.norm_offset();
+        let left = layout.x - layout.width * (0.5 + anchor_x);
+        let top = layout.y + height * (0.5 - anchor_y);
+
+        let mut parts = Vec::new();
+        if let Some(frame) = &layout.frame {
+            let sprite_sheet = resolve::load_spritesheet_handle(world, "dialogs/sheet".to_string());
+            parts.push(world.create_entity()
+                .with(UiTransform::new(
+                    format!("{}_frame", layout.id),
+                    layout.anchor,
+                    layout.anchor,
+                    layout.x,
+                    layout.y,
+                    layout.z,
+                    layout.width,
+                    height,
+                ))
+                .with(UiImage::Sprite(SpriteRender {
+                    sprite_sheet,
+                    sprite_number: frame.to_sprite_index(),
+                }))
+                .build());
+        }
+
+        let text_transform = |id: String, x: f32, y: f32, width: f32| UiTransform::new(
+            id,
+            layout.anchor,
+            Anchor::TopLeft,
+            x,
+            y,
+            layout.z + 1.,
+            width,
+            layout.line_height,
+        );
+
+        let mut row_entities = Vec::new();
+        for i in 0..rows {
+            let transform = text_transform(
+                format!("{}_row_{}", layout.id, i),
+                left + PADDING,
+                top - PADDING - i as f32 * layout.line_height,
+                layout.width - 2. * PADDING - 6.,
+            );
+            row_entities.push(ListWidget::create_text(world, transform, layout));
+        }
+
+        let up = text_transform(format!("{}_more_above", layout.id), left + layout.width - PADDING - 5., top - 3., 6.);
+        let down = text_transform(format!("{}_more_below", layout.id), left + layout.width - PADDING - 5., top - height + layout.line_height - 3., 6.);
+        let arrows = (ListWidget::create_text(world, up, layout), ListWidget::create_text(world, down, layout));
+
+        parts.extend(row_entities.iter().cloned());
+        parts.push(arrows.0);
+        parts.push(arrows.1);
+
+        let mut selection = ListSelection::new(entries.len()).with_visible_rows(layout.rows);
+        if layout.wrap {
+            selection = selection.with_wrap();
+        }
+
+        let mut widget = ListWidget {
+            selection,
+            labels: Vec::new(),
+            active: true,
+            armed: false,
+            color: layout.color,
+            parts,
+            rows: row_entities,
+            arrows,
+        };
+        widget.set_entries(entries);
+
+        world.create_entity()
+            .with(widget)
+            .build()
+    }
+
+    fn create_text(world: &mut World, transform: UiTransform, layout: &ListLayout) -> Entity {
+        let font = resolve::load_font_handle(world);
+        let mut text = UiText::new(font, "".to_string(), layout.color, layout.font_size);
+        text.line_mode = LineMode::Single;
+        text.align = Anchor::TopLeft;
+
+        world.create_entity()
+            .with(transform)
+            .with(text)
+            .build()
+    }
+
+    // Deletes the list along with its frame and rows.
+    pub fn delete(world: &mut World, list: Entity) {
+        let parts = world.read_storage::<ListWidget>().get(list).map(|w| w.parts.clone()).unwrap_or_default();
+        for entity in parts {
+            world.delete_entity(entity).expect("failed to delete list widget part");
+        }
+        world.delete_entity(list).expect("failed to delete list widget");
+    }
+
+    // Replaces the entries, keeping the cursor where it was if it is still on the list.
+    pub fn set_entries(&mut self, entries: Vec<ListEntry>) {
+        self.selection.set_len(entries.len());
+        for (i, entry) in entries.iter().enumerate() {
+            self.selection.set_disabled(i, entry.disabled);
+        }
+        self.labels = entries.into_iter().map(|e| e.label).collect();
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub fn set_active(&mut self, active: bool) {
+        self.active = active;
+        self.armed = false;
+    }
+
+    // Text and colour of each row, in order.
+    pub fn row_texts(&self) -> Vec<(String, [f32; 4])> {
+        let visible = self.selection.visible();
+        (0..self.rows.len())
+            .map(|row| {
+                let i = visible.start + row;
+                match self.labels.get(i) {
+                    Some(label) if i < visible.end => {
+                        let marker = if i == self.selection.index() { "> " } else { "  " };
+                        let color = if self.selection.is_disabled(i) { DISABLED_COLOR } else { self.color };
+                        (format!("{}{}", marker, label), color)
+                    }
+                    _ => ("".to_string(), self.color),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Component for ListWidget {
+    type Storage = DenseVecStorage<Self>;
+}
+
+// Registers a reader for list events. States keep it while they own a list.
+pub fn register_reader(world: &mut World) -> ReaderId<ListEvent> {
+    world.write_resource::<EventChannel<ListEvent>>().register_reader()
+}
+
+pub fn read_events(world: &World, reader: &mut ReaderId<ListEvent>) -> Vec<ListEvent> {
+    world.read_resource::<EventChannel<ListEvent>>().read(reader).cloned().collect()
+}
@@ -0,0 +1,239 @@
+use std::ops::Range;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ListInput {
+    Up,
+    Down,
+}
+
+// Cursor and scroll position over a list of entries. It knows nothing about rendering, so
+// menus can drive and check it directly.
+#[derive(Debug, Clone, Default)]
+pub struct ListSelection {
+    index: usize,
+    scroll: usize,
+    // How many entries are shown at once. 0 shows all of them.
+    visible_rows: usize,
+    disabled: Vec<bool>,
+    // Whether moving past either end jumps to the other one.
+    wrap: bool,
+}
+
+impl ListSelection {
+    pub fn new(len: usize) -> Self {
+        ListSelection {
+            index: 0,
+            scroll: 0,
+            visible_rows: 0,
+            disabled: vec![false; len],
+            wrap: false,
+        }
+    }
+
+    pub fn with_wrap(mut self) -> Self {
+        self.wrap = true;
+        self
+    }
+
+    pub fn with_visible_rows(mut self, rows: usize) -> Self {
+        self.visible_rows = rows;
+        self.fit_scroll();
+        self
+    }
+
+    fn len(&self) -> usize {
+        self.disabled.len()
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn rows(&self) -> usize {
+        if self.visible_rows == 0 { self.len() } else { self.visible_rows }
+    }
+
+    // Entries that were already there keep their disabled flag.
+    pub fn set_len(&mut self, len: usize) {
+        self.disabled.resize(len, false);
+        self.select(self.index);
+    }
+
+    pub fn is_disabled(&self, index: usize) -> bool {
+        self.disabled.get(index).cloned().unwrap_or(true)
+    }
+
+    // Disabling the selected entry moves the cursor to the nearest enabled one.
+    pub fn set_disabled(&mut self, index: usize, disabled: bool) {
+        if let Some(flag) = self.disabled.get_mut(index) {
+            *flag = disabled;
+        }
+        self.select(self.index);
+    }
+
+    // Selects the entry, or the nearest enabled one after or before it.
+    pub fn select(&mut self, index: usize) {
+        let len = self.len();
+        if len == 0 {
+            self.index = 0;
+            self.scroll = 0;
+            return;
+        }
+
+        let index = index.min(len - 1);
+        self.index = (index..len).chain((0..index).rev())
+            .find(|i| !self.is_disabled(*i))
+            .unwrap_or(index);
+        self.fit_scroll();
+    }
+
+    // Moves to the next enabled entry in the given direction. Returns whether the cursor moved.
+    pub fn navigate(&mut self, input: ListInput) -> bool {
+        match next_enabled(self.index, input, &self.disabled, self.wrap) {
+            Some(index) => {
+                self.index = index;
+                self.fit_scroll();
+                true
+            }
+            None => false,
+        }
+    }
+
+    // The entry to act on, if the selected one can be confirmed.
+    pub fn confirm(&self) -> Option<usize> {
+        if self.is_disabled(self.index) { None } else { Some(self.index) }
+    }
+
+    pub fn visible(&self) -> Range<usize> {
+        self.scroll..(self.scroll + self.rows()).min(self.len())
+    }
+
+    pub fn more_above(&self) -> bool {
+        self.scroll > 0
+    }
+
+    pub fn more_below(&self) -> bool {
+        self.scroll + self.rows() < self.len()
+    }
+
+    fn fit_scroll(&mut self) {
+        self.scroll = fit_scroll(self.index, self.scroll, self.rows(), self.len());
+    }
+}
+
+// The entry that moving from `index` lands on, skipping disabled entries. None when there is
+// nowhere to go.
+pub fn next_enabled(index: usize, input: ListInput, disabled: &[bool], wrap: bool) -> Option<usize> {
+    let len = disabled.len();
+    let mut next = index;
+
+    for _ in 0..len {
+        next = match input {
+            ListInput::Up if next > 0 => next - 1,
+            ListInput::Up if wrap => len - 1,
+            ListInput::Down if next + 1 < len => next + 1,
+            ListInput::Down if wrap => 0,
+            _ => return None,
+        };
+
+        if next == index {
+            return None;
+        }
+
+        if !disabled[next] {
+            return Some(next);
+        }
+    }
+
+    None
+}
+
+// The first shown entry once the window of `rows` entries is scrolled as little as possible to
+// show `index`.
+pub fn fit_scroll(index: usize, scroll: usize, rows: usize, len: usize) -> usize {
+    let rows = rows.max(1);
+    let scroll = if index < scroll {
+        index
+    } else if index >= scroll + rows {
+        index + 1 - rows
+    } else {
+        scroll
+    };
+    scroll.min(len.saturating_sub(rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_stop_at_the_ends_without_wrap() {
+        let disabled = [false; 3];
+        assert_eq!(next_enabled(0, ListInput::Down, &disabled, false), Some(1));
+        assert_eq!(next_enabled(2, ListInput::Down, &disabled, false), None);
+        assert_eq!(next_enabled(0, ListInput::Up, &disabled, false), None);
+    }
+
+    #[test]
+    fn moves_wrap_around_the_ends() {
+        let disabled = [false; 3];
+        assert_eq!(next_enabled(2, ListInput::Down, &disabled, true), Some(0));
+        assert_eq!(next_enabled(0, ListInput::Up, &disabled, true), Some(2));
+    }
+
+    #[test]
+    fn moves_skip_disabled_entries() {
+        let disabled = [false, true, true, false];
+        assert_eq!(next_enabled(0, ListInput::Down, &disabled, false), Some(3));
+        assert_eq!(next_enabled(3, ListInput::Up, &disabled, false), Some(0));
+
+        // Nothing enabled past the cursor, and no wrapping to reach the rest.
+        assert_eq!(next_enabled(0, ListInput::Down, &[false, true], false), None);
+        assert_eq!(next_enabled(0, ListInput::Down, &[false, true], true), None);
+    }
+
+    #[test]
+    fn empty_and_single_entry_lists_never_move() {
+        assert_eq!(next_enabled(0, ListInput::Down, &[], true), None);
+        assert_eq!(next_enabled(0, ListInput::Up, &[false], true), None);
+    }
+
+    #[test]
+    fn scrolling_follows_the_cursor() {
+        assert_eq!(fit_scroll(0, 0, 3, 10), 0);
+        assert_eq!(fit_scroll(3, 0, 3, 10), 1);
+        assert_eq!(fit_scroll(9, 0, 3, 10), 7);
+        assert_eq!(fit_scroll(4, 7, 3, 10), 4);
+        assert_eq!(fit_scroll(5, 4, 3, 10), 4);
+    }
+
+    #[test]
+    fn scrolling_never_leaves_empty_rows() {
+        assert_eq!(fit_scroll(2, 5, 3, 4), 1);
+        assert_eq!(fit_scroll(1, 0, 5, 2), 0);
+    }
+
+    #[test]
+    fn selection_lands_on_the_nearest_enabled_entry() {
+        let mut selection = ListSelection::new(4);
+        selection.set_disabled(0, true);
+        assert_eq!(selection.index(), 1);
+
+        selection.set_disabled(3, true);
+        selection.select(3);
+        assert_eq!(selection.index(), 2);
+        assert_eq!(selection.confirm(), Some(2));
+    }
+
+    #[test]
+    fn selection_shows_a_window_of_rows() {
+        let mut selection = ListSelection::new(6).with_visible_rows(4).with_wrap();
+        assert_eq!(selection.visible(), 0..4);
+        assert!(!selection.more_above() && selection.more_below());
+
+        assert!(selection.navigate(ListInput::Up));
+        assert_eq!(selection.index(), 5);
+        assert_eq!(selection.visible(), 2..6);
+        assert!(selection.more_above() && !selection.more_below());
+    }
+}
@@ -1,3 +1,9 @@
 pub mod actor;
 pub mod tile;
-pub mod dialog;
\ No newline at end of file
+pub mod dialog;
+pub mod battle;
+pub mod menu;
+pub mod evolution;
+pub mod map;
+pub mod camera;
+pub mod screen;
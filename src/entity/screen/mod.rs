@@ -0,0 +1,65 @@
+use amethyst::{
+    ecs::{Component, DenseVecStorage, Entity, ReadStorage, WriteStorage},
+    ui::UiTransform,
+};
+
+// One of the black bars around the virtual screen. The viewport system sizes them to the window.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Letterbox {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl Letterbox {
+    pub const ALL: [Letterbox; 4] = [Letterbox::Left, Letterbox::Right, Letterbox::Top, Letterbox::Bottom];
+}
+
+impl Component for Letterbox {
+    type Storage = DenseVecStorage<Self>;
+}
+
+// The layout of a UI element in virtual pixels. UI elements are made with their transform in
+// virtual pixels, and the viewport system takes it in here the first time it sees them. From
+// then on it derives the transform from this layout, so code that moves or resizes an element
+// writes here rather than to the transform.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct VirtualLayout {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    // Taken in from the element's text the first time it has one.
+    pub font_size: Option<f32>,
+}
+
+impl Component for VirtualLayout {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl VirtualLayout {
+    pub fn of(transform: &UiTransform) -> Self {
+        VirtualLayout {
+            x: transform.local_x,
+            y: transform.local_y,
+            width: transform.width,
+            height: transform.height,
+            font_size: None,
+        }
+    }
+
+    // The layout of an element, taken from its transform if the viewport system hasn't seen
+    // it yet.
+    pub fn get_mut<'a>(
+        layouts: &'a mut WriteStorage<'_, VirtualLayout>,
+        transforms: &ReadStorage<'_, UiTransform>,
+        entity: Entity,
+    ) -> Option<&'a mut VirtualLayout> {
+        if !layouts.contains(entity) {
+            let layout = VirtualLayout::of(transforms.get(entity)?);
+            layouts.insert(entity, layout).ok()?;
+        }
+        layouts.get_mut(entity)
+    }
+}
@@ -0,0 +1,166 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+};
+
+use amethyst::ecs::{Component, DenseVecStorage};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::tile::TileClass;
+use crate::utils::data::{self, DataError};
+
+// A single animation frame: the tile state (sprite offset) to show and for how many seconds.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TileFrame {
+    pub state: usize,
+    pub duration: f32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TileAnimations {
+    // Animations that loop for as long as the tile exists, e.g. water and flowers.
+    #[serde(default)]
+    pub looping: HashMap<TileClass, Vec<TileFrame>>,
+    // Animations played once when an actor steps onto the tile, e.g. grass rustling.
+    #[serde(default)]
+    pub on_step: HashMap<TileClass, Vec<TileFrame>>,
+}
+
+impl TileAnimations {
+    pub fn load(path: &Path) -> Result<Self, DataError> {
+        data::load_ron(path)
+    }
+
+    pub fn animates(&self, class: TileClass) -> bool {
+        self.looping.contains_key(&class) || self.on_step.contains_key(&class)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Playback {
+    frames: Vec<TileFrame>,
+    index: usize,
+    // Seconds into the current frame.
+    elapsed: f32,
+}
+
+impl Playback {
+    pub fn new(frames: Vec<TileFrame>) -> Self {
+        Playback {
+            frames,
+            index: 0,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn state(&self) -> usize {
+        self.frames.get(self.index).map(|f| f.state).unwrap_or(0)
+    }
+
+    // Advances the playback by `delta` seconds, past several frames at once on a slow frame.
+    // Returns false once a non-looping playback is over.
+    pub fn advance(&mut self, delta: f32, looping: bool) -> bool {
+        if self.frames.is_empty() {
+            return false;
+        }
+
+        self.elapsed += delta;
+        while self.elapsed >= self.frames[self.index].duration {
+            self.elapsed -= self.frames[self.index].duration;
+            self.index += 1;
+
+            if self.index == self.frames.len() {
+                if !looping {
+                    return false;
+                }
+                self.index = 0;
+            }
+
+            // A loop of zero-length frames would never end.
+            if self.frames.iter().all(|f| f.duration <= 0.0) {
+                self.elapsed = 0.0;
+                break;
+            }
+        }
+
+        true
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct TileAnimator {
+    pub looping: Option<Playback>,
+    pub one_shot: Option<Playback>,
+}
+
+impl TileAnimator {
+    pub fn new(looping: Option<Vec<TileFrame>>) -> Self {
+        TileAnimator {
+            looping: looping.map(Playback::new),
+            one_shot: None,
+        }
+    }
+
+    // Restarts the one-shot animation, e.g. when another actor steps in mid-rustle.
+    pub fn trigger(&mut self, frames: Vec<TileFrame>) {
+        self.one_shot = Some(Playback::new(frames));
+    }
+
+    pub fn advance(&mut self, delta: f32) {
+        if let Some(playback) = &mut self.looping {
+            playback.advance(delta, true);
+        }
+
+        let finished = self.one_shot.as_mut().map(|p| !p.advance(delta, false)).unwrap_or(false);
+        if finished {
+            self.one_shot = None;
+        }
+    }
+
+    // One-shot animations are drawn over the looping one while they play.
+    pub fn state(&self) -> usize {
+        self.one_shot.as_ref()
+            .or(self.looping.as_ref())
+            .map(|p| p.state())
+            .unwrap_or(0)
+    }
+}
+
+impl Component for TileAnimator {
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frames() -> Vec<TileFrame> {
+        vec![
+            TileFrame { state: 0, duration: 0.5 },
+            TileFrame { state: 1, duration: 0.25 },
+        ]
+    }
+
+    #[test]
+    fn playback_shows_the_same_frame_whatever_the_frame_rate() {
+        for delta in &[1.0 / 30.0, 1.0 / 60.0, 1.0 / 144.0] {
+            let mut playback = Playback::new(frames());
+            let mut time = 0.0;
+            while time + delta < 0.6 {
+                playback.advance(*delta, true);
+                time += delta;
+            }
+            assert_eq!(playback.state(), 1, "at {} seconds a frame", delta);
+        }
+    }
+
+    #[test]
+    fn a_slow_frame_skips_ahead() {
+        let mut playback = Playback::new(frames());
+        assert!(playback.advance(0.8, true));
+        assert_eq!(playback.state(), 0);
+
+        let mut playback = Playback::new(frames());
+        assert!(!playback.advance(0.8, false));
+    }
+}
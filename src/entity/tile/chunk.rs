@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use amethyst::{
+    prelude::*,
+    assets::{AssetStorage, Loader},
+    core::transform::Transform,
+    ecs::Entity,
+    renderer::{
+        rendy::{
+            hal::image::{Filter, SamplerInfo, ViewKind, WrapMode},
+            texture::TextureBuilder,
+        },
+        sprite::Sprites,
+        types::TextureData,
+        Format, Kind, Sprite, SpriteRender, SpriteSheet, Texture,
+    },
+};
+use image::RgbaImage;
+
+use crate::entity::actor::CELL_SIZE;
+use crate::entity::tile::{TileAttrs, tile::TILE_DROP};
+use crate::map::{chunk::CHUNK_SIZE, stream::MapTile};
+use crate::utils::{data, resolve};
+
+// Tiles are 16 pixels wide in the tilesets, one cell on the map.
+const TILE_PIXELS: u32 = 16;
+
+// Chunk images are drawn under the loose tiles, which sit at 1.0.
+const CHUNK_Z: f32 = 0.9;
+
+// A tileset's pixels and where each of its sprites lies in them.
+pub struct TileAtlas {
+    image: RgbaImage,
+    sprites: Vec<(u32, u32)>,
+}
+
+impl TileAtlas {
+    pub fn load(tileset: &str) -> Result<Self, String> {
+        let name = format!("tiles/{}", tileset);
+        let image = image::open(resolve::get_texture_path(&format!("{}.png", name)))
+            .map_err(|e| e.to_string())?
+            .to_rgba();
+        let sprites = match data::load_ron(&resolve::get_texture_path(&format!("{}.ron", name))) {
+            Ok(Sprites::List(list)) => list.sprites.iter().map(|s| (s.x, s.y)).collect(),
+            Ok(Sprites::Grid(_)) => return Err(format!("{} is not a sprite list", name)),
+            Err(e) => return Err(e.to_string()),
+        };
+
+        Ok(TileAtlas {
+            image,
+            sprites,
+        })
+    }
+
+    // Draws a sprite over the image, with its top left corner at (left, top).
+    fn draw(&self, sprite: usize, image: &mut RgbaImage, left: u32, top: u32) {
+        let (x, y) = match self.sprites.get(sprite) {
+            Some(position) => *position,
+            None => return,
+        };
+
+        for dy in 0..TILE_PIXELS {
+            for dx in 0..TILE_PIXELS {
+                if x + dx >= self.image.width() || y + dy >= self.image.height() {
+                    continue;
+                }
+
+                let src = self.image.get_pixel(x + dx, y + dy).0;
+                let dst = &mut image.get_pixel_mut(left + dx, top + dy).0;
+                let alpha = src[3] as u32;
+                for i in 0..3 {
+                    dst[i] = ((src[i] as u32 * alpha + dst[i] as u32 * (255 - alpha)) / 255) as u8;
+                }
+                dst[3] = (alpha + dst[3] as u32 * (255 - alpha) / 255) as u8;
+            }
+        }
+    }
+}
+
+// The tilesets read so far, by name. One that failed to load is not tried again.
+#[derive(Default)]
+pub struct TileAtlases {
+    atlases: HashMap<String, Option<TileAtlas>>,
+}
+
+impl TileAtlases {
+    fn get(&mut self, tileset: &str) -> Option<&TileAtlas> {
+        self.atlases.entry(tileset.to_string())
+            .or_insert_with(|| TileAtlas::load(tileset).map_err(|e| {
+                eprintln!("failed to load tileset {}: {}", tileset, e);
+            }).ok())
+            .as_ref()
+    }
+
+    // Draws the tiles into one image covering the chunk, in the order they are given. The
+    // top row of the image is the top row of cells.
+    pub fn compose(&mut self, chunk: (i32, i32), tiles: &[((i32, i32), MapTile)]) -> RgbaImage {
+        let size = CHUNK_SIZE as u32 * TILE_PIXELS;
+        let mut image = RgbaImage::new(size, size);
+
+        for ((x, y), tile) in tiles {
+            let column = (x - chunk.0 * CHUNK_SIZE) as u32;
+            let row = (CHUNK_SIZE - 1 - (y - chunk.1 * CHUNK_SIZE)) as u32;
+            let sprite = TileAttrs::new(Some(tile.class)).to_sprite_index();
+            if let Some(atlas) = self.get(&tile.tileset) {
+                atlas.draw(sprite, &mut image, column * TILE_PIXELS, row * TILE_PIXELS);
+            }
+        }
+
+        image
+    }
+}
+
+// Spawns one render entity showing the static tiles of a chunk, in place of one per tile.
+pub fn create(world: &mut World, chunk: (i32, i32), tiles: &[((i32, i32), MapTile)]) -> Entity {
+    let image = world.entry::<TileAtlases>()
+        .or_insert_with(TileAtlases::default)
+        .compose(chunk, tiles);
+    let (width, height) = image.dimensions();
+
+    let sprite_sheet = {
+        let loader = world.read_resource::<Loader>();
+        let builder = TextureBuilder::new()
+            .with_kind(Kind::D2(width, height, 1, 1))
+            .with_view_kind(ViewKind::D2)
+            .with_data_width(width)
+            .with_data_height(height)
+            .with_sampler_info(SamplerInfo::new(Filter::Nearest, WrapMode::Clamp))
+            .with_raw_data(image.into_raw(), Format::Rgba8Srgb);
+        let texture = loader.load_from_data(TextureData(builder), (), &world.read_resource::<AssetStorage<Texture>>());
+
+        let sprite = Sprite::from_pixel_values(width, height, width, height, 0, 0, [0.0, 0.0], false, false);
+        loader.load_from_data(SpriteSheet {
+            texture,
+            sprites: vec![sprite],
+        }, (), &world.read_resource::<AssetStorage<SpriteSheet>>())
+    };
+
+    // Centred on the middle of the chunk's cells, and as low as the tiles themselves.
+    let middle = (CHUNK_SIZE - 1) as f32 / 2.0;
+    let mut transform = Transform::default();
+    transform.set_translation_xyz(
+        CELL_SIZE * (chunk.0 as f32 * CHUNK_SIZE as f32 + middle),
+        CELL_SIZE * (chunk.1 as f32 * CHUNK_SIZE as f32 + middle) - TILE_DROP,
+        CHUNK_Z,
+    );
+
+    world.create_entity()
+        .with(SpriteRender {
+            sprite_sheet,
+            sprite_number: 0,
+        })
+        .with(transform)
+        .build()
+}
@@ -1,10 +1,34 @@
 use core::fmt;
 
+use serde::{Deserialize, Serialize};
+
+#[allow(clippy::module_inception)]
 pub mod tile;
+pub mod animation;
+pub mod chunk;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum TileClass {
-    GreenPatch
+    GreenPatch,
+    Water,
+    Flowers,
+    // A storage terminal, opened by talking to it.
+    Pc,
+    // Marks a warp to another map.
+    Door,
+    Floor
+}
+
+impl TileClass {
+    // Grass cells roll for wild encounters when an actor finishes a step onto them.
+    pub fn is_grass(&self) -> bool {
+        matches!(self, TileClass::GreenPatch)
+    }
+
+    // Solid cells block movement and line of sight.
+    pub fn is_solid(&self) -> bool {
+        matches!(self, TileClass::Water | TileClass::Pc)
+    }
 }
 
 impl fmt::Display for TileClass {
@@ -20,14 +44,35 @@ pub struct TileAttrs {
 }
 
 impl TileAttrs {
-    pub fn to_sprite_index(&self) -> usize {
-        if self.class.is_some() {
-            let class_index = match self.class.unwrap() {
-                TileClass::GreenPatch => 0
-            };
-            class_index + self.state
-        } else {
-            0
+    pub fn new(class: Option<TileClass>) -> Self {
+        TileAttrs {
+            class,
+            state: 0,
+        }
+    }
+
+    pub fn class(&self) -> Option<TileClass> {
+        self.class
+    }
+
+    pub fn set_state(&mut self, state: usize) {
+        self.state = state;
+    }
+
+    pub fn to_sprite_index(self) -> usize {
+        match self.class {
+            Some(class) => {
+                let class_index = match class {
+                    TileClass::GreenPatch => 0,
+                    TileClass::Water => 3,
+                    TileClass::Flowers => 6,
+                    TileClass::Pc => 10,
+                    TileClass::Door => 11,
+                    TileClass::Floor => 12
+                };
+                class_index + self.state
+            }
+            None => 0,
         }
     }
 }
\ No newline at end of file
@@ -2,52 +2,62 @@ use amethyst::{
     prelude::*,
     core::{
         transform::Transform,
-        math::Vector3,
+        math::Vector2,
     },
-    ecs::{Component, DenseVecStorage},
+    ecs::{Component, DenseVecStorage, Entity},
     renderer::{SpriteSheet, SpriteRender},
     assets::Handle,
 };
 
-use crate::entity::tile::{TileAttrs};
+use crate::entity::tile::{TileAttrs, TileClass, animation::{TileAnimations, TileAnimator}};
+use crate::entity::actor::world_position;
 use crate::utils::resolve;
 
+// How far below the actors' position tiles are drawn, in pixels.
+pub const TILE_DROP: f32 = 12.0;
+
 #[derive(Clone)]
 pub struct Tile {
     pub attrs: TileAttrs,
     pub sheet: Handle<SpriteSheet>,
+    pub grid_pos: Vector2<i32>,
 }
 
 impl Tile {
-    pub fn create(world: &mut World, tileset: String) {
+    pub fn create(world: &mut World, tileset: String, class: Option<TileClass>, grid_pos: Vector2<i32>) -> Entity {
         let tileset_str = "tiles/".to_string() + tileset.to_string().as_str();
         let sheet = resolve::load_spritesheet_handle(world, tileset_str);
         let mut tile = Tile {
             sheet,
-            attrs: TileAttrs {
-                class: None,
-                state: 0,
-            },
+            attrs: TileAttrs::new(class),
+            grid_pos,
         };
 
-        tile.init(world);
+        tile.init(world)
     }
 
-    fn init(&mut self, world: &mut World) {
+    fn init(&mut self, world: &mut World) -> Entity {
         let sprite = SpriteRender {
             sprite_sheet: self.sheet.clone(),
             sprite_number: self.attrs.to_sprite_index(),
         };
 
+        // Tiles sit a little lower than the actors on them, under their feet.
+        let (x, y) = world_position(self.grid_pos);
         let mut transform = Transform::default();
-        transform.set_translation_xyz(320.0, 296.0, 1.0);
-        transform.set_scale(Vector3::new(2.0, 2.0, 1.0));
+        transform.set_translation_xyz(x, y - TILE_DROP, 1.0);
+
+        let animator = {
+            let animations = world.read_resource::<TileAnimations>();
+            TileAnimator::new(self.attrs.class().and_then(|c| animations.looping.get(&c).cloned()))
+        };
 
         world.create_entity()
             .with(sprite)
             .with(self.clone())
             .with(transform)
-            .build();
+            .with(animator)
+            .build()
     }
 }
 
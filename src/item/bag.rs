@@ -0,0 +1,191 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::item::{ItemData, Pocket};
+use crate::pokemon::library::Library;
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ItemStack {
+    pub id: String,
+    pub quantity: u16,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SortOrder {
+    Name,
+    Quantity,
+}
+
+impl SortOrder {
+    pub fn next(&self) -> Self {
+        match self {
+            SortOrder::Name => SortOrder::Quantity,
+            SortOrder::Quantity => SortOrder::Name,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortOrder::Name => "NAME",
+            SortOrder::Quantity => "AMOUNT",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum BagError {
+    UnknownItem,
+    // The stack is already at the pocket's limit.
+    Full,
+    NotEnough,
+}
+
+// The player's inventory. Items are kept in the pocket their data names, in the order they
+// were picked up until the pocket is sorted.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Bag {
+    pockets: BTreeMap<Pocket, Vec<ItemStack>>,
+}
+
+impl Bag {
+    pub fn pocket(&self, pocket: Pocket) -> &[ItemStack] {
+        self.pockets.get(&pocket).map(|p| p.as_slice()).unwrap_or(&[])
+    }
+
+    pub fn count(&self, library: &Library, id: &str) -> u16 {
+        library.get_item(id)
+            .and_then(|item| self.pocket(item.pocket).iter().find(|s| s.id == id))
+            .map(|s| s.quantity)
+            .unwrap_or(0)
+    }
+
+    // How many more of the item fit in the bag.
+    pub fn room_for(&self, library: &Library, id: &str) -> u16 {
+        library.get_item(id)
+            .map(|item| item.pocket.stack_limit().saturating_sub(self.count(library, id)))
+            .unwrap_or(0)
+    }
+
+    pub fn add(&mut self, library: &Library, id: &str, quantity: u16) -> Result<(), BagError> {
+        let item = library.get_item(id).ok_or(BagError::UnknownItem)?;
+        if quantity > self.room_for(library, id) {
+            return Err(BagError::Full);
+        }
+
+        let pocket = self.pockets.entry(item.pocket).or_default();
+        match pocket.iter_mut().find(|s| s.id == id) {
+            Some(stack) => stack.quantity += quantity,
+            None => pocket.push(ItemStack { id: id.to_string(), quantity }),
+        }
+
+        Ok(())
+    }
+
+    pub fn remove(&mut self, library: &Library, id: &str, quantity: u16) -> Result<(), BagError> {
+        let item = library.get_item(id).ok_or(BagError::UnknownItem)?;
+        let pocket = self.pockets.get_mut(&item.pocket).ok_or(BagError::NotEnough)?;
+        let index = pocket.iter().position(|s| s.id == id).ok_or(BagError::NotEnough)?;
+
+        if pocket[index].quantity < quantity {
+            return Err(BagError::NotEnough);
+        }
+
+        pocket[index].quantity -= quantity;
+        if pocket[index].quantity == 0 {
+            pocket.remove(index);
+        }
+
+        Ok(())
+    }
+
+    pub fn sort(&mut self, library: &Library, pocket: Pocket, order: SortOrder) {
+        let name = |stack: &ItemStack| library.get_item(&stack.id)
+            .map(|item: &ItemData| item.name.clone())
+            .unwrap_or_else(|| stack.id.clone());
+
+        if let Some(stacks) = self.pockets.get_mut(&pocket) {
+            match order {
+                SortOrder::Name => stacks.sort_by_key(|s| name(s)),
+                SortOrder::Quantity => stacks.sort_by(|a, b| b.quantity.cmp(&a.quantity).then_with(|| name(a).cmp(&name(b)))),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(bag: &Bag, pocket: Pocket) -> Vec<&str> {
+        bag.pocket(pocket).iter().map(|s| s.id.as_str()).collect()
+    }
+
+    fn bag(library: &Library, items: &[(&str, u16)]) -> Bag {
+        let mut bag = Bag::default();
+        for (id, quantity) in items {
+            bag.add(library, id, *quantity).unwrap();
+        }
+        bag
+    }
+
+    #[test]
+    fn items_keep_the_order_they_were_picked_up_in() {
+        let library = Library::bundled();
+        let bag = bag(&library, &[("super-potion", 1), ("ultra-ball", 2), ("antidote", 3), ("poke-ball", 4)]);
+
+        assert_eq!(ids(&bag, Pocket::Medicine), vec!["super-potion", "antidote"]);
+        assert_eq!(ids(&bag, Pocket::Balls), vec!["ultra-ball", "poke-ball"]);
+    }
+
+    #[test]
+    fn sorting_by_name_is_alphabetical() {
+        let library = Library::bundled();
+        let mut bag = bag(&library, &[("super-potion", 1), ("antidote", 5), ("potion", 3), ("full-heal", 5)]);
+
+        bag.sort(&library, Pocket::Medicine, SortOrder::Name);
+        assert_eq!(ids(&bag, Pocket::Medicine), vec!["antidote", "full-heal", "potion", "super-potion"]);
+    }
+
+    #[test]
+    fn sorting_by_quantity_puts_the_most_first_and_breaks_ties_by_name() {
+        let library = Library::bundled();
+        let mut bag = bag(&library, &[("super-potion", 1), ("potion", 5), ("antidote", 3), ("full-heal", 5)]);
+
+        bag.sort(&library, Pocket::Medicine, SortOrder::Quantity);
+        assert_eq!(ids(&bag, Pocket::Medicine), vec!["full-heal", "potion", "antidote", "super-potion"]);
+    }
+
+    #[test]
+    fn sorting_a_pocket_leaves_the_others_alone() {
+        let library = Library::bundled();
+        let mut bag = bag(&library, &[
+            ("super-potion", 1), ("ultra-ball", 1), ("antidote", 2), ("poke-ball", 9), ("great-ball", 5),
+        ]);
+
+        bag.sort(&library, Pocket::Medicine, SortOrder::Name);
+        assert_eq!(ids(&bag, Pocket::Medicine), vec!["antidote", "super-potion"]);
+        assert_eq!(ids(&bag, Pocket::Balls), vec!["ultra-ball", "poke-ball", "great-ball"]);
+
+        bag.sort(&library, Pocket::Balls, SortOrder::Quantity);
+        assert_eq!(ids(&bag, Pocket::Balls), vec!["poke-ball", "great-ball", "ultra-ball"]);
+        assert_eq!(ids(&bag, Pocket::Medicine), vec!["antidote", "super-potion"]);
+
+        // Sorting an empty pocket does nothing.
+        bag.sort(&library, Pocket::Berries, SortOrder::Name);
+        assert!(bag.pocket(Pocket::Berries).is_empty());
+    }
+
+    #[test]
+    fn items_picked_up_after_sorting_go_last() {
+        let library = Library::bundled();
+        let mut bag = bag(&library, &[("potion", 1), ("antidote", 1)]);
+        bag.sort(&library, Pocket::Medicine, SortOrder::Name);
+
+        // New items go at the end until the pocket is sorted again.
+        bag.add(&library, "awakening", 1).unwrap();
+        assert_eq!(ids(&bag, Pocket::Medicine), vec!["antidote", "potion", "awakening"]);
+        bag.sort(&library, Pocket::Medicine, SortOrder::Name);
+        assert_eq!(ids(&bag, Pocket::Medicine), vec!["antidote", "awakening", "potion"]);
+    }
+}
@@ -0,0 +1,206 @@
+pub mod bag;
+pub mod shop;
+
+use serde::{Deserialize, Serialize};
+
+use crate::pokemon::{evolution::{self, EvolutionTrigger}, library::Library, MoveSlot, Pokemon, Status};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum Pocket {
+    Items,
+    Medicine,
+    Balls,
+    Tms,
+    Berries,
+    KeyItems,
+}
+
+impl Pocket {
+    pub const ALL: [Pocket; 6] = [
+        Pocket::Items,
+        Pocket::Medicine,
+        Pocket::Balls,
+        Pocket::Tms,
+        Pocket::Berries,
+        Pocket::KeyItems,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Pocket::Items => "ITEMS",
+            Pocket::Medicine => "MEDICINE",
+            Pocket::Balls => "POKE BALLS",
+            Pocket::Tms => "TMs",
+            Pocket::Berries => "BERRIES",
+            Pocket::KeyItems => "KEY ITEMS",
+        }
+    }
+
+    // How many of a single item fit in the bag.
+    pub fn stack_limit(&self) -> u16 {
+        match self {
+            Pocket::KeyItems => 1,
+            _ => 999,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ItemEffect {
+    // Restores up to the given amount of HP. u16::MAX heals fully.
+    Heal(u16),
+    // Cures the given status, or any status when None.
+    Cure(Option<Status>),
+    // Brings a fainted pokemon back with half of its HP.
+    Revive,
+    // Restores the given amount of PP to every move.
+    RestorePp(u8),
+    // Thrown at wild pokemon, with a catch rate multiplier.
+    Ball(f32),
+    // Teaches the move in `ItemData::teaches`.
+    Teach,
+    // Raises the level by one.
+    LevelUp,
+    // Makes species with an `EvolutionMethod::Item` for this item evolve.
+    Evolve,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemData {
+    pub id: String,
+    pub name: String,
+    pub pocket: Pocket,
+    // Price at the Poke Mart. Items sell for half of it; 0 means it can't be sold.
+    pub price: u32,
+    pub description: String,
+    #[serde(default)]
+    pub effect: Option<ItemEffect>,
+    // Whether the item can be used from the bag in the overworld and in battle.
+    #[serde(default)]
+    pub field: bool,
+    #[serde(default)]
+    pub battle: bool,
+    #[serde(default)]
+    pub teaches: Option<String>,
+}
+
+impl ItemData {
+    // Items that are used on a member of the party.
+    pub fn needs_target(&self) -> bool {
+        match self.effect {
+            Some(ItemEffect::Ball(_)) | None => false,
+            Some(_) => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ItemOutcome {
+    Healed(u16),
+    Cured,
+    Revived,
+    PpRestored,
+    Learned(String),
+    // The new level, and the names of the moves learnt at it.
+    LevelUp { level: u8, learnt: Vec<String> },
+    // The pokemon is ready to evolve into the given species. It is left unchanged, for the
+    // evolution sequence to evolve it.
+    Evolves(String),
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ItemError {
+    // The item would not do anything to this pokemon.
+    NoEffect,
+    // The pokemon already knows four moves, or can't learn the move at all.
+    CantLearn,
+}
+
+// Uses an item on a pokemon, changing it only if the item had an effect.
+pub fn use_on(item: &ItemData, pokemon: &mut Pokemon, library: &Library) -> Result<ItemOutcome, ItemError> {
+    match item.effect {
+        Some(ItemEffect::Heal(amount)) => {
+            if pokemon.is_fainted() || pokemon.hp >= pokemon.stats.hp {
+                return Err(ItemError::NoEffect);
+            }
+
+            let healed = amount.min(pokemon.stats.hp - pokemon.hp);
+            pokemon.hp += healed;
+            Ok(ItemOutcome::Healed(healed))
+        }
+        Some(ItemEffect::Cure(status)) => {
+            let cures = match (pokemon.status, status) {
+                (Some(_), None) => true,
+                (Some(current), Some(cured)) => current == cured,
+                (None, _) => false,
+            };
+
+            if pokemon.is_fainted() || !cures {
+                return Err(ItemError::NoEffect);
+            }
+
+            pokemon.status = None;
+            pokemon.sleep_turns = 0;
+            Ok(ItemOutcome::Cured)
+        }
+        Some(ItemEffect::Revive) => {
+            if !pokemon.is_fainted() {
+                return Err(ItemError::NoEffect);
+            }
+
+            pokemon.hp = (pokemon.stats.hp / 2).max(1);
+            Ok(ItemOutcome::Revived)
+        }
+        Some(ItemEffect::RestorePp(amount)) => {
+            let mut restored = false;
+            for slot in &mut pokemon.moves {
+                if let Some(max) = library.get_move(&slot.id).map(|m| m.pp) {
+                    if slot.pp < max {
+                        slot.pp = slot.pp.saturating_add(amount).min(max);
+                        restored = true;
+                    }
+                }
+            }
+
+            if restored { Ok(ItemOutcome::PpRestored) } else { Err(ItemError::NoEffect) }
+        }
+        Some(ItemEffect::Teach) => {
+            let mv = item.teaches.as_ref()
+                .and_then(|id| library.get_move(id))
+                .ok_or(ItemError::NoEffect)?;
+
+            let compatible = library.get_species(&pokemon.species)
+                .map(|s| s.machines.contains(&mv.id))
+                .unwrap_or(false);
+
+            if pokemon.moves.iter().any(|slot| slot.id == mv.id) {
+                return Err(ItemError::NoEffect);
+            }
+
+            if !compatible || pokemon.moves.len() >= 4 {
+                return Err(ItemError::CantLearn);
+            }
+
+            pokemon.moves.push(MoveSlot { id: mv.id.clone(), pp: mv.pp });
+            Ok(ItemOutcome::Learned(mv.name.clone()))
+        }
+        Some(ItemEffect::LevelUp) => {
+            if pokemon.is_fainted() {
+                return Err(ItemError::NoEffect);
+            }
+
+            let learnt = pokemon.level_up(library).ok_or(ItemError::NoEffect)?;
+            pokemon.friendship = pokemon.friendship.saturating_add(5);
+            Ok(ItemOutcome::LevelUp {
+                level: pokemon.level,
+                learnt: learnt.iter()
+                    .filter_map(|id| library.get_move(id).map(|m| m.name.clone()))
+                    .collect(),
+            })
+        }
+        Some(ItemEffect::Evolve) => evolution::find(library, pokemon, EvolutionTrigger::Item(&item.id))
+            .map(|evolution| ItemOutcome::Evolves(evolution.into.clone()))
+            .ok_or(ItemError::NoEffect),
+        Some(ItemEffect::Ball(_)) | None => Err(ItemError::NoEffect),
+    }
+}
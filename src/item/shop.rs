@@ -0,0 +1,204 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::item::{bag::{Bag, BagError}, ItemData, Pocket};
+use crate::pokemon::library::Library;
+use crate::utils::data::{self, DataError};
+
+// The most money the player can carry. Anything earned beyond it is lost.
+pub const MAX_MONEY: u32 = 999_999;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShopData {
+    pub id: String,
+    // Items for sale, in the order they are listed.
+    pub items: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct Shops {
+    shops: HashMap<String, ShopData>,
+}
+
+impl Shops {
+    pub fn load(path: &Path) -> Result<Self, DataError> {
+        let shops: Vec<ShopData> = data::load_ron(path)?;
+
+        Ok(Shops {
+            shops: shops.into_iter().map(|s| (s.id.clone(), s)).collect(),
+        })
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ShopData> {
+        self.shops.get(id)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TransactionError {
+    UnknownItem,
+    NoQuantity,
+    NotEnoughMoney,
+    // The bag can't hold that many more of the item.
+    BagFull,
+    NotEnoughItems,
+    // Key items and items without a price can't be sold.
+    CantSell,
+}
+
+// The bag and money after a transaction.
+#[derive(Debug, Clone)]
+pub struct Transaction {
+    pub bag: Bag,
+    pub money: u32,
+}
+
+pub fn sell_price(item: &ItemData) -> u32 {
+    item.price / 2
+}
+
+pub fn can_sell(item: &ItemData) -> bool {
+    item.pocket != Pocket::KeyItems && sell_price(item) > 0
+}
+
+pub fn buy(library: &Library, bag: &Bag, money: u32, id: &str, quantity: u16) -> Result<Transaction, TransactionError> {
+    let item = library.get_item(id).ok_or(TransactionError::UnknownItem)?;
+    if quantity == 0 {
+        return Err(TransactionError::NoQuantity);
+    }
+
+    // A cost too large to count is more than anyone can carry.
+    let cost = item.price.checked_mul(quantity as u32).ok_or(TransactionError::NotEnoughMoney)?;
+    if cost > money {
+        return Err(TransactionError::NotEnoughMoney);
+    }
+
+    let mut bag = bag.clone();
+    bag.add(library, id, quantity).map_err(|e| match e {
+        BagError::UnknownItem => TransactionError::UnknownItem,
+        BagError::Full | BagError::NotEnough => TransactionError::BagFull,
+    })?;
+
+    Ok(Transaction { bag, money: money - cost })
+}
+
+pub fn sell(library: &Library, bag: &Bag, money: u32, id: &str, quantity: u16) -> Result<Transaction, TransactionError> {
+    let item = library.get_item(id).ok_or(TransactionError::UnknownItem)?;
+    if quantity == 0 {
+        return Err(TransactionError::NoQuantity);
+    }
+
+    if !can_sell(item) {
+        return Err(TransactionError::CantSell);
+    }
+
+    let mut bag = bag.clone();
+    bag.remove(library, id, quantity).map_err(|_| TransactionError::NotEnoughItems)?;
+
+    let earned = sell_price(item).saturating_mul(quantity as u32);
+    Ok(Transaction { bag, money: money.saturating_add(earned).min(MAX_MONEY) })
+}
+
+// The most of an item that can be bought at once, limited by money and bag space.
+pub fn max_buy(library: &Library, bag: &Bag, money: u32, id: &str) -> u16 {
+    let item = match library.get_item(id) {
+        Some(item) => item,
+        None => return 0,
+    };
+
+    // Free items are only limited by the bag.
+    let affordable = money.checked_div(item.price).unwrap_or(u32::MAX);
+    (affordable.min(bag.room_for(library, id) as u32)) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bag_with(library: &Library, id: &str, quantity: u16) -> Bag {
+        let mut bag = Bag::default();
+        bag.add(library, id, quantity).unwrap();
+        bag
+    }
+
+    #[test]
+    fn buying_charges_the_price_of_every_item() {
+        let library = Library::bundled();
+        let transaction = buy(&library, &Bag::default(), 1000, "potion", 3).unwrap();
+
+        assert_eq!(transaction.money, 100);
+        assert_eq!(transaction.bag.count(&library, "potion"), 3);
+    }
+
+    #[test]
+    fn buying_needs_enough_money() {
+        let library = Library::bundled();
+        assert_eq!(buy(&library, &Bag::default(), 599, "potion", 2).unwrap_err(), TransactionError::NotEnoughMoney);
+        assert_eq!(buy(&library, &Bag::default(), 600, "potion", 2).unwrap().money, 0);
+        assert_eq!(buy(&library, &Bag::default(), 600, "potion", 0).unwrap_err(), TransactionError::NoQuantity);
+        assert_eq!(buy(&library, &Bag::default(), 600, "missingno", 1).unwrap_err(), TransactionError::UnknownItem);
+    }
+
+    #[test]
+    fn buying_stops_at_the_stack_limit() {
+        let library = Library::bundled();
+        let bag = bag_with(&library, "potion", 998);
+
+        assert_eq!(buy(&library, &bag, 10_000, "potion", 2).unwrap_err(), TransactionError::BagFull);
+        assert_eq!(buy(&library, &bag, 10_000, "potion", 1).unwrap().bag.count(&library, "potion"), 999);
+        assert_eq!(bag.count(&library, "potion"), 998);
+    }
+
+    #[test]
+    fn costs_too_large_to_count_are_refused() {
+        let mut library = Library::bundled();
+        let mut item = library.get_item("nugget").unwrap().clone();
+        item.id = "gold-bar".to_string();
+        item.price = 3_000_000_000;
+        library.insert_item(item);
+
+        assert_eq!(buy(&library, &Bag::default(), MAX_MONEY, "gold-bar", 2).unwrap_err(), TransactionError::NotEnoughMoney);
+        assert_eq!(buy(&library, &Bag::default(), u32::MAX, "gold-bar", 999).unwrap_err(), TransactionError::NotEnoughMoney);
+
+        let bag = bag_with(&library, "gold-bar", 999);
+        assert_eq!(sell(&library, &bag, 0, "gold-bar", 999).unwrap().money, MAX_MONEY);
+    }
+
+    #[test]
+    fn the_most_to_buy_is_limited_by_money_and_room() {
+        let library = Library::bundled();
+        assert_eq!(max_buy(&library, &Bag::default(), 1000, "potion"), 3);
+        assert_eq!(max_buy(&library, &bag_with(&library, "potion", 998), 10_000, "potion"), 1);
+        assert_eq!(max_buy(&library, &Bag::default(), 299, "potion"), 0);
+        assert_eq!(max_buy(&library, &Bag::default(), 1000, "missingno"), 0);
+    }
+
+    #[test]
+    fn selling_pays_half_the_price() {
+        let library = Library::bundled();
+        let bag = bag_with(&library, "potion", 3);
+        let transaction = sell(&library, &bag, 100, "potion", 2).unwrap();
+
+        assert_eq!(transaction.money, 400);
+        assert_eq!(transaction.bag.count(&library, "potion"), 1);
+        assert_eq!(sell(&library, &bag, 100, "potion", 4).unwrap_err(), TransactionError::NotEnoughItems);
+    }
+
+    #[test]
+    fn selling_caps_the_money() {
+        let library = Library::bundled();
+        let bag = bag_with(&library, "nugget", 1);
+        assert_eq!(sell(&library, &bag, MAX_MONEY - 10, "nugget", 1).unwrap().money, MAX_MONEY);
+    }
+
+    #[test]
+    fn key_items_cant_be_sold() {
+        let library = Library::bundled();
+        let bag = bag_with(&library, "town-map", 1);
+        assert_eq!(sell(&library, &bag, 0, "town-map", 1).unwrap_err(), TransactionError::CantSell);
+    }
+}
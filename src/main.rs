@@ -2,11 +2,24 @@ mod entity;
 mod state;
 mod system;
 mod utils;
+mod pokemon;
+mod battle;
+mod map;
+mod clock;
+mod trainer;
+mod story;
+mod pokedex;
+mod item;
+mod save;
+mod audio;
 
 
 
+#[cfg(feature = "gamepad")]
+use amethyst::input::SdlEventsSystemDesc;
 use amethyst::{
     prelude::*,
+    audio::AudioBundle,
     renderer::{
         plugins::{RenderFlat2D, RenderToWindow},
         types::DefaultBackend,
@@ -21,7 +34,7 @@ use amethyst::{
 use crate::state::game::GameState;
 use crate::system::bundle::GameBundle;
 use std::time::Duration;
-use crate::utils::resolve;
+use crate::utils::{controls, resolve};
 
 fn main() -> amethyst::Result<()> {
     amethyst::start_logger(Default::default());
@@ -30,12 +43,25 @@ fn main() -> amethyst::Result<()> {
     let display_config_path = app_root.join("./config/display.ron");
 
     let assets_dir = app_root.join("./assets");
-    let input_binding_config = app_root.join("./config/input.ron");
+    // Bindings changed on the controls screen take the place of the shipped ones.
+    let input_binding_config = if controls::path().exists() {
+        controls::path()
+    } else {
+        app_root.join("./config/input.ron")
+    };
 
-    let game_data = GameDataBuilder::default()
+    let game_data = GameDataBuilder::default();
+
+    // Gamepad buttons are read through SDL.
+    #[cfg(feature = "gamepad")]
+    let game_data = game_data
+        .with_thread_local_desc(SdlEventsSystemDesc::<StringBindings>::default());
+
+    let game_data = game_data
         .with_bundle(TransformBundle::new())?
         .with_bundle(InputBundle::<StringBindings>::new()
             .with_bindings_from_file(input_binding_config)?)?
+        .with_bundle(AudioBundle::default())?
         .with_bundle(GameBundle)?
         .with_bundle(UiBundle::<StringBindings>::new())?
         .with_bundle(
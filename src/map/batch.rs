@@ -0,0 +1,73 @@
+use std::collections::HashSet;
+
+use crate::entity::tile::TileClass;
+use crate::map::stream::MapTile;
+
+// The tiles of a chunk, split into the ones drawn together into one image for the whole
+// chunk and the ones that stay render entities of their own.
+#[derive(Debug, Default)]
+pub struct ChunkBatch {
+    pub baked: Vec<((i32, i32), MapTile)>,
+    pub loose: Vec<((i32, i32), MapTile)>,
+}
+
+// Takes the tiles of a chunk bottom layer first. Tiles that animate stay loose, and so do the
+// tiles stacked over them on the same cell, which have to be drawn over them.
+pub fn split(tiles: Vec<((i32, i32), MapTile)>, animates: impl Fn(TileClass) -> bool) -> ChunkBatch {
+    let mut batch = ChunkBatch::default();
+    let mut loose_cells = HashSet::new();
+
+    for (cell, tile) in tiles {
+        if animates(tile.class) || loose_cells.contains(&cell) {
+            loose_cells.insert(cell);
+            batch.loose.push((cell, tile));
+        } else {
+            batch.baked.push((cell, tile));
+        }
+    }
+
+    batch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tile(class: TileClass) -> MapTile {
+        MapTile { tileset: "tileset-bw".to_string(), class }
+    }
+
+    fn animates(class: TileClass) -> bool {
+        class == TileClass::Water || class == TileClass::GreenPatch
+    }
+
+    #[test]
+    fn static_tiles_are_baked_and_animated_ones_stay_loose() {
+        let batch = split(vec![
+            ((0, 0), tile(TileClass::Floor)),
+            ((1, 0), tile(TileClass::Water)),
+            ((2, 0), tile(TileClass::Door)),
+        ], animates);
+
+        assert_eq!(batch.baked, vec![((0, 0), tile(TileClass::Floor)), ((2, 0), tile(TileClass::Door))]);
+        assert_eq!(batch.loose, vec![((1, 0), tile(TileClass::Water))]);
+    }
+
+    #[test]
+    fn tiles_over_an_animated_tile_stay_loose() {
+        let batch = split(vec![
+            ((0, 0), tile(TileClass::Floor)),
+            ((1, 0), tile(TileClass::Floor)),
+            ((0, 0), tile(TileClass::GreenPatch)),
+            ((0, 0), tile(TileClass::Pc)),
+            ((1, 0), tile(TileClass::Pc)),
+        ], animates);
+
+        assert_eq!(batch.baked, vec![
+            ((0, 0), tile(TileClass::Floor)),
+            ((1, 0), tile(TileClass::Floor)),
+            ((1, 0), tile(TileClass::Pc)),
+        ]);
+        assert_eq!(batch.loose, vec![((0, 0), tile(TileClass::GreenPatch)), ((0, 0), tile(TileClass::Pc))]);
+    }
+}
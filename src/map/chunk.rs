@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+
+// Chunks are square blocks of cells that are shown and culled as a whole.
+pub const CHUNK_SIZE: i32 = 16;
+const CHUNK_CELLS: usize = (CHUNK_SIZE * CHUNK_SIZE) as usize;
+
+// A layer holds one tile per cell, as an index into the palette plus one. Zero is no tile.
+type Layer = Vec<u16>;
+
+#[derive(Debug, Clone, Default)]
+struct Chunk {
+    layers: Vec<Layer>,
+}
+
+// The tiles of a map, stored in chunks of compact index arrays. Cells can hold several
+// tiles, stacked in layers in the order they were inserted.
+#[derive(Debug, Clone)]
+pub struct TileGrid<T> {
+    palette: Vec<T>,
+    chunks: HashMap<(i32, i32), Chunk>,
+    len: usize,
+}
+
+impl<T> Default for TileGrid<T> {
+    fn default() -> Self {
+        TileGrid {
+            palette: Vec::new(),
+            chunks: HashMap::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<T: PartialEq> TileGrid<T> {
+    pub fn new() -> Self {
+        TileGrid::default()
+    }
+
+    pub fn chunk_of(cell: (i32, i32)) -> (i32, i32) {
+        (cell.0.div_euclid(CHUNK_SIZE), cell.1.div_euclid(CHUNK_SIZE))
+    }
+
+    fn index_of(cell: (i32, i32)) -> usize {
+        (cell.0.rem_euclid(CHUNK_SIZE) + cell.1.rem_euclid(CHUNK_SIZE) * CHUNK_SIZE) as usize
+    }
+
+    fn cell_of(chunk: (i32, i32), index: usize) -> (i32, i32) {
+        let index = index as i32;
+        (chunk.0 * CHUNK_SIZE + index % CHUNK_SIZE, chunk.1 * CHUNK_SIZE + index / CHUNK_SIZE)
+    }
+
+    // Puts the tile on the first layer that is free at the cell.
+    pub fn insert(&mut self, cell: (i32, i32), tile: T) {
+        let code = match self.palette.iter().position(|t| *t == tile) {
+            Some(i) => i + 1,
+            None => {
+                self.palette.push(tile);
+                self.palette.len()
+            }
+        } as u16;
+
+        let index = TileGrid::<T>::index_of(cell);
+        let chunk = self.chunks.entry(TileGrid::<T>::chunk_of(cell)).or_default();
+        match chunk.layers.iter_mut().find(|layer| layer[index] == 0) {
+            Some(layer) => layer[index] = code,
+            None => {
+                let mut layer = vec![0; CHUNK_CELLS];
+                layer[index] = code;
+                chunk.layers.push(layer);
+            }
+        }
+        self.len += 1;
+    }
+
+    fn decode(&self, code: u16) -> Option<&T> {
+        match code {
+            0 => None,
+            code => self.palette.get(code as usize - 1),
+        }
+    }
+
+    // The tiles on a cell, bottom layer first.
+    pub fn at(&self, cell: (i32, i32)) -> impl Iterator<Item = &T> + '_ {
+        let index = TileGrid::<T>::index_of(cell);
+        self.chunks.get(&TileGrid::<T>::chunk_of(cell))
+            .into_iter()
+            .flat_map(move |chunk| chunk.layers.iter().filter_map(move |layer| self.decode(layer[index])))
+    }
+
+    // The tiles of a chunk with their cells, bottom layer first.
+    pub fn chunk_tiles(&self, chunk: (i32, i32)) -> Vec<((i32, i32), &T)> {
+        self.chunks.get(&chunk)
+            .map(|c| c.layers.iter()
+                .flat_map(|layer| layer.iter().enumerate())
+                .filter_map(|(index, code)| self.decode(*code).map(|tile| (TileGrid::<T>::cell_of(chunk, index), tile)))
+                .collect())
+            .unwrap_or_default()
+    }
+
+    // The chunks holding tiles that overlap the cells from `from` to `to`, corners included.
+    pub fn chunks_in(&self, from: (i32, i32), to: (i32, i32)) -> Vec<(i32, i32)> {
+        let (x0, y0) = TileGrid::<T>::chunk_of(from);
+        let (x1, y1) = TileGrid::<T>::chunk_of(to);
+        (x0..=x1)
+            .flat_map(|x| (y0..=y1).map(move |y| (x, y)))
+            .filter(|chunk| self.chunks.contains_key(chunk))
+            .collect()
+    }
+}
+
+// Figures reported by the map benchmark.
+#[allow(dead_code)]
+impl<T> TileGrid<T> {
+    pub fn tile_count(&self) -> usize {
+        self.len
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    // Memory taken by the index arrays, in bytes.
+    pub fn index_bytes(&self) -> usize {
+        self.chunks.values().map(|c| c.layers.len() * CHUNK_CELLS * std::mem::size_of::<u16>()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tiles(grid: &TileGrid<u32>, cell: (i32, i32)) -> Vec<u32> {
+        grid.at(cell).cloned().collect()
+    }
+
+    #[test]
+    fn cells_map_to_chunks_on_both_sides_of_zero() {
+        assert_eq!(TileGrid::<u32>::chunk_of((0, 0)), (0, 0));
+        assert_eq!(TileGrid::<u32>::chunk_of((15, 15)), (0, 0));
+        assert_eq!(TileGrid::<u32>::chunk_of((16, 0)), (1, 0));
+        assert_eq!(TileGrid::<u32>::chunk_of((-1, -1)), (-1, -1));
+        assert_eq!(TileGrid::<u32>::chunk_of((-16, -16)), (-1, -1));
+        assert_eq!(TileGrid::<u32>::chunk_of((-17, 16)), (-2, 1));
+    }
+
+    #[test]
+    fn tiles_on_either_side_of_a_chunk_border_stay_apart() {
+        let mut grid = TileGrid::new();
+        let cells = [(-17, 0), (-16, 0), (-1, 0), (0, 0), (15, 0), (16, 0), (0, -1), (0, 15), (0, 16), (-1, -1)];
+        for (i, cell) in cells.iter().enumerate() {
+            grid.insert(*cell, i as u32);
+        }
+
+        for (i, cell) in cells.iter().enumerate() {
+            assert_eq!(tiles(&grid, *cell), vec![i as u32], "at {:?}", cell);
+        }
+        assert!(tiles(&grid, (1, 0)).is_empty());
+        assert!(tiles(&grid, (-2, -1)).is_empty());
+        assert_eq!(grid.tile_count(), cells.len());
+    }
+
+    #[test]
+    fn chunk_tiles_come_back_at_their_cells() {
+        let mut grid = TileGrid::new();
+        grid.insert((-1, -1), 1);
+        grid.insert((-16, -16), 2);
+        grid.insert((-17, -1), 3);
+
+        assert_eq!(grid.chunk_tiles((-1, -1)), vec![((-16, -16), &2), ((-1, -1), &1)]);
+        assert_eq!(grid.chunk_tiles((-2, -1)), vec![((-17, -1), &3)]);
+        assert!(grid.chunk_tiles((0, 0)).is_empty());
+    }
+
+    #[test]
+    fn tiles_on_the_same_cell_stack_in_layers() {
+        let mut grid = TileGrid::new();
+        grid.insert((3, -3), 7);
+        grid.insert((3, -3), 8);
+        grid.insert((4, -3), 9);
+
+        assert_eq!(tiles(&grid, (3, -3)), vec![7, 8]);
+        assert_eq!(tiles(&grid, (4, -3)), vec![9]);
+        assert_eq!(grid.chunk_count(), 1);
+        assert_eq!(grid.index_bytes(), 2 * CHUNK_CELLS * 2);
+    }
+
+    #[test]
+    fn the_palette_holds_each_tile_once_and_past_a_byte() {
+        let mut grid = TileGrid::new();
+        for x in 0..CHUNK_SIZE {
+            grid.insert((x, 0), 5);
+        }
+        assert_eq!(grid.palette.len(), 1);
+
+        // More distinct tiles than fit in a byte still decode to themselves.
+        for i in 0..600 {
+            grid.insert((i % 40 - 20, i / 40 - 8), 1000 + i as u32);
+        }
+        assert_eq!(grid.palette.len(), 601);
+        for i in 0..600 {
+            assert!(tiles(&grid, (i % 40 - 20, i / 40 - 8)).contains(&(1000 + i as u32)));
+        }
+    }
+
+    #[test]
+    fn chunks_in_covers_the_corners_of_the_range() {
+        let mut grid = TileGrid::new();
+        for cell in &[(-20, -20), (-1, 0), (0, 0), (16, 16), (40, 40)] {
+            grid.insert(*cell, 1);
+        }
+
+        let mut chunks = grid.chunks_in((-17, -17), (16, 16));
+        chunks.sort();
+        assert_eq!(chunks, vec![(-2, -2), (-1, 0), (0, 0), (1, 1)]);
+        assert_eq!(grid.chunks_in((-1, 0), (-1, 0)), vec![(-1, 0)]);
+    }
+}
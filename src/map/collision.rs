@@ -0,0 +1,90 @@
+use std::collections::HashSet;
+
+// Grid cells that actors cannot walk or see through.
+#[derive(Debug, Clone, Default)]
+pub struct CollisionMap {
+    blocked: HashSet<(i32, i32)>,
+}
+
+impl CollisionMap {
+    pub fn new() -> Self {
+        CollisionMap::default()
+    }
+
+    pub fn block(&mut self, cell: (i32, i32)) {
+        self.blocked.insert(cell);
+    }
+
+    pub fn is_blocked(&self, cell: (i32, i32)) -> bool {
+        self.blocked.contains(&cell)
+    }
+
+    // Casts a ray from `origin` (exclusive) in steps of `delta` and returns the distance to
+    // `target` if it is within `range` and no blocked cell lies in between.
+    pub fn line_of_sight(&self, origin: (i32, i32), delta: (i32, i32), range: u32, target: (i32, i32)) -> Option<u32> {
+        if delta == (0, 0) {
+            return None;
+        }
+
+        for distance in 1..=range {
+            let cell = (origin.0 + delta.0 * distance as i32, origin.1 + delta.1 * distance as i32);
+            if cell == target {
+                return Some(distance);
+            }
+
+            if self.is_blocked(cell) {
+                return None;
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_in_range_are_seen_at_their_distance() {
+        let map = CollisionMap::new();
+        assert_eq!(map.line_of_sight((0, 0), (1, 0), 4, (3, 0)), Some(3));
+        assert_eq!(map.line_of_sight((2, 2), (0, -1), 4, (2, 1)), Some(1));
+    }
+
+    #[test]
+    fn blocked_cells_hide_what_is_behind_them() {
+        let mut map = CollisionMap::new();
+        map.block((2, 0));
+        assert_eq!(map.line_of_sight((0, 0), (1, 0), 4, (3, 0)), None);
+        // Cells before the block are still seen.
+        assert_eq!(map.line_of_sight((0, 0), (1, 0), 4, (1, 0)), Some(1));
+    }
+
+    #[test]
+    fn targets_at_exactly_the_range_are_seen() {
+        let map = CollisionMap::new();
+        assert_eq!(map.line_of_sight((0, 0), (0, 1), 4, (0, 4)), Some(4));
+    }
+
+    #[test]
+    fn targets_beyond_the_range_are_not_seen() {
+        let map = CollisionMap::new();
+        assert_eq!(map.line_of_sight((0, 0), (0, 1), 4, (0, 5)), None);
+        assert_eq!(map.line_of_sight((0, 0), (0, 1), 0, (0, 1)), None);
+    }
+
+    #[test]
+    fn targets_off_the_ray_are_not_seen() {
+        let map = CollisionMap::new();
+        assert_eq!(map.line_of_sight((0, 0), (1, 0), 4, (2, 1)), None);
+        assert_eq!(map.line_of_sight((0, 0), (1, 0), 4, (-2, 0)), None);
+    }
+
+    #[test]
+    fn a_zero_delta_sees_nothing() {
+        let map = CollisionMap::new();
+        assert_eq!(map.line_of_sight((0, 0), (0, 0), 4, (0, 0)), None);
+        assert_eq!(map.line_of_sight((0, 0), (0, 0), 4, (1, 0)), None);
+    }
+}
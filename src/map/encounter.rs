@@ -0,0 +1,181 @@
+use std::path::Path;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::clock::TimeOfDay;
+use crate::utils::data::{self, DataError};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncounterSlot {
+    pub species: String,
+    pub min_level: u8,
+    pub max_level: u8,
+    pub weight: u32,
+    // Periods of the day in which this slot can appear. Empty means always.
+    #[serde(default)]
+    pub times: Vec<TimeOfDay>,
+}
+
+impl EncounterSlot {
+    pub fn is_available(&self, time: TimeOfDay) -> bool {
+        time.is_any_of(&self.times)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct WildEncounter {
+    pub species: String,
+    pub level: u8,
+}
+
+// Encounter table of a single map. `rate` is the chance out of 256 that a step in grass
+// starts an encounter.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EncounterTable {
+    pub rate: u32,
+    pub slots: Vec<EncounterSlot>,
+}
+
+impl EncounterTable {
+    pub fn load(path: &Path) -> Result<Self, DataError> {
+        data::load_ron(path)
+    }
+
+    // Rolls for an encounter after a step in grass.
+    pub fn roll<R: Rng>(&self, time: TimeOfDay, rng: &mut R) -> Option<WildEncounter> {
+        if rng.gen_range(0, 256) >= self.rate {
+            return None;
+        }
+
+        self.pick(time, rng)
+    }
+
+    // Picks a slot by weight among the slots available at the given time.
+    pub fn pick<R: Rng>(&self, time: TimeOfDay, rng: &mut R) -> Option<WildEncounter> {
+        let slots: Vec<&EncounterSlot> = self.slots.iter()
+            .filter(|s| s.is_available(time) && s.weight > 0)
+            .collect();
+
+        let total: u32 = slots.iter().map(|s| s.weight).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut roll = rng.gen_range(0, total);
+        for slot in slots {
+            if roll < slot.weight {
+                let (min, max) = (slot.min_level.min(slot.max_level), slot.min_level.max(slot.max_level));
+                return Some(WildEncounter {
+                    species: slot.species.clone(),
+                    level: rng.gen_range(min as u16, max as u16 + 1) as u8,
+                });
+            }
+            roll -= slot.weight;
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn slot(species: &str, (min_level, max_level): (u8, u8), weight: u32, times: Vec<TimeOfDay>) -> EncounterSlot {
+        EncounterSlot {
+            species: species.to_string(),
+            min_level,
+            max_level,
+            weight,
+            times,
+        }
+    }
+
+    fn table() -> EncounterTable {
+        EncounterTable {
+            rate: 256,
+            slots: vec![
+                slot("pidgey", (2, 4), 60, vec![]),
+                slot("rattata", (3, 3), 30, vec![]),
+                slot("hoothoot", (5, 7), 10, vec![TimeOfDay::Night]),
+            ],
+        }
+    }
+
+    fn count(table: &EncounterTable, time: TimeOfDay, species: &str, rolls: usize) -> usize {
+        let mut rng = StdRng::seed_from_u64(26);
+        (0..rolls)
+            .filter_map(|_| table.pick(time, &mut rng))
+            .filter(|e| e.species == species)
+            .count()
+    }
+
+    #[test]
+    fn slots_are_picked_by_weight() {
+        let table = table();
+        let pidgey = count(&table, TimeOfDay::Night, "pidgey", 10_000);
+        let rattata = count(&table, TimeOfDay::Night, "rattata", 10_000);
+        let hoothoot = count(&table, TimeOfDay::Night, "hoothoot", 10_000);
+
+        assert_eq!(pidgey + rattata + hoothoot, 10_000);
+        assert!((5_700..6_300).contains(&pidgey), "pidgey: {}", pidgey);
+        assert!((2_700..3_300).contains(&rattata), "rattata: {}", rattata);
+        assert!((800..1_200).contains(&hoothoot), "hoothoot: {}", hoothoot);
+    }
+
+    #[test]
+    fn levels_stay_within_the_slot_bounds() {
+        let table = table();
+        let mut rng = StdRng::seed_from_u64(26);
+        let levels: Vec<u8> = (0..1_000)
+            .filter_map(|_| table.pick(TimeOfDay::Day, &mut rng))
+            .filter(|e| e.species == "pidgey")
+            .map(|e| e.level)
+            .collect();
+
+        assert!(levels.iter().all(|level| (2..=4).contains(level)));
+        assert!(levels.contains(&2));
+        assert!(levels.contains(&4));
+    }
+
+    #[test]
+    fn slots_only_appear_at_their_times() {
+        let table = table();
+        for time in [TimeOfDay::Morning, TimeOfDay::Day, TimeOfDay::Evening].iter() {
+            assert_eq!(count(&table, *time, "hoothoot", 1_000), 0);
+        }
+        assert!(count(&table, TimeOfDay::Night, "hoothoot", 1_000) > 0);
+    }
+
+    #[test]
+    fn no_slot_available_means_no_encounter() {
+        let table = EncounterTable {
+            rate: 256,
+            slots: vec![slot("hoothoot", (5, 7), 10, vec![TimeOfDay::Night])],
+        };
+        let mut rng = StdRng::seed_from_u64(26);
+        assert_eq!(table.roll(TimeOfDay::Day, &mut rng), None);
+    }
+
+    #[test]
+    fn the_rate_decides_whether_a_step_starts_an_encounter() {
+        let mut never = table();
+        never.rate = 0;
+        let mut rng = StdRng::seed_from_u64(26);
+        assert!((0..1_000).all(|_| never.roll(TimeOfDay::Day, &mut rng).is_none()));
+        assert!((0..1_000).all(|_| table().roll(TimeOfDay::Day, &mut rng).is_some()));
+    }
+
+    #[test]
+    fn the_same_seed_rolls_the_same_encounters() {
+        let table = table();
+        let roll = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..100).map(|_| table.roll(TimeOfDay::Night, &mut rng)).collect::<Vec<_>>()
+        };
+        assert_eq!(roll(7), roll(7));
+    }
+}
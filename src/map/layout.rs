@@ -0,0 +1,113 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::battle::weather::Weather;
+use crate::clock::TimeOfDay;
+use crate::entity::actor::ActorDirection;
+use crate::entity::tile::TileClass;
+use crate::utils::data::{self, DataError};
+
+// A rectangle of tiles of the same class, corners included.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TileArea {
+    pub class: TileClass,
+    pub from: (i32, i32),
+    pub to: (i32, i32),
+}
+
+impl TileArea {
+    pub fn cells(&self) -> impl Iterator<Item = (i32, i32)> {
+        let (x0, x1) = (self.from.0.min(self.to.0), self.from.0.max(self.to.0));
+        let (y0, y1) = (self.from.1.min(self.to.1), self.from.1.max(self.to.1));
+        (x0..=x1).flat_map(move |x| (y0..=y1).map(move |y| (x, y)))
+    }
+}
+
+// The cells a map covers, corners included.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Bounds {
+    pub from: (i32, i32),
+    pub to: (i32, i32),
+}
+
+impl Bounds {
+    pub fn contains(&self, cell: (i32, i32)) -> bool {
+        let (x0, x1) = (self.from.0.min(self.to.0), self.from.0.max(self.to.0));
+        let (y0, y1) = (self.from.1.min(self.to.1), self.from.1.max(self.to.1));
+        (x0..=x1).contains(&cell.0) && (y0..=y1).contains(&cell.1)
+    }
+}
+
+// A map joined to the edge of this one, whose cell (0, 0) lies at `offset` in this map's grid.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Connection {
+    pub map: String,
+    pub offset: (i32, i32),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum NpcKind {
+    // The sprite comes from the trainer data.
+    Trainer { id: String, sight_range: u32 },
+    Clerk { sprite: String, shop: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NpcPlacement {
+    pub kind: NpcKind,
+    pub cell: (i32, i32),
+    pub direction: ActorDirection,
+    // Periods of the day the npc is out in, checked when the map loads. Empty means always.
+    #[serde(default)]
+    pub times: Vec<TimeOfDay>,
+}
+
+// Stepping onto `cell` moves the player to `destination` on `map`, facing `direction`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Warp {
+    pub cell: (i32, i32),
+    pub map: String,
+    pub destination: (i32, i32),
+    pub direction: ActorDirection,
+}
+
+// Everything placed on a map, loaded from assets/data/maps.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MapLayout {
+    pub tileset: String,
+    #[serde(default)]
+    pub tiles: Vec<TileArea>,
+    #[serde(default)]
+    pub npcs: Vec<NpcPlacement>,
+    #[serde(default)]
+    pub warps: Vec<Warp>,
+    // Maps without bounds cover the whole grid and cannot be walked out of.
+    #[serde(default)]
+    pub bounds: Option<Bounds>,
+    #[serde(default)]
+    pub connections: Vec<Connection>,
+    // The track looped while the player is on the map, from assets/audio/music.
+    #[serde(default)]
+    pub music: Option<String>,
+    // Indoor maps are left out of the day and night tint.
+    #[serde(default)]
+    pub indoors: bool,
+    // Shown over the map, and carried into battles fought on it.
+    #[serde(default)]
+    pub weather: Option<Weather>,
+}
+
+impl MapLayout {
+    pub fn load(path: &Path) -> Result<Self, DataError> {
+        data::load_ron(path)
+    }
+
+    pub fn contains(&self, cell: (i32, i32)) -> bool {
+        self.bounds.map(|bounds| bounds.contains(cell)).unwrap_or(true)
+    }
+
+    pub fn warp_at(&self, cell: (i32, i32)) -> Option<&Warp> {
+        self.warps.iter().find(|warp| warp.cell == cell)
+    }
+}
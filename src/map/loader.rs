@@ -0,0 +1,246 @@
+use amethyst::{
+    prelude::*,
+    core::{math::Vector2, transform::Transform},
+    ecs::{Entity, Join},
+    renderer::camera::Camera,
+};
+
+use crate::audio::music::Music;
+use crate::clock::Clock;
+use crate::entity::actor::{npc::Npc, player::Player, CELL_SIZE};
+use crate::entity::tile::{animation::TileAnimations, chunk, tile::{Tile, TILE_DROP}};
+use crate::map::{
+    batch,
+    encounter::EncounterTable,
+    layout::{MapLayout, NpcKind},
+    stream::{MapStream, MapTile, StreamedMap},
+};
+use crate::state::Game;
+use crate::trainer::Trainers;
+use crate::utils::{resolve, screen::VirtualScreen};
+
+fn load_layout(id: &str) -> MapLayout {
+    MapLayout::load(&resolve::get_data_path(format!("maps/{}.ron", id).as_str()))
+        .unwrap_or_else(|e| {
+            eprintln!("failed to load map {}: {}", id, e);
+            MapLayout::default()
+        })
+}
+
+fn load_encounters(id: &str) -> EncounterTable {
+    let path = resolve::get_data_path(format!("encounters/{}.ron", id).as_str());
+    if path.exists() {
+        EncounterTable::load(&path).expect("failed to load encounter table")
+    } else {
+        EncounterTable::default()
+    }
+}
+
+fn spawn_npcs(world: &mut World, layout: &MapLayout, offset: (i32, i32)) -> Vec<Entity> {
+    let mut entities = Vec::new();
+
+    for npc in &layout.npcs {
+        if !world.read_resource::<Clock>().is_any_of(&npc.times) {
+            continue;
+        }
+
+        let cell = Vector2::new(npc.cell.0 + offset.0, npc.cell.1 + offset.1);
+        match &npc.kind {
+            NpcKind::Trainer { id, sight_range } => {
+                let sprite = world.read_resource::<Trainers>().get(id).map(|t| t.sprite.clone());
+                if let Some(sprite) = sprite {
+                    entities.push(Npc::create(world, sprite, cell, npc.direction, *sight_range, Some(id.clone())));
+                }
+            }
+            NpcKind::Clerk { sprite, shop } => {
+                entities.push(Npc::create_clerk(world, sprite.clone(), cell, npc.direction, shop.clone()));
+            }
+        }
+    }
+
+    entities
+}
+
+// Makes the first streamed map the current one, and streams in the maps connected to it.
+// Neighbors that are no longer connected are dropped along with their NPCs.
+fn connect(world: &mut World) {
+    let mut stream = world.remove::<MapStream>().unwrap_or_default();
+    let current = match stream.maps.first() {
+        Some(current) => current.layout.clone(),
+        None => {
+            world.insert(stream);
+            return;
+        }
+    };
+    let id = stream.maps[0].id.clone();
+
+    let mut maps = stream.maps.drain(..);
+    let mut kept = vec![maps.next().unwrap()];
+    let mut dropped = Vec::new();
+    for map in maps {
+        if current.connections.iter().any(|c| c.map == map.id && c.offset == map.offset) {
+            kept.push(map);
+        } else {
+            dropped.extend(map.npcs);
+        }
+    }
+    world.delete_entities(&dropped).expect("failed to delete map npcs");
+
+    for connection in &current.connections {
+        if kept.iter().any(|map| map.id == connection.map) {
+            continue;
+        }
+        let layout = load_layout(&connection.map);
+        let npcs = spawn_npcs(world, &layout, connection.offset);
+        kept.push(StreamedMap {
+            id: connection.map.clone(),
+            offset: connection.offset,
+            layout,
+            npcs,
+        });
+    }
+    stream.maps = kept;
+    let stale = stream.rebuild();
+    world.delete_entities(&stale).expect("failed to delete map tiles");
+
+    world.insert(stream);
+    world.insert(load_encounters(&id));
+    world.write_resource::<Music>().set_map_track(current.music.clone());
+    world.insert(current);
+    world.write_resource::<Game>().map = id;
+}
+
+// Makes a map the current one, along with its layout and encounter table as resources. Its
+// tiles are spawned by `stream` once the camera is in place.
+pub fn load(world: &mut World, id: &str) {
+    enter(world, id, load_layout(id));
+}
+
+// Like `load`, with a layout that does not come from the map's file.
+pub fn enter(world: &mut World, id: &str, layout: MapLayout) {
+    let npcs = spawn_npcs(world, &layout, (0, 0));
+
+    world.insert(MapStream::default());
+    world.write_resource::<MapStream>().maps.push(StreamedMap {
+        id: id.to_string(),
+        offset: (0, 0),
+        layout,
+        npcs,
+    });
+    connect(world);
+}
+
+// Deletes everything that belongs to the current map and its neighbors. Tiles and NPCs live
+// only as long as the map they were spawned for.
+pub fn unload(world: &mut World) {
+    let mut entities = world.write_resource::<MapStream>().hide();
+    {
+        let all = world.entities();
+        let npcs = world.read_storage::<Npc>();
+        entities.extend((&all, &npcs).join().map(|(e, _)| e));
+    }
+
+    world.delete_entities(&entities).expect("failed to delete map entities");
+    world.insert(MapStream::default());
+}
+
+// Moves the actors so that the cell at `by` becomes (0, 0). The camera moves along, so
+// nothing changes on screen. Tiles are spawned again from the rebuilt grid.
+fn rebase(world: &mut World, by: (i32, i32)) {
+    let (dx, dy) = (by.0 as f32 * CELL_SIZE, by.1 as f32 * CELL_SIZE);
+    let delta = Vector2::new(by.0, by.1);
+
+    let mut transforms = world.write_storage::<Transform>();
+    for (npc, transform) in (&mut world.write_storage::<Npc>(), &mut transforms).join() {
+        npc.grid_pos -= delta;
+        transform.prepend_translation_x(-dx);
+        transform.prepend_translation_y(-dy);
+    }
+    for (player, transform) in (&mut world.write_storage::<Player>(), &mut transforms).join() {
+        player.grid_pos -= delta;
+        transform.prepend_translation_x(-dx);
+        transform.prepend_translation_y(-dy);
+    }
+
+    let mut game = world.write_resource::<Game>();
+    for (_, transform) in (&world.read_storage::<Camera>(), &mut transforms).join() {
+        transform.prepend_translation_x(-dx);
+        transform.prepend_translation_y(-dy);
+        game.camera_trans = *transform.translation();
+    }
+}
+
+// Switches to the neighbor the player walked into, without a transition.
+fn cross(world: &mut World) {
+    let cell = match (&world.read_storage::<Player>()).join().next() {
+        Some(player) => (player.grid_pos[0], player.grid_pos[1]),
+        None => return,
+    };
+
+    let (index, offset) = {
+        let stream = world.read_resource::<MapStream>();
+        match stream.crossed(cell) {
+            Some(index) => (index, stream.maps[index].offset),
+            None => return,
+        }
+    };
+
+    {
+        let mut stream = world.write_resource::<MapStream>();
+        stream.maps.swap(0, index);
+        stream.shift(offset);
+    }
+    rebase(world, offset);
+    connect(world);
+}
+
+// The cells the camera shows. The camera's translation is the top left corner of the screen.
+fn view(world: &World) -> ((i32, i32), (i32, i32)) {
+    let (width, height) = {
+        let dim = world.read_resource::<VirtualScreen>();
+        (dim.width, dim.height)
+    };
+    let camera = world.read_resource::<Game>().camera_trans;
+
+    let cell_x = |px: f32| px / CELL_SIZE;
+    let cell_y = |py: f32| (py + TILE_DROP) / CELL_SIZE;
+    (
+        (cell_x(camera[0]).floor() as i32, cell_y(camera[1] - height).floor() as i32),
+        (cell_x(camera[0] + width).ceil() as i32, cell_y(camera[1]).ceil() as i32),
+    )
+}
+
+// Spawns the tiles of the chunks that scrolled into view and deletes the ones that left it,
+// crossing into a connected map first if the player walked into one. The static tiles of a
+// chunk are drawn into one image, and only the animated ones are spawned one by one.
+pub fn stream(world: &mut World) {
+    cross(world);
+
+    let (from, to) = view(world);
+    let culled = match world.write_resource::<MapStream>().cull(from, to) {
+        Some(culled) => culled,
+        None => return,
+    };
+    world.delete_entities(&culled.hidden).expect("failed to delete hidden tiles");
+
+    for chunk in culled.shown {
+        let tiles: Vec<((i32, i32), MapTile)> = world.read_resource::<MapStream>().tiles
+            .chunk_tiles(chunk)
+            .into_iter()
+            .map(|(cell, tile)| (cell, tile.clone()))
+            .collect();
+
+        let batch = {
+            let animations = world.read_resource::<TileAnimations>();
+            batch::split(tiles, |class| animations.animates(class))
+        };
+
+        let mut entities: Vec<Entity> = batch.loose.into_iter()
+            .map(|((x, y), tile)| Tile::create(world, tile.tileset, Some(tile.class), Vector2::new(x, y)))
+            .collect();
+        if !batch.baked.is_empty() {
+            entities.push(chunk::create(world, chunk, &batch.baked));
+        }
+        world.write_resource::<MapStream>().show(chunk, entities);
+    }
+}
@@ -0,0 +1,7 @@
+pub mod encounter;
+pub mod collision;
+pub mod layout;
+pub mod loader;
+pub mod stream;
+pub mod chunk;
+pub mod batch;
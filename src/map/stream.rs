@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use amethyst::ecs::Entity;
+
+use crate::entity::tile::TileClass;
+use crate::map::chunk::TileGrid;
+use crate::map::layout::MapLayout;
+
+// Cells around the camera view whose chunks are shown too, so that chunks are spawned a little
+// before they scroll onto the screen.
+pub const VIEW_MARGIN: i32 = 2;
+
+// Chunks that came into view, and the render entities of the ones that left it.
+pub struct Culled {
+    pub shown: Vec<(i32, i32)>,
+    pub hidden: Vec<Entity>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MapTile {
+    pub tileset: String,
+    pub class: TileClass,
+}
+
+pub struct StreamedMap {
+    pub id: String,
+    // Where the map's cell (0, 0) lies in the current map's grid.
+    pub offset: (i32, i32),
+    pub layout: MapLayout,
+    pub npcs: Vec<Entity>,
+}
+
+impl StreamedMap {
+    fn local(&self, cell: (i32, i32)) -> (i32, i32) {
+        (cell.0 - self.offset.0, cell.1 - self.offset.1)
+    }
+
+    pub fn contains(&self, cell: (i32, i32)) -> bool {
+        self.layout.contains(self.local(cell))
+    }
+}
+
+// The current map and the maps connected to it, with their tiles in one chunked grid.
+// Everything is in the grid of the current map, which moves when the player crosses into a
+// neighbor.
+#[derive(Default)]
+pub struct MapStream {
+    // The current map first.
+    pub maps: Vec<StreamedMap>,
+    pub tiles: TileGrid<MapTile>,
+    // Render entities of the chunks in view.
+    shown: HashMap<(i32, i32), Vec<Entity>>,
+    // The cells in view when the chunks were last culled.
+    view: Option<((i32, i32), (i32, i32))>,
+}
+
+impl MapStream {
+    pub fn current(&self) -> Option<&StreamedMap> {
+        self.maps.first()
+    }
+
+    // The map a cell belongs to. The current map wins where maps overlap.
+    pub fn owner(&self, cell: (i32, i32)) -> Option<&StreamedMap> {
+        self.maps.iter().find(|map| map.contains(cell))
+    }
+
+    // The neighbor the player entered, if the cell is outside of the current map.
+    pub fn crossed(&self, cell: (i32, i32)) -> Option<usize> {
+        match self.current() {
+            Some(current) if !current.contains(cell) => {
+                self.maps.iter().skip(1).position(|map| map.contains(cell)).map(|i| i + 1)
+            }
+            _ => None,
+        }
+    }
+
+    // The cells covered by the streamed maps, corners included, or None if one of them has
+    // no bounds.
+    pub fn bounds(&self) -> Option<((i32, i32), (i32, i32))> {
+        let mut union: Option<((i32, i32), (i32, i32))> = None;
+        for map in &self.maps {
+            let bounds = map.layout.bounds?;
+            let from = (bounds.from.0.min(bounds.to.0) + map.offset.0, bounds.from.1.min(bounds.to.1) + map.offset.1);
+            let to = (bounds.from.0.max(bounds.to.0) + map.offset.0, bounds.from.1.max(bounds.to.1) + map.offset.1);
+            union = Some(match union {
+                Some((a, b)) => ((a.0.min(from.0), a.1.min(from.1)), (b.0.max(to.0), b.1.max(to.1))),
+                None => (from, to),
+            });
+        }
+        union
+    }
+
+    pub fn any_at(&self, cell: (i32, i32), f: impl Fn(TileClass) -> bool) -> bool {
+        self.tiles.at(cell).any(|tile| f(tile.class))
+    }
+
+    // Moves the grid by `by`, so that the cell at `by` becomes (0, 0).
+    pub fn shift(&mut self, by: (i32, i32)) {
+        for map in &mut self.maps {
+            map.offset = (map.offset.0 - by.0, map.offset.1 - by.1);
+        }
+    }
+
+    // Fills the grid with the tiles of every map, each cell from the map it belongs to.
+    // Returns the render entities of the old grid, which are out of date.
+    pub fn rebuild(&mut self) -> Vec<Entity> {
+        let mut tiles = TileGrid::new();
+        for map in &self.maps {
+            for area in &map.layout.tiles {
+                for (x, y) in area.cells() {
+                    let cell = (x + map.offset.0, y + map.offset.1);
+                    if self.owner(cell).map(|owner| owner.id == map.id).unwrap_or(false) {
+                        tiles.insert(cell, MapTile { tileset: map.layout.tileset.clone(), class: area.class });
+                    }
+                }
+            }
+        }
+
+        self.tiles = tiles;
+        self.hide()
+    }
+
+    // Forgets the chunks in view, so that the next cull shows them all again. Returns their
+    // render entities.
+    pub fn hide(&mut self) -> Vec<Entity> {
+        self.view = None;
+        self.shown.drain().flat_map(|(_, entities)| entities).collect()
+    }
+
+    // Returns None if the view did not change.
+    pub fn cull(&mut self, from: (i32, i32), to: (i32, i32)) -> Option<Culled> {
+        let from = (from.0 - VIEW_MARGIN, from.1 - VIEW_MARGIN);
+        let to = (to.0 + VIEW_MARGIN, to.1 + VIEW_MARGIN);
+        if self.view == Some((from, to)) {
+            return None;
+        }
+        self.view = Some((from, to));
+
+        let visible = self.tiles.chunks_in(from, to);
+        let hidden: Vec<(i32, i32)> = self.shown.keys()
+            .filter(|chunk| !visible.contains(chunk))
+            .cloned()
+            .collect();
+        let hidden = hidden.iter()
+            .flat_map(|chunk| self.shown.remove(chunk).unwrap_or_default())
+            .collect();
+        let shown = visible.into_iter()
+            .filter(|chunk| !self.shown.contains_key(chunk))
+            .collect();
+
+        Some(Culled { shown, hidden })
+    }
+
+    // Records the render entities spawned for a chunk that came into view.
+    pub fn show(&mut self, chunk: (i32, i32), entities: Vec<Entity>) {
+        self.shown.insert(chunk, entities);
+    }
+}
+
+// Figures reported by the map benchmark.
+#[allow(dead_code)]
+impl MapStream {
+    pub fn shown_chunks(&self) -> usize {
+        self.shown.len()
+    }
+
+    pub fn shown_entities(&self) -> usize {
+        self.shown.values().map(|entities| entities.len()).sum()
+    }
+
+    // The tiles in the chunks in view, which would each take a render entity without batching.
+    pub fn shown_tiles(&self) -> usize {
+        self.shown.keys().map(|chunk| self.tiles.chunk_tiles(*chunk).len()).sum()
+    }
+}
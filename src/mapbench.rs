@@ -0,0 +1,260 @@
+// Headless benchmark of map streaming. Builds a large synthetic map and loads it through the
+// same loader the game uses, then reports as JSON how long loading and streaming in the view
+// take and how many render entities the view needs, next to the one entity per tile that it
+// would take without batching static tiles per chunk.
+//
+// cargo run --release --bin mapbench --no-default-features --features client -- --size 100 --layers 3 --runs 20
+
+// The whole game is built in, to run the real loader. Only the map code is used.
+#[allow(dead_code)]
+mod entity;
+#[allow(dead_code)]
+mod state;
+#[allow(dead_code)]
+mod system;
+#[allow(dead_code)]
+mod utils;
+#[allow(dead_code)]
+mod pokemon;
+#[allow(dead_code)]
+mod battle;
+#[allow(dead_code)]
+mod map;
+#[allow(dead_code)]
+mod clock;
+#[allow(dead_code)]
+mod trainer;
+#[allow(dead_code)]
+mod story;
+#[allow(dead_code)]
+mod pokedex;
+#[allow(dead_code)]
+mod item;
+#[allow(dead_code)]
+mod save;
+#[allow(dead_code)]
+mod audio;
+
+use std::{env, process, time::Instant};
+
+use amethyst::{
+    prelude::*,
+    assets::AssetStorage,
+    core::{math::Vector3, transform::Transform},
+    renderer::{camera::Camera, SpriteRender, SpriteSheet, Texture},
+    utils::application_root_dir,
+};
+use serde::Serialize;
+
+use crate::audio::music::Music;
+use crate::clock::Clock;
+use crate::entity::actor::{npc::Npc, player::Player};
+use crate::entity::tile::{TileClass, animation::{TileAnimations, TileAnimator}, tile::Tile};
+use crate::map::{
+    chunk::CHUNK_SIZE,
+    layout::{Bounds, MapLayout, TileArea},
+    loader,
+    stream::MapStream,
+};
+use crate::state::Game;
+use crate::trainer::Trainers;
+use crate::utils::{resolve, screen::VirtualScreen};
+
+// Classes of the tiles laid over the ground, static and animated ones alike.
+const COVER: [TileClass; 5] = [
+    TileClass::GreenPatch,
+    TileClass::Door,
+    TileClass::Flowers,
+    TileClass::Pc,
+    TileClass::Water,
+];
+
+struct Options {
+    size: i32,
+    layers: u8,
+    runs: u32,
+}
+
+#[derive(Serialize)]
+struct Report {
+    size: i32,
+    layers: u8,
+    runs: u32,
+    tiles: usize,
+    chunks: usize,
+    chunk_size: i32,
+    index_bytes: usize,
+    // Loading the map and building its chunked grid.
+    average_load_ms: f64,
+    // Streaming in the view at the middle of the map, chunk images included.
+    average_stream_ms: f64,
+    visible_chunks: usize,
+    // The tiles in view, which would each take a render entity without batching.
+    visible_tiles: usize,
+    // One per chunk, plus one per animated tile.
+    visible_entities: usize,
+}
+
+fn usage() -> ! {
+    eprintln!("usage: mapbench [--size N] [--layers N] [--runs N]");
+    process::exit(2);
+}
+
+fn parse_options() -> Options {
+    let mut options = Options {
+        size: 100,
+        layers: 3,
+        runs: 20,
+    };
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().unwrap_or_else(|| usage());
+        match arg.as_str() {
+            "--size" => options.size = value().parse().unwrap_or_else(|_| usage()),
+            "--layers" => options.layers = value().parse().unwrap_or_else(|_| usage()),
+            "--runs" => options.runs = value().parse().unwrap_or_else(|_| usage()),
+            _ => usage(),
+        }
+    }
+
+    if options.size <= 0 || options.layers == 0 || options.runs == 0 {
+        usage();
+    }
+    options
+}
+
+// A map as wide as it is tall and centred on (0, 0), with a ground layer over all of it and
+// sparser patches above, in the way grass, water and buildings cover a route.
+fn synthesize(options: &Options) -> MapLayout {
+    let half = options.size / 2;
+    let (from, to) = ((-half, -half), (options.size - half - 1, options.size - half - 1));
+
+    let mut tiles = vec![TileArea {
+        class: TileClass::Floor,
+        from,
+        to,
+    }];
+    for layer in 1..options.layers as i32 {
+        let step = 3 + layer;
+        for x in (from.0..=to.0).step_by(step as usize) {
+            for y in (from.1..=to.1).step_by(step as usize) {
+                let (bx, by) = (x.div_euclid(step), y.div_euclid(step));
+                if (bx + 2 * by + layer).rem_euclid(3) != 0 {
+                    continue;
+                }
+
+                tiles.push(TileArea {
+                    class: COVER[(bx * 7 + by * 13 + layer).rem_euclid(COVER.len() as i32) as usize],
+                    from: (x, y),
+                    to: ((x + step - 2).min(to.0), (y + step - 2).min(to.1)),
+                });
+            }
+        }
+    }
+
+    MapLayout {
+        tileset: "tileset-bw".to_string(),
+        tiles,
+        bounds: Some(Bounds { from, to }),
+        ..MapLayout::default()
+    }
+}
+
+// Sets up what the loader needs, in place of the game's systems and states.
+fn prepare(world: &mut World) {
+    world.register::<Transform>();
+    world.register::<SpriteRender>();
+    world.register::<Camera>();
+    world.register::<Tile>();
+    world.register::<TileAnimator>();
+    world.register::<Npc>();
+    world.register::<Player>();
+
+    world.insert(AssetStorage::<Texture>::default());
+    world.insert(AssetStorage::<SpriteSheet>::default());
+    world.insert(Game::default());
+    world.insert(VirtualScreen::load());
+    world.insert(Clock::default());
+    world.insert(Music::default());
+    world.insert(Trainers::default());
+    world.insert(MapStream::default());
+    world.insert(TileAnimations::load(&resolve::get_data_path("tile_animations.ron"))
+        .expect("failed to load tile animations"));
+
+    // The camera's translation is the top left corner of the view, which is centred on (0, 0).
+    let (width, height) = {
+        let dim = world.read_resource::<VirtualScreen>();
+        (dim.width, dim.height)
+    };
+    world.write_resource::<Game>().camera_trans = Vector3::new(-width / 2.0, height / 2.0, 10.0);
+}
+
+fn bench(world: &mut World, options: &Options) -> Report {
+    prepare(world);
+    let layout = synthesize(options);
+
+    let mut load = 0.0;
+    let mut stream = 0.0;
+    for _ in 0..options.runs {
+        loader::unload(world);
+        world.maintain();
+
+        let start = Instant::now();
+        loader::enter(world, "mapbench", layout.clone());
+        load += start.elapsed().as_secs_f64();
+
+        let start = Instant::now();
+        loader::stream(world);
+        stream += start.elapsed().as_secs_f64();
+        world.maintain();
+    }
+
+    let streamed = world.read_resource::<MapStream>();
+    Report {
+        size: options.size,
+        layers: options.layers,
+        runs: options.runs,
+        tiles: streamed.tiles.tile_count(),
+        chunks: streamed.tiles.chunk_count(),
+        chunk_size: CHUNK_SIZE,
+        index_bytes: streamed.tiles.index_bytes(),
+        average_load_ms: load * 1000.0 / options.runs as f64,
+        average_stream_ms: stream * 1000.0 / options.runs as f64,
+        visible_chunks: streamed.shown_chunks(),
+        visible_tiles: streamed.shown_tiles(),
+        visible_entities: streamed.shown_entities(),
+    }
+}
+
+// Runs the benchmark once the application has set up the world, then quits.
+struct BenchState {
+    options: Options,
+}
+
+impl SimpleState for BenchState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let report = bench(data.world, &self.options);
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("failed to write the report: {}", e);
+                process::exit(1);
+            }
+        }
+    }
+
+    fn update(&mut self, _: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        Trans::Quit
+    }
+}
+
+fn main() -> amethyst::Result<()> {
+    let options = parse_options();
+    let assets_dir = application_root_dir()?.join("./assets");
+
+    let mut bench = Application::build(assets_dir, BenchState { options })?
+        .build(GameDataBuilder::default())?;
+    bench.run();
+    Ok(())
+}
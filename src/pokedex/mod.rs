@@ -0,0 +1,126 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::battle::{Battle, BattleEvent};
+
+// The species the player has seen and caught. Catching a species also counts as seeing it.
+// The sets are kept sorted so that saves come out the same every time.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Pokedex {
+    seen: BTreeSet<String>,
+    caught: BTreeSet<String>,
+}
+
+impl Pokedex {
+    pub fn see(&mut self, species: &str) {
+        self.seen.insert(species.to_string());
+    }
+
+    pub fn catch(&mut self, species: &str) {
+        self.see(species);
+        self.caught.insert(species.to_string());
+    }
+
+    pub fn is_seen(&self, species: &str) -> bool {
+        self.seen.contains(species)
+    }
+
+    pub fn is_caught(&self, species: &str) -> bool {
+        self.caught.contains(species)
+    }
+
+    pub fn seen_count(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn caught_count(&self) -> usize {
+        self.caught.len()
+    }
+
+    // Marks the pokemon that are on the field as seen, for the start of a battle.
+    pub fn observe(&mut self, battle: &Battle) {
+        for side in battle.sides.iter() {
+            self.see(&side.active().species);
+        }
+    }
+
+    // Updates the Pokedex from an event of a battle turn.
+    pub fn record(&mut self, event: &BattleEvent) {
+        match event {
+            BattleEvent::SwitchedIn { species, .. } => self.see(species),
+            BattleEvent::Caught { species, .. } => self.catch(species),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pokemon::{library::Library, Pokemon};
+
+    #[test]
+    fn catching_also_counts_as_seeing() {
+        let mut pokedex = Pokedex::default();
+        pokedex.see("pidgey");
+        pokedex.catch("rattata");
+
+        assert!(pokedex.is_seen("pidgey"));
+        assert!(!pokedex.is_caught("pidgey"));
+        assert!(pokedex.is_seen("rattata"));
+        assert!(pokedex.is_caught("rattata"));
+        assert!(!pokedex.is_seen("oddish"));
+    }
+
+    #[test]
+    fn counts_each_species_once() {
+        let mut pokedex = Pokedex::default();
+        for species in &["pidgey", "rattata", "pidgey", "caterpie"] {
+            pokedex.see(species);
+        }
+        pokedex.catch("rattata");
+        pokedex.catch("rattata");
+
+        assert_eq!(pokedex.seen_count(), 3);
+        assert_eq!(pokedex.caught_count(), 1);
+    }
+
+    #[test]
+    fn battles_record_what_was_seen_and_caught() {
+        let library = Library::bundled();
+        let battle = Battle::wild(
+            vec![Pokemon::new(&library, "charmander", 5).unwrap()],
+            Pokemon::new(&library, "pidgey", 3).unwrap(),
+        );
+
+        let mut pokedex = Pokedex::default();
+        pokedex.observe(&battle);
+        pokedex.record(&BattleEvent::SwitchedIn { side: 0, name: "SQUIRTLE".to_string(), species: "squirtle".to_string() });
+        pokedex.record(&BattleEvent::Caught { name: "PIDGEY".to_string(), species: "pidgey".to_string() });
+        pokedex.record(&BattleEvent::Failed);
+
+        assert_eq!(pokedex.seen_count(), 3);
+        assert!(pokedex.is_seen("charmander") && pokedex.is_seen("squirtle"));
+        assert_eq!(pokedex.caught_count(), 1);
+        assert!(pokedex.is_caught("pidgey"));
+    }
+
+    #[test]
+    fn saves_round_trip_and_come_out_the_same_in_any_order() {
+        let mut first = Pokedex::default();
+        let mut second = Pokedex::default();
+        for species in &["rattata", "pidgey", "oddish", "caterpie"] {
+            first.see(species);
+        }
+        for species in &["caterpie", "oddish", "pidgey", "rattata"] {
+            second.see(species);
+        }
+        first.catch("pidgey");
+        second.catch("pidgey");
+
+        let text = ron::ser::to_string(&first).unwrap();
+        assert_eq!(text, ron::ser::to_string(&second).unwrap());
+        assert_eq!(ron::de::from_str::<Pokedex>(&text).unwrap(), first);
+    }
+}
@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pokemon::{library::Library, species::Species, MoveSlot, Pokemon};
+
+// Friendship needed for a `Friendship` evolution.
+pub const FRIENDSHIP_THRESHOLD: u8 = 220;
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum EvolutionMethod {
+    // Levelling up at or past the given level.
+    Level(u8),
+    // Using the given item on the pokemon, e.g. a stone.
+    Item(String),
+    Trade,
+    // Levelling up with at least FRIENDSHIP_THRESHOLD friendship.
+    Friendship,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Evolution {
+    // The species evolved into.
+    pub into: String,
+    pub method: EvolutionMethod,
+    // Moves learnt on evolving.
+    #[serde(default)]
+    pub moves: Vec<String>,
+}
+
+// What just happened to the pokemon that could make it evolve.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum EvolutionTrigger<'a> {
+    // A battle ended or the pokemon gained a level.
+    LevelUp,
+    Item(&'a str),
+    // Nothing trades pokemon yet, but trade evolutions are already in the species data.
+    #[allow(dead_code)]
+    Trade,
+}
+
+// The first evolution of the species that the trigger allows the pokemon to go through.
+pub fn check<'s>(species: &'s Species, pokemon: &Pokemon, trigger: EvolutionTrigger) -> Option<&'s Evolution> {
+    species.evolutions.iter().find(|evolution| match (&evolution.method, trigger) {
+        (EvolutionMethod::Level(level), EvolutionTrigger::LevelUp) => pokemon.level >= *level,
+        (EvolutionMethod::Friendship, EvolutionTrigger::LevelUp) => pokemon.friendship >= FRIENDSHIP_THRESHOLD,
+        (EvolutionMethod::Item(item), EvolutionTrigger::Item(used)) => item == used,
+        (EvolutionMethod::Trade, EvolutionTrigger::Trade) => true,
+        _ => false,
+    })
+}
+
+// Same as `check`, looking the species of the pokemon up in the library.
+pub fn find<'l>(library: &'l Library, pokemon: &Pokemon, trigger: EvolutionTrigger) -> Option<&'l Evolution> {
+    library.get_species(&pokemon.species).and_then(|species| check(species, pokemon, trigger))
+}
+
+// Turns the pokemon into the species it evolves into, keeping the damage it has taken.
+// Evolution moves are learnt while there is room for them. Returns the moves learnt.
+pub fn evolve(library: &Library, pokemon: &mut Pokemon, evolution: &Evolution) -> Vec<String> {
+    let species = match library.get_species(&evolution.into) {
+        Some(species) => species,
+        None => return Vec::new(),
+    };
+
+    pokemon.species = species.id.clone();
+    pokemon.recalculate_stats(species);
+
+    let mut learnt = Vec::new();
+    for id in &evolution.moves {
+        if pokemon.moves.len() >= 4 || pokemon.moves.iter().any(|slot| &slot.id == id) {
+            continue;
+        }
+        if let Some(data) = library.get_move(id) {
+            pokemon.moves.push(MoveSlot { id: id.clone(), pp: data.pp });
+            learnt.push(id.clone());
+        }
+    }
+
+    learnt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pokemon(library: &Library, species: &str, level: u8) -> Pokemon {
+        Pokemon::new(library, species, level).unwrap()
+    }
+
+    // A species that evolves by the given method only.
+    fn evolving_by(library: &Library, method: EvolutionMethod) -> Species {
+        let mut species = library.get_species("pidgey").unwrap().clone();
+        species.evolutions = vec![Evolution { into: "pidgeotto".to_string(), method, moves: Vec::new() }];
+        species
+    }
+
+    #[test]
+    fn level_evolutions_need_the_level() {
+        let library = Library::bundled();
+        let species = library.get_species("caterpie").unwrap();
+
+        assert!(check(species, &pokemon(&library, "caterpie", 6), EvolutionTrigger::LevelUp).is_none());
+        let evolution = check(species, &pokemon(&library, "caterpie", 7), EvolutionTrigger::LevelUp).unwrap();
+        assert_eq!(evolution.into, "metapod");
+        assert!(check(species, &pokemon(&library, "caterpie", 30), EvolutionTrigger::Item("leaf-stone")).is_none());
+    }
+
+    #[test]
+    fn item_evolutions_need_the_right_item() {
+        let library = Library::bundled();
+        let gloom = pokemon(&library, "gloom", 30);
+
+        assert_eq!(find(&library, &gloom, EvolutionTrigger::Item("leaf-stone")).map(|e| e.into.as_str()), Some("vileplume"));
+        assert!(find(&library, &gloom, EvolutionTrigger::Item("fire-stone")).is_none());
+        assert!(find(&library, &gloom, EvolutionTrigger::LevelUp).is_none());
+    }
+
+    #[test]
+    fn friendship_evolutions_need_friendship_and_a_level_up() {
+        let library = Library::bundled();
+        let species = evolving_by(&library, EvolutionMethod::Friendship);
+        let mut pidgey = pokemon(&library, "pidgey", 5);
+
+        pidgey.friendship = FRIENDSHIP_THRESHOLD - 1;
+        assert!(check(&species, &pidgey, EvolutionTrigger::LevelUp).is_none());
+        pidgey.friendship = FRIENDSHIP_THRESHOLD;
+        assert!(check(&species, &pidgey, EvolutionTrigger::LevelUp).is_some());
+        assert!(check(&species, &pidgey, EvolutionTrigger::Trade).is_none());
+    }
+
+    #[test]
+    fn trade_evolutions_only_happen_on_trades() {
+        let library = Library::bundled();
+        let species = evolving_by(&library, EvolutionMethod::Trade);
+        let pidgey = pokemon(&library, "pidgey", 50);
+
+        assert!(check(&species, &pidgey, EvolutionTrigger::Trade).is_some());
+        assert!(check(&species, &pidgey, EvolutionTrigger::LevelUp).is_none());
+    }
+
+    #[test]
+    fn final_forms_never_evolve() {
+        let library = Library::bundled();
+        let noctowl = pokemon(&library, "noctowl", 100);
+
+        for trigger in [EvolutionTrigger::LevelUp, EvolutionTrigger::Item("leaf-stone"), EvolutionTrigger::Trade].iter() {
+            assert!(find(&library, &noctowl, *trigger).is_none());
+        }
+    }
+
+    #[test]
+    fn evolving_keeps_the_damage_and_learns_the_evolution_moves() {
+        let library = Library::bundled();
+        let mut metapod = pokemon(&library, "metapod", 10);
+        metapod.hp -= 5;
+
+        let evolution = find(&library, &metapod, EvolutionTrigger::LevelUp).unwrap().clone();
+        let learnt = evolve(&library, &mut metapod, &evolution);
+
+        assert_eq!(metapod.species, "butterfree");
+        assert_eq!(metapod.hp, metapod.stats.hp - 5);
+        assert_eq!(learnt, vec!["gust".to_string()]);
+        assert!(metapod.moves.iter().any(|slot| slot.id == "gust"));
+    }
+}
@@ -0,0 +1,61 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+};
+
+use crate::item::ItemData;
+use crate::pokemon::{moves::Move, species::Species};
+use crate::utils::data::{self, DataError};
+
+// Static species, move and item data, shared by the overworld and the battle engine.
+#[derive(Default)]
+pub struct Library {
+    species: HashMap<String, Species>,
+    moves: HashMap<String, Move>,
+    items: HashMap<String, ItemData>,
+}
+
+impl Library {
+    pub fn load(dir: &Path) -> Result<Self, DataError> {
+        let species: Vec<Species> = data::load_ron(&dir.join("species.ron"))?;
+        let moves: Vec<Move> = data::load_ron(&dir.join("moves.ron"))?;
+        let items: Vec<ItemData> = data::load_ron(&dir.join("items.ron"))?;
+
+        Ok(Library {
+            species: species.into_iter().map(|s| (s.id.clone(), s)).collect(),
+            moves: moves.into_iter().map(|m| (m.id.clone(), m)).collect(),
+            items: items.into_iter().map(|i| (i.id.clone(), i)).collect(),
+        })
+    }
+
+    pub fn get_species(&self, id: &str) -> Option<&Species> {
+        self.species.get(id)
+    }
+
+    // Species with a Pokedex entry, by Pokedex number.
+    pub fn dex_species(&self) -> Vec<&Species> {
+        let mut species: Vec<&Species> = self.species.values().filter(|s| s.dex.is_some()).collect();
+        species.sort_by_key(|s| s.dex.as_ref().map(|d| d.number));
+        species
+    }
+
+    pub fn get_move(&self, id: &str) -> Option<&Move> {
+        self.moves.get(id)
+    }
+
+    pub fn get_item(&self, id: &str) -> Option<&ItemData> {
+        self.items.get(id)
+    }
+}
+
+#[cfg(test)]
+impl Library {
+    // The data the game ships with, for tests.
+    pub fn bundled() -> Library {
+        Library::load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/data")).expect("failed to load assets/data")
+    }
+
+    pub fn insert_item(&mut self, item: ItemData) {
+        self.items.insert(item.id.clone(), item);
+    }
+}
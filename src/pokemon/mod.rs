@@ -0,0 +1,174 @@
+pub mod types;
+pub mod moves;
+pub mod species;
+pub mod library;
+pub mod party;
+pub mod storage;
+pub mod evolution;
+
+use serde::{Deserialize, Serialize};
+
+use crate::pokemon::{library::Library, species::Species};
+
+pub const MAX_LEVEL: u8 = 100;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct Stats {
+    pub hp: u16,
+    pub attack: u16,
+    pub defense: u16,
+    pub sp_attack: u16,
+    pub sp_defense: u16,
+    pub speed: u16,
+}
+
+impl Stats {
+    // Stats of a pokemon at the given level. IVs and EVs are not modelled yet.
+    pub fn at_level(base: &Stats, level: u8) -> Self {
+        let level = level as u32;
+        let other = |b: u16| ((2 * b as u32 * level) / 100 + 5) as u16;
+
+        Stats {
+            hp: ((2 * base.hp as u32 * level) / 100 + level + 10) as u16,
+            attack: other(base.attack),
+            defense: other(base.defense),
+            sp_attack: other(base.sp_attack),
+            sp_defense: other(base.sp_defense),
+            speed: other(base.speed),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Status {
+    Poison,
+    Burn,
+    Paralysis,
+    Sleep,
+}
+
+impl Status {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Status::Poison => "PSN",
+            Status::Burn => "BRN",
+            Status::Paralysis => "PAR",
+            Status::Sleep => "SLP",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MoveSlot {
+    pub id: String,
+    pub pp: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pokemon {
+    pub species: String,
+    pub nickname: Option<String>,
+    pub level: u8,
+    pub hp: u16,
+    pub stats: Stats,
+    pub moves: Vec<MoveSlot>,
+    #[serde(default)]
+    pub status: Option<Status>,
+    // Turns left before waking up, while asleep.
+    #[serde(default)]
+    pub sleep_turns: u8,
+    // How attached the pokemon is to the player, up to 255.
+    #[serde(default = "Pokemon::base_friendship")]
+    pub friendship: u8,
+}
+
+impl Pokemon {
+    pub fn new(library: &Library, species: &str, level: u8) -> Option<Self> {
+        let data = library.get_species(species)?;
+        let moves = data.moves_at_level(level).into_iter()
+            .filter_map(|id| library.get_move(&id).map(|m| MoveSlot { id, pp: m.pp }))
+            .collect();
+
+        let mut pokemon = Pokemon {
+            species: species.to_string(),
+            nickname: None,
+            level,
+            hp: 0,
+            stats: Stats::default(),
+            moves,
+            status: None,
+            sleep_turns: 0,
+            friendship: Pokemon::base_friendship(),
+        };
+
+        pokemon.recalculate_stats(data);
+        pokemon.hp = pokemon.stats.hp;
+        Some(pokemon)
+    }
+
+    fn base_friendship() -> u8 {
+        70
+    }
+
+    // Raises the level by one and learns the moves of the new level while there is room for
+    // them. Returns the moves learnt, or None at the level cap.
+    pub fn level_up(&mut self, library: &Library) -> Option<Vec<String>> {
+        let species = library.get_species(&self.species)?;
+        if self.level >= MAX_LEVEL {
+            return None;
+        }
+
+        self.level += 1;
+        self.recalculate_stats(species);
+
+        let level = self.level;
+        let mut learnt = Vec::new();
+        for (_, id) in species.learnset.iter().filter(|(l, _)| *l == level) {
+            if self.moves.len() >= 4 || self.moves.iter().any(|slot| &slot.id == id) {
+                continue;
+            }
+            if let Some(data) = library.get_move(id) {
+                self.moves.push(MoveSlot { id: id.clone(), pp: data.pp });
+                learnt.push(id.clone());
+            }
+        }
+
+        Some(learnt)
+    }
+
+    // Recomputes stats from the species, keeping the amount of damage taken.
+    pub fn recalculate_stats(&mut self, species: &Species) {
+        let damage = self.stats.hp.saturating_sub(self.hp);
+        self.stats = Stats::at_level(&species.base_stats, self.level);
+        self.hp = self.stats.hp.saturating_sub(damage);
+    }
+
+    pub fn name(&self, library: &Library) -> String {
+        match &self.nickname {
+            Some(nickname) => nickname.clone(),
+            None => library.get_species(&self.species)
+                .map(|s| s.name.clone())
+                .unwrap_or_else(|| self.species.clone())
+        }
+    }
+
+    pub fn is_fainted(&self) -> bool {
+        self.hp == 0
+    }
+
+    pub fn take_damage(&mut self, amount: u16) {
+        self.hp = self.hp.saturating_sub(amount);
+    }
+
+    // Restores HP, PP and status, as a Pokemon Center would.
+    pub fn restore(&mut self, library: &Library) {
+        self.hp = self.stats.hp;
+        self.status = None;
+        self.sleep_turns = 0;
+        for slot in &mut self.moves {
+            if let Some(m) = library.get_move(&slot.id) {
+                slot.pp = m.pp;
+            }
+        }
+    }
+}
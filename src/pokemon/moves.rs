@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pokemon::{types::PokemonType, Status};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum MoveCategory {
+    Physical,
+    Special,
+    Status,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum BattleStat {
+    Attack,
+    Defense,
+    SpAttack,
+    SpDefense,
+    Speed,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum MoveEffect {
+    // Raises or lowers a stat stage of the target, or of the user when `on_user` is set.
+    Stat { stat: BattleStat, stages: i8, on_user: bool },
+    // Inflicts a status on the target with the given chance out of 100.
+    Inflict { status: Status, chance: u8 },
+}
+
+// Moves that can be used from the party menu outside of battle.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum FieldMove {
+    // Lures out a wild pokemon when used in tall grass.
+    SweetScent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Move {
+    pub id: String,
+    pub name: String,
+    pub kind: PokemonType,
+    pub category: MoveCategory,
+    pub power: u16,
+    // None for moves that never miss.
+    pub accuracy: Option<u8>,
+    pub pp: u8,
+    #[serde(default)]
+    pub effect: Option<MoveEffect>,
+    #[serde(default)]
+    pub field: Option<FieldMove>,
+}
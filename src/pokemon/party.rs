@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pokemon::{library::Library, Pokemon};
+
+pub const PARTY_SIZE: usize = 6;
+
+// The pokemon the player carries around, in the order they are sent into battle.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Party {
+    members: Vec<Pokemon>,
+}
+
+impl Party {
+    // Anything past the sixth member is dropped.
+    pub fn new(mut members: Vec<Pokemon>) -> Self {
+        members.truncate(PARTY_SIZE);
+        Party { members }
+    }
+
+    pub fn members(&self) -> &[Pokemon] {
+        &self.members
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Pokemon> {
+        self.members.get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Pokemon> {
+        self.members.get_mut(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.members.len() >= PARTY_SIZE
+    }
+
+    // Gives the pokemon back if there is no room for it.
+    pub fn add(&mut self, pokemon: Pokemon) -> Result<(), Pokemon> {
+        if self.is_full() {
+            return Err(pokemon);
+        }
+
+        self.members.push(pokemon);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Pokemon> {
+        if index < self.members.len() {
+            Some(self.members.remove(index))
+        } else {
+            None
+        }
+    }
+
+    pub fn swap(&mut self, a: usize, b: usize) {
+        if a < self.members.len() && b < self.members.len() {
+            self.members.swap(a, b);
+        }
+    }
+
+    pub fn restore_all(&mut self, library: &Library) {
+        for pokemon in &mut self.members {
+            pokemon.restore(library);
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pokemon::{evolution::Evolution, types::PokemonType, Stats};
+
+// What the Pokedex shows about a species once it has been seen.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DexInfo {
+    pub number: u16,
+    pub category: String,
+    // In metres and kilograms.
+    pub height: f32,
+    pub weight: f32,
+    pub flavor: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Species {
+    pub id: String,
+    pub name: String,
+    pub types: Vec<PokemonType>,
+    pub base_stats: Stats,
+    // How easily the species is caught, from 3 (hardest) to 255.
+    #[serde(default = "Species::default_catch_rate")]
+    pub catch_rate: u8,
+    // (level, move id) pairs, in the order they are learnt.
+    pub learnset: Vec<(u8, String)>,
+    // Moves that can be taught with TMs.
+    #[serde(default)]
+    pub machines: Vec<String>,
+    // Species without an entry are left out of the Pokedex.
+    #[serde(default)]
+    pub dex: Option<DexInfo>,
+    #[serde(default)]
+    pub evolutions: Vec<Evolution>,
+}
+
+impl Species {
+    fn default_catch_rate() -> u8 {
+        255
+    }
+
+    pub fn moves_at_level(&self, level: u8) -> Vec<String> {
+        let learnt: Vec<String> = self.learnset.iter()
+            .filter(|(l, _)| *l <= level)
+            .map(|(_, id)| id.clone())
+            .collect();
+
+        // A pokemon knows at most four moves, the most recently learnt ones.
+        let skip = if learnt.len() > 4 { learnt.len() - 4 } else { 0 };
+        learnt.into_iter().skip(skip).collect()
+    }
+}
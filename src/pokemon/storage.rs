@@ -0,0 +1,367 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pokemon::{party::Party, Pokemon};
+
+pub const BOX_COUNT: usize = 14;
+pub const BOX_SIZE: usize = 30;
+pub const MAX_BOX_NAME: usize = 8;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Wallpaper {
+    Forest,
+    City,
+    Desert,
+    Savanna,
+    Crag,
+    Volcano,
+    Snow,
+    Cave,
+    Beach,
+    Seafloor,
+    River,
+    Sky,
+}
+
+impl Wallpaper {
+    pub const ALL: [Wallpaper; 12] = [
+        Wallpaper::Forest,
+        Wallpaper::City,
+        Wallpaper::Desert,
+        Wallpaper::Savanna,
+        Wallpaper::Crag,
+        Wallpaper::Volcano,
+        Wallpaper::Snow,
+        Wallpaper::Cave,
+        Wallpaper::Beach,
+        Wallpaper::Seafloor,
+        Wallpaper::River,
+        Wallpaper::Sky,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Wallpaper::Forest => "FOREST",
+            Wallpaper::City => "CITY",
+            Wallpaper::Desert => "DESERT",
+            Wallpaper::Savanna => "SAVANNA",
+            Wallpaper::Crag => "CRAG",
+            Wallpaper::Volcano => "VOLCANO",
+            Wallpaper::Snow => "SNOW",
+            Wallpaper::Cave => "CAVE",
+            Wallpaper::Beach => "BEACH",
+            Wallpaper::Seafloor => "SEAFLOOR",
+            Wallpaper::River => "RIVER",
+            Wallpaper::Sky => "SKY",
+        }
+    }
+
+    // Background colour of the box screen.
+    pub fn color(&self) -> [f32; 4] {
+        match self {
+            Wallpaper::Forest => [0.35, 0.6, 0.35, 1.],
+            Wallpaper::City => [0.55, 0.55, 0.6, 1.],
+            Wallpaper::Desert => [0.85, 0.75, 0.5, 1.],
+            Wallpaper::Savanna => [0.75, 0.7, 0.35, 1.],
+            Wallpaper::Crag => [0.55, 0.4, 0.3, 1.],
+            Wallpaper::Volcano => [0.75, 0.35, 0.25, 1.],
+            Wallpaper::Snow => [0.85, 0.9, 0.95, 1.],
+            Wallpaper::Cave => [0.35, 0.3, 0.35, 1.],
+            Wallpaper::Beach => [0.9, 0.85, 0.65, 1.],
+            Wallpaper::Seafloor => [0.2, 0.35, 0.6, 1.],
+            Wallpaper::River => [0.4, 0.65, 0.8, 1.],
+            Wallpaper::Sky => [0.6, 0.8, 0.95, 1.],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PokemonBox {
+    pub name: String,
+    pub wallpaper: Wallpaper,
+    // Always BOX_SIZE long. Empty slots are None.
+    slots: Vec<Option<Pokemon>>,
+}
+
+impl PokemonBox {
+    fn new(index: usize) -> Self {
+        PokemonBox {
+            name: format!("BOX {}", index + 1),
+            wallpaper: Wallpaper::ALL[index % Wallpaper::ALL.len()],
+            slots: vec![None; BOX_SIZE],
+        }
+    }
+
+    pub fn slots(&self) -> &[Option<Pokemon>] {
+        &self.slots
+    }
+
+    pub fn get(&self, slot: usize) -> Option<&Pokemon> {
+        self.slots.get(slot).and_then(|s| s.as_ref())
+    }
+
+    pub fn count(&self) -> usize {
+        self.slots.iter().filter(|s| s.is_some()).count()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.count() >= BOX_SIZE
+    }
+
+    fn first_free(&self) -> Option<usize> {
+        self.slots.iter().position(|s| s.is_none())
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum StorageError {
+    InvalidBox,
+    InvalidSlot,
+    EmptySlot,
+    PartyFull,
+    BoxFull,
+    // Every box is full.
+    StorageFull,
+    // The party must keep at least one pokemon that can battle.
+    LastPokemon,
+}
+
+// The PC boxes, holding the pokemon that are not in the party. Operations that touch the party
+// check everything before changing anything, so a failed one leaves both as they were.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Storage {
+    boxes: Vec<PokemonBox>,
+    // The box that is opened first, and filled first by `store`.
+    pub current: usize,
+}
+
+impl Default for Storage {
+    fn default() -> Self {
+        Storage::new(BOX_COUNT)
+    }
+}
+
+impl Storage {
+    pub fn new(count: usize) -> Self {
+        Storage {
+            boxes: (0..count.max(1)).map(PokemonBox::new).collect(),
+            current: 0,
+        }
+    }
+
+    pub fn boxes(&self) -> &[PokemonBox] {
+        &self.boxes
+    }
+
+    pub fn get_box(&self, index: usize) -> Option<&PokemonBox> {
+        self.boxes.get(index)
+    }
+
+    pub fn get(&self, index: usize, slot: usize) -> Option<&Pokemon> {
+        self.boxes.get(index).and_then(|b| b.get(slot))
+    }
+
+    pub fn get_mut(&mut self, index: usize, slot: usize) -> Option<&mut Pokemon> {
+        self.boxes.get_mut(index).and_then(|b| b.slots.get_mut(slot)).and_then(|s| s.as_mut())
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.boxes.iter().all(|b| b.is_full())
+    }
+
+    pub fn rename(&mut self, index: usize, name: &str) -> Result<(), StorageError> {
+        let pokemon_box = self.boxes.get_mut(index).ok_or(StorageError::InvalidBox)?;
+        let name: String = name.trim().chars().take(MAX_BOX_NAME).collect();
+        if !name.is_empty() {
+            pokemon_box.name = name;
+        }
+        Ok(())
+    }
+
+    pub fn set_wallpaper(&mut self, index: usize, wallpaper: Wallpaper) -> Result<(), StorageError> {
+        self.boxes.get_mut(index).ok_or(StorageError::InvalidBox)?.wallpaper = wallpaper;
+        Ok(())
+    }
+
+    fn slot_mut(&mut self, index: usize, slot: usize) -> Result<&mut Option<Pokemon>, StorageError> {
+        self.boxes.get_mut(index)
+            .ok_or(StorageError::InvalidBox)?
+            .slots.get_mut(slot)
+            .ok_or(StorageError::InvalidSlot)
+    }
+
+    // Puts a pokemon in the first free slot, starting from the current box. Gives it back
+    // with the error when every box is full.
+    pub fn store(&mut self, pokemon: Pokemon) -> Result<(usize, usize), (Pokemon, StorageError)> {
+        let count = self.boxes.len();
+        let found = (0..count)
+            .map(|i| (self.current + i) % count)
+            .find_map(|i| self.boxes[i].first_free().map(|slot| (i, slot)));
+
+        match found {
+            Some((index, slot)) => {
+                self.boxes[index].slots[slot] = Some(pokemon);
+                Ok((index, slot))
+            }
+            None => Err((pokemon, StorageError::StorageFull)),
+        }
+    }
+
+    // Moves a party member into the first free slot of a box.
+    pub fn deposit(&mut self, party: &mut Party, member: usize, index: usize) -> Result<usize, StorageError> {
+        let pokemon_box = self.boxes.get(index).ok_or(StorageError::InvalidBox)?;
+        if member >= party.len() {
+            return Err(StorageError::InvalidSlot);
+        }
+
+        let others_usable = party.members().iter()
+            .enumerate()
+            .any(|(i, p)| i != member && !p.is_fainted());
+        if !others_usable {
+            return Err(StorageError::LastPokemon);
+        }
+
+        let slot = pokemon_box.first_free().ok_or(StorageError::BoxFull)?;
+        let pokemon = party.remove(member).ok_or(StorageError::InvalidSlot)?;
+        self.boxes[index].slots[slot] = Some(pokemon);
+        Ok(slot)
+    }
+
+    // Moves a boxed pokemon to the end of the party. Returns its index in the party.
+    pub fn withdraw(&mut self, party: &mut Party, index: usize, slot: usize) -> Result<usize, StorageError> {
+        if self.slot_mut(index, slot)?.is_none() {
+            return Err(StorageError::EmptySlot);
+        }
+        if party.is_full() {
+            return Err(StorageError::PartyFull);
+        }
+
+        let pokemon = self.slot_mut(index, slot)?.take().ok_or(StorageError::EmptySlot)?;
+        party.add(pokemon).map_err(|_| StorageError::PartyFull)?;
+        Ok(party.len() - 1)
+    }
+
+    // Moves a boxed pokemon to another slot, in any box. Swaps with whatever is already there.
+    pub fn move_pokemon(&mut self, from: (usize, usize), to: (usize, usize)) -> Result<(), StorageError> {
+        self.slot_mut(to.0, to.1)?;
+        let pokemon = self.slot_mut(from.0, from.1)?.take().ok_or(StorageError::EmptySlot)?;
+        let replaced = self.slot_mut(to.0, to.1)?.replace(pokemon);
+        *self.slot_mut(from.0, from.1)? = replaced;
+        Ok(())
+    }
+
+    // Releases a boxed pokemon for good.
+    pub fn release(&mut self, index: usize, slot: usize) -> Result<Pokemon, StorageError> {
+        self.slot_mut(index, slot)?.take().ok_or(StorageError::EmptySlot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pokemon::{library::Library, party::PARTY_SIZE};
+
+    fn pokemon(library: &Library, species: &str) -> Pokemon {
+        Pokemon::new(library, species, 5).unwrap()
+    }
+
+    fn fainted(library: &Library, species: &str) -> Pokemon {
+        let mut pokemon = pokemon(library, species);
+        pokemon.hp = 0;
+        pokemon
+    }
+
+    #[test]
+    fn deposit_keeps_a_usable_pokemon_in_the_party() {
+        let library = Library::bundled();
+        let mut storage = Storage::new(2);
+        let mut party = Party::new(vec![pokemon(&library, "pidgey"), fainted(&library, "rattata")]);
+
+        assert_eq!(storage.deposit(&mut party, 0, 0), Err(StorageError::LastPokemon));
+        assert_eq!(party.len(), 2);
+        assert!(storage.get(0, 0).is_none());
+
+        // The fainted one can go, leaving the pidgey behind.
+        assert_eq!(storage.deposit(&mut party, 1, 0), Ok(0));
+        assert_eq!(party.len(), 1);
+        assert_eq!(storage.get(0, 0).map(|p| p.species.as_str()), Some("rattata"));
+        assert_eq!(storage.deposit(&mut party, 0, 0), Err(StorageError::LastPokemon));
+    }
+
+    #[test]
+    fn full_boxes_refuse_deposits() {
+        let library = Library::bundled();
+        let mut storage = Storage::new(2);
+        for _ in 0..BOX_SIZE {
+            storage.store(pokemon(&library, "caterpie")).unwrap();
+        }
+        assert!(storage.get_box(0).unwrap().is_full());
+
+        let mut party = Party::new(vec![pokemon(&library, "pidgey"), pokemon(&library, "rattata")]);
+        assert_eq!(storage.deposit(&mut party, 1, 0), Err(StorageError::BoxFull));
+        assert_eq!(party.len(), 2);
+        assert_eq!(storage.deposit(&mut party, 1, 1), Ok(0));
+    }
+
+    #[test]
+    fn store_moves_on_to_the_next_box_until_all_are_full() {
+        let library = Library::bundled();
+        let mut storage = Storage::new(2);
+        for _ in 0..BOX_SIZE {
+            storage.store(pokemon(&library, "caterpie")).unwrap();
+        }
+
+        assert_eq!(storage.store(pokemon(&library, "pidgey")).unwrap(), (1, 0));
+        for _ in 1..BOX_SIZE {
+            storage.store(pokemon(&library, "caterpie")).unwrap();
+        }
+
+        assert!(storage.is_full());
+        let (returned, error) = storage.store(pokemon(&library, "rattata")).unwrap_err();
+        assert_eq!((returned.species.as_str(), error), ("rattata", StorageError::StorageFull));
+    }
+
+    #[test]
+    fn withdrawing_needs_room_in_the_party() {
+        let library = Library::bundled();
+        let mut storage = Storage::new(1);
+        storage.store(pokemon(&library, "oddish")).unwrap();
+
+        let mut party = Party::new((0..PARTY_SIZE).map(|_| pokemon(&library, "pidgey")).collect());
+        assert_eq!(storage.withdraw(&mut party, 0, 0), Err(StorageError::PartyFull));
+        assert!(storage.get(0, 0).is_some());
+
+        party.remove(0);
+        assert_eq!(storage.withdraw(&mut party, 0, 0), Ok(PARTY_SIZE - 1));
+        assert!(storage.get(0, 0).is_none());
+        assert_eq!(storage.withdraw(&mut party, 0, 0), Err(StorageError::EmptySlot));
+    }
+
+    #[test]
+    fn moving_swaps_with_the_pokemon_already_there() {
+        let library = Library::bundled();
+        let mut storage = Storage::new(2);
+        storage.store(pokemon(&library, "pidgey")).unwrap();
+        storage.store(pokemon(&library, "rattata")).unwrap();
+
+        storage.move_pokemon((0, 0), (0, 1)).unwrap();
+        assert_eq!(storage.get(0, 0).map(|p| p.species.as_str()), Some("rattata"));
+        assert_eq!(storage.get(0, 1).map(|p| p.species.as_str()), Some("pidgey"));
+
+        storage.move_pokemon((0, 1), (1, 5)).unwrap();
+        assert!(storage.get(0, 1).is_none());
+        assert_eq!(storage.move_pokemon((0, 1), (1, 0)), Err(StorageError::EmptySlot));
+        assert_eq!(storage.move_pokemon((0, 0), (2, 0)), Err(StorageError::InvalidBox));
+        assert_eq!(storage.get(0, 0).map(|p| p.species.as_str()), Some("rattata"));
+    }
+
+    #[test]
+    fn box_names_are_trimmed_and_shortened() {
+        let mut storage = Storage::new(1);
+        storage.rename(0, "  GRASSLANDS  ").unwrap();
+        assert_eq!(storage.get_box(0).unwrap().name, "GRASSLAN");
+
+        storage.rename(0, "   ").unwrap();
+        assert_eq!(storage.get_box(0).unwrap().name, "GRASSLAN");
+        assert_eq!(storage.rename(1, "X"), Err(StorageError::InvalidBox));
+    }
+}
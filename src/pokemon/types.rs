@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum PokemonType {
+    Normal,
+    Fire,
+    Water,
+    Electric,
+    Grass,
+    Ice,
+    Fighting,
+    Poison,
+    Ground,
+    Flying,
+    Psychic,
+    Bug,
+    Rock,
+    Ghost,
+    Dragon,
+    Dark,
+    Steel,
+}
+
+impl PokemonType {
+    // Index of the party icon in texture/pokemon/icons.
+    pub fn icon_index(&self) -> usize {
+        *self as usize
+    }
+
+    // Damage multiplier of an attack of this type against a single defending type.
+    pub fn effectiveness(&self, defender: PokemonType) -> f32 {
+        use PokemonType::*;
+
+        match (*self, defender) {
+            (Normal, Ghost) | (Electric, Ground) | (Fighting, Ghost) | (Poison, Steel) |
+            (Ground, Flying) | (Psychic, Dark) | (Ghost, Normal) => 0.0,
+
+            (Fire, Grass) | (Fire, Ice) | (Fire, Bug) | (Fire, Steel) |
+            (Water, Fire) | (Water, Ground) | (Water, Rock) |
+            (Electric, Water) | (Electric, Flying) |
+            (Grass, Water) | (Grass, Ground) | (Grass, Rock) |
+            (Ice, Grass) | (Ice, Ground) | (Ice, Flying) | (Ice, Dragon) |
+            (Fighting, Normal) | (Fighting, Ice) | (Fighting, Rock) | (Fighting, Dark) | (Fighting, Steel) |
+            (Poison, Grass) |
+            (Ground, Fire) | (Ground, Electric) | (Ground, Poison) | (Ground, Rock) | (Ground, Steel) |
+            (Flying, Grass) | (Flying, Fighting) | (Flying, Bug) |
+            (Psychic, Fighting) | (Psychic, Poison) |
+            (Bug, Grass) | (Bug, Psychic) | (Bug, Dark) |
+            (Rock, Fire) | (Rock, Ice) | (Rock, Flying) | (Rock, Bug) |
+            (Ghost, Psychic) | (Ghost, Ghost) |
+            (Dragon, Dragon) |
+            (Dark, Psychic) | (Dark, Ghost) |
+            (Steel, Ice) | (Steel, Rock) => 2.0,
+
+            (Normal, Rock) | (Normal, Steel) |
+            (Fire, Fire) | (Fire, Water) | (Fire, Rock) | (Fire, Dragon) |
+            (Water, Water) | (Water, Grass) | (Water, Dragon) |
+            (Electric, Electric) | (Electric, Grass) | (Electric, Dragon) |
+            (Grass, Fire) | (Grass, Grass) | (Grass, Poison) | (Grass, Flying) | (Grass, Bug) |
+            (Grass, Dragon) | (Grass, Steel) |
+            (Ice, Fire) | (Ice, Water) | (Ice, Ice) | (Ice, Steel) |
+            (Fighting, Poison) | (Fighting, Flying) | (Fighting, Psychic) | (Fighting, Bug) |
+            (Poison, Poison) | (Poison, Ground) | (Poison, Rock) | (Poison, Ghost) |
+            (Ground, Grass) | (Ground, Bug) |
+            (Flying, Electric) | (Flying, Rock) | (Flying, Steel) |
+            (Psychic, Psychic) | (Psychic, Steel) |
+            (Bug, Fire) | (Bug, Fighting) | (Bug, Poison) | (Bug, Flying) | (Bug, Ghost) | (Bug, Steel) |
+            (Rock, Fighting) | (Rock, Ground) | (Rock, Steel) |
+            (Ghost, Dark) |
+            (Dragon, Steel) |
+            (Dark, Fighting) | (Dark, Dark) |
+            (Steel, Fire) | (Steel, Water) | (Steel, Electric) | (Steel, Steel) => 0.5,
+
+            _ => 1.0
+        }
+    }
+
+    pub fn effectiveness_against(&self, defender: &[PokemonType]) -> f32 {
+        defender.iter().fold(1.0, |acc, t| acc * self.effectiveness(*t))
+    }
+}
@@ -0,0 +1,84 @@
+use std::path::PathBuf;
+
+use amethyst::{
+    prelude::*,
+    ecs::Join,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::clock::Clock;
+use crate::entity::actor::{ActorDirection, player::Player};
+use crate::item::bag::Bag;
+use crate::pokedex::Pokedex;
+use crate::pokemon::{party::Party, storage::Storage};
+use crate::state::Game;
+use crate::story::Story;
+use crate::utils::{data::{self, DataError}, resolve};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerSave {
+    pub name: String,
+    pub id: u16,
+    pub money: u32,
+    pub grid_pos: (i32, i32),
+    pub direction: ActorDirection,
+    pub party: Party,
+    pub bag: Bag,
+}
+
+// Everything that is written to the save file. Entities are rebuilt from it on start.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveData {
+    pub map: String,
+    pub player: PlayerSave,
+    pub story: Story,
+    #[serde(default)]
+    pub storage: Storage,
+    #[serde(default)]
+    pub pokedex: Pokedex,
+    // Seconds since midnight on the in-game clock.
+    #[serde(default)]
+    pub time: Option<u32>,
+}
+
+impl SaveData {
+    pub fn path() -> PathBuf {
+        resolve::get_user_path("save.ron")
+    }
+
+    // Returns None when there is no save yet.
+    pub fn load() -> Result<Option<SaveData>, DataError> {
+        let path = SaveData::path();
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        data::load_ron(&path).map(Some)
+    }
+
+    pub fn write(&self) -> Result<(), DataError> {
+        data::save_ron(&SaveData::path(), self)
+    }
+
+    pub fn capture(world: &World) -> Option<SaveData> {
+        let players = world.read_storage::<Player>();
+        let player = (&players).join().next()?;
+
+        Some(SaveData {
+            map: world.read_resource::<Game>().map.clone(),
+            player: PlayerSave {
+                name: player.name.clone(),
+                id: player.id,
+                money: player.money,
+                grid_pos: (player.grid_pos[0], player.grid_pos[1]),
+                direction: player.attrs.direction,
+                party: player.party.clone(),
+                bag: player.bag.clone(),
+            },
+            story: (*world.read_resource::<Story>()).clone(),
+            storage: (*world.read_resource::<Storage>()).clone(),
+            pokedex: (*world.read_resource::<Pokedex>()).clone(),
+            time: Some(world.read_resource::<Clock>().seconds()),
+        })
+    }
+}
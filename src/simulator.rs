@@ -0,0 +1,224 @@
+// Headless battle simulator. Plays many battles between two teams through the battle engine
+// and prints the results as JSON, without opening a window.
+//
+// cargo run --bin simulator --no-default-features -- assets/data/sim/example.ron --battles 1000 --seed 7
+
+// Only the modules that don't depend on amethyst are shared with the game.
+#[allow(dead_code)]
+mod pokemon;
+#[allow(dead_code)]
+mod battle;
+#[allow(dead_code)]
+mod trainer;
+#[allow(dead_code)]
+mod item;
+mod utils {
+    #[allow(dead_code)]
+    pub mod data;
+}
+
+use std::{
+    collections::BTreeMap,
+    env,
+    path::{Path, PathBuf},
+    process,
+};
+
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::battle::{ai::AiTier, weather::Weather, Battle, BattleEvent, BattleOutcome, OPPONENT, PLAYER};
+use crate::pokemon::library::Library;
+use crate::trainer::TrainerPokemon;
+use crate::utils::data;
+
+// Battles that last longer than this are counted as draws.
+const MAX_TURNS: u32 = 500;
+
+#[derive(Debug, Deserialize)]
+struct SimSide {
+    #[serde(default)]
+    ai: AiTier,
+    team: Vec<TrainerPokemon>,
+}
+
+// The file given on the command line, in RON.
+#[derive(Debug, Deserialize)]
+struct SimConfig {
+    player: SimSide,
+    opponent: SimSide,
+    // The weather the battles are fought in.
+    #[serde(default)]
+    weather: Option<Weather>,
+    // How many turns the weather lasts, or the whole battle when left out.
+    #[serde(default)]
+    weather_turns: Option<u32>,
+}
+
+struct Options {
+    config: PathBuf,
+    data: PathBuf,
+    battles: u32,
+    seed: u64,
+    player_ai: Option<AiTier>,
+    opponent_ai: Option<AiTier>,
+}
+
+#[derive(Default, Serialize)]
+struct SideReport {
+    ai: String,
+    wins: u32,
+    win_rate: f32,
+    move_usage: BTreeMap<String, u32>,
+}
+
+#[derive(Serialize)]
+struct Report {
+    battles: u32,
+    seed: u64,
+    draws: u32,
+    average_turns: f32,
+    player: SideReport,
+    opponent: SideReport,
+}
+
+fn usage() -> ! {
+    eprintln!("usage: simulator <teams.ron> [--battles N] [--seed N] [--data DIR] \
+               [--player-ai Random|Greedy|Smart] [--opponent-ai Random|Greedy|Smart]");
+    process::exit(2);
+}
+
+fn parse_tier(value: &str) -> AiTier {
+    match value.to_lowercase().as_str() {
+        "random" => AiTier::Random,
+        "greedy" => AiTier::Greedy,
+        "smart" => AiTier::Smart,
+        _ => usage(),
+    }
+}
+
+fn parse_options() -> Options {
+    let mut options = Options {
+        config: PathBuf::new(),
+        data: Path::new(env!("CARGO_MANIFEST_DIR")).join("assets").join("data"),
+        battles: 1000,
+        seed: 0,
+        player_ai: None,
+        opponent_ai: None,
+    };
+
+    let mut config = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().unwrap_or_else(|| usage());
+        match arg.as_str() {
+            "--battles" => options.battles = value().parse().unwrap_or_else(|_| usage()),
+            "--seed" => options.seed = value().parse().unwrap_or_else(|_| usage()),
+            "--data" => options.data = PathBuf::from(value()),
+            "--player-ai" => options.player_ai = Some(parse_tier(&value())),
+            "--opponent-ai" => options.opponent_ai = Some(parse_tier(&value())),
+            "-h" | "--help" => usage(),
+            _ if config.is_none() && !arg.starts_with("--") => config = Some(PathBuf::from(arg)),
+            _ => usage(),
+        }
+    }
+
+    options.config = config.unwrap_or_else(|| usage());
+    options
+}
+
+fn build_team(library: &Library, team: &[TrainerPokemon]) -> Result<Vec<pokemon::Pokemon>, String> {
+    team.iter()
+        .map(|p| pokemon::Pokemon::new(library, &p.species, p.level)
+            .ok_or_else(|| format!("unknown species '{}'", p.species)))
+        .collect()
+}
+
+fn simulate(library: &Library, config: &SimConfig, options: &Options) -> Result<Report, String> {
+    let player_team = build_team(library, &config.player.team)?;
+    let opponent_team = build_team(library, &config.opponent.team)?;
+    if player_team.is_empty() || opponent_team.is_empty() {
+        return Err("both teams need at least one pokemon".to_string());
+    }
+
+    let tiers = [
+        options.player_ai.unwrap_or(config.player.ai),
+        options.opponent_ai.unwrap_or(config.opponent.ai),
+    ];
+
+    let mut sides = [SideReport::default(), SideReport::default()];
+    for (side, tier) in sides.iter_mut().zip(tiers.iter()) {
+        side.ai = format!("{:?}", tier);
+    }
+
+    let mut draws = 0;
+    let mut total_turns = 0u64;
+
+    // Every battle gets its own seed, drawn from a generator seeded with the base seed, so runs
+    // are reproducible and nearby base seeds don't replay each other's battles.
+    let mut seeds = StdRng::seed_from_u64(options.seed);
+    for _ in 0..options.battles {
+        let seed = seeds.next_u64();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut ais = [tiers[PLAYER].build(seed.wrapping_add(1)), tiers[OPPONENT].build(seed.wrapping_add(2))];
+
+        let mut battle = Battle::trainer(player_team.clone(), opponent_team.clone()).with_weather(config.weather);
+        battle.weather_turns = config.weather_turns;
+        while !battle.is_over() && battle.turn < MAX_TURNS {
+            let actions = [
+                ais[PLAYER].choose(&battle, PLAYER, library),
+                ais[OPPONENT].choose(&battle, OPPONENT, library),
+            ];
+
+            for event in battle.play_turn(library, actions, &mut rng) {
+                if let BattleEvent::MoveUsed { side, move_id, .. } = event {
+                    *sides[side].move_usage.entry(move_id).or_insert(0) += 1;
+                }
+            }
+        }
+
+        total_turns += battle.turn as u64;
+        match battle.outcome {
+            Some(BattleOutcome::Won) => sides[PLAYER].wins += 1,
+            Some(BattleOutcome::Lost) => sides[OPPONENT].wins += 1,
+            _ => draws += 1,
+        }
+    }
+
+    let battles = options.battles.max(1) as f32;
+    for side in sides.iter_mut() {
+        side.win_rate = side.wins as f32 / battles;
+    }
+
+    let [player, opponent] = sides;
+    Ok(Report {
+        battles: options.battles,
+        seed: options.seed,
+        draws,
+        average_turns: total_turns as f32 / battles,
+        player,
+        opponent,
+    })
+}
+
+fn main() {
+    let options = parse_options();
+
+    let library = Library::load(&options.data).unwrap_or_else(|e| {
+        eprintln!("failed to load game data from {}: {}", options.data.display(), e);
+        process::exit(1);
+    });
+
+    let config: SimConfig = data::load_ron(&options.config).unwrap_or_else(|e| {
+        eprintln!("failed to load {}: {}", options.config.display(), e);
+        process::exit(1);
+    });
+
+    match simulate(&library, &config, &options) {
+        Ok(report) => println!("{}", serde_json::to_string_pretty(&report).expect("failed to serialize report")),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
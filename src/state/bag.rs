@@ -0,0 +1,560 @@
+use amethyst::{
+    prelude::*,
+    ecs::{Entity, Join},
+    input::InputEvent,
+    renderer::SpriteRender,
+    shred::FetchMut,
+    ui::{UiTransform, UiText, UiImage, Anchor, LineMode, Stretch},
+};
+
+use crate::entity::actor::player::Player;
+use crate::entity::dialog::DialogSpritetype;
+use crate::item::{self, bag::{Bag, SortOrder}, ItemEffect, ItemError, ItemOutcome, Pocket};
+use crate::pokemon::{evolution::{self, EvolutionTrigger}, library::Library, storage::Storage};
+use crate::state::{Game, Trigger};
+use crate::state::evolution::EvolutionState;
+use crate::state::party::PartyState;
+use crate::utils::{resolve, screen::VirtualScreen};
+
+const VISIBLE_ROWS: usize = 6;
+
+// Where the bag was opened from, which decides what items can be used for.
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum BagContext {
+    Field,
+    Battle,
+}
+
+#[derive(Clone, Eq, PartialEq)]
+enum BagPhase {
+    List,
+    Actions,
+    Message,
+    // Waiting for the party screen to pick who gets the item.
+    Target(String),
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum BagAction {
+    Use,
+    Toss,
+    Cancel,
+}
+
+impl BagAction {
+    fn label(&self) -> &'static str {
+        match self {
+            BagAction::Use => "USE",
+            BagAction::Toss => "TOSS",
+            BagAction::Cancel => "CANCEL",
+        }
+    }
+}
+
+struct BagUi {
+    entities: Vec<Entity>,
+    header: Entity,
+    list: Entity,
+    text: Entity,
+}
+
+// The bag screen: browses the pockets, sorts them and uses or tosses items. In battle, the
+// chosen item is handed back to the battle with `Trigger::ItemChosen` instead of being used.
+pub struct BagState {
+    context: BagContext,
+    pocket: usize,
+    phase: BagPhase,
+    cursor: usize,
+    scroll: usize,
+    action_cursor: usize,
+    sort: SortOrder,
+    message: String,
+    ui: Option<BagUi>,
+    was_paused: bool,
+}
+
+impl BagState {
+    pub fn new(context: BagContext) -> Self {
+        BagState {
+            context,
+            pocket: 0,
+            phase: BagPhase::List,
+            cursor: 0,
+            scroll: 0,
+            action_cursor: 0,
+            sort: SortOrder::Name,
+            message: String::new(),
+            ui: None,
+            was_paused: false,
+        }
+    }
+
+    fn fetch_game<'s>(&mut self, world: &'s mut World) -> FetchMut<'s, Game> {
+        world.write_resource::<Game>()
+    }
+
+    fn bag(world: &World) -> Bag {
+        (&world.read_storage::<Player>()).join()
+            .next()
+            .map(|player| player.bag.clone())
+            .unwrap_or_default()
+    }
+
+    fn pocket(&self) -> Pocket {
+        Pocket::ALL[self.pocket]
+    }
+
+    fn selected_item(&self, world: &World) -> Option<String> {
+        BagState::bag(world).pocket(self.pocket()).get(self.cursor).map(|stack| stack.id.clone())
+    }
+
+    fn create_text(world: &mut World, transform: UiTransform, size: f32) -> Entity {
+        let font = resolve::load_font_handle(world);
+        let mut text = UiText::new(font, "".to_string(), [1., 1., 1., 1.], size);
+        text.line_mode = LineMode::Wrap;
+        text.align = Anchor::TopLeft;
+
+        world.create_entity()
+            .with(transform)
+            .with(text)
+            .build()
+    }
+
+    fn initialize_ui(&mut self, world: &mut World) {
+        let (width, height) = {
+            let dim = world.read_resource::<VirtualScreen>();
+            (dim.width, dim.height)
+        };
+
+        let sprite_handle = resolve::load_spritesheet_handle(world, "dialogs/sheet".to_string());
+        let panel = |id: &str, anchor: Anchor, y: f32, height: f32| UiTransform::new(
+            id.to_string(), anchor, anchor, 0., y, 16., width, height,
+        );
+
+        let background = world.create_entity()
+            .with(UiTransform::new(
+                "bag_background".to_string(),
+                Anchor::Middle,
+                Anchor::Middle,
+                0.,
+                0.,
+                15.,
+                width,
+                height,
+            ).with_stretch(Stretch::XY {
+                x_margin: 0.,
+                y_margin: 0.,
+                keep_aspect_ratio: false,
+            }))
+            .with(UiImage::SolidColor([0.85, 0.55, 0.3, 1.]))
+            .build();
+
+        let list_box = world.create_entity()
+            .with(panel("bag_list_box", Anchor::TopMiddle, -20., 92.))
+            .with(UiImage::Sprite(SpriteRender {
+                sprite_sheet: sprite_handle.clone(),
+                sprite_number: DialogSpritetype::Bottom.to_sprite_index(),
+            }))
+            .build();
+
+        let text_box = world.create_entity()
+            .with(panel("bag_text_box", Anchor::BottomMiddle, 0., 48.))
+            .with(UiImage::Sprite(SpriteRender {
+                sprite_sheet: sprite_handle,
+                sprite_number: DialogSpritetype::Bottom.to_sprite_index(),
+            }))
+            .build();
+
+        let header = BagState::create_text(world, UiTransform::new(
+            "bag_header".to_string(),
+            Anchor::TopLeft,
+            Anchor::TopLeft,
+            8.,
+            -4.,
+            17.,
+            width - 16.,
+            14.,
+        ), 12.);
+
+        let list = BagState::create_text(world, UiTransform::new(
+            "bag_list".to_string(),
+            Anchor::TopLeft,
+            Anchor::TopLeft,
+            14.,
+            -26.,
+            17.,
+            width - 28.,
+            80.,
+        ), 12.);
+
+        let text = BagState::create_text(world, UiTransform::new(
+            "bag_text".to_string(),
+            Anchor::BottomLeft,
+            Anchor::MiddleLeft,
+            10.,
+            24.,
+            17.,
+            width - 20.,
+            40.,
+        ), 12.);
+
+        self.ui = Some(BagUi {
+            entities: vec![background, list_box, text_box, header, list, text],
+            header,
+            list,
+            text,
+        });
+    }
+
+    // Key items can't be tossed, and nothing is tossed in the middle of a battle.
+    fn actions(&self) -> Vec<BagAction> {
+        if self.context == BagContext::Battle || self.pocket() == Pocket::KeyItems {
+            vec![BagAction::Use, BagAction::Cancel]
+        } else {
+            vec![BagAction::Use, BagAction::Toss, BagAction::Cancel]
+        }
+    }
+
+    fn menu(labels: Vec<String>, cursor: usize) -> String {
+        labels.iter().enumerate()
+            .map(|(i, label)| if i == cursor { format!("> {}", label) } else { format!("  {}", label) })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn refresh(&self, world: &mut World) {
+        let ui = match &self.ui {
+            Some(ui) => ui,
+            None => return,
+        };
+
+        let bag = BagState::bag(world);
+        let actions = self.actions();
+        let library = world.read_resource::<Library>();
+        let mut texts = world.write_storage::<UiText>();
+        let stacks = bag.pocket(self.pocket());
+
+        if let Some(text) = texts.get_mut(ui.header) {
+            text.text = format!("< {} >      ENTER: SORT BY {}", self.pocket().label(), self.sort.label());
+        }
+
+        if let Some(text) = texts.get_mut(ui.list) {
+            text.text = if stacks.is_empty() {
+                "  (empty)".to_string()
+            } else {
+                stacks.iter().enumerate()
+                    .skip(self.scroll)
+                    .take(VISIBLE_ROWS)
+                    .map(|(i, stack)| {
+                        let marker = if i == self.cursor { "> " } else { "  " };
+                        let name = library.get_item(&stack.id).map(|item| item.name.clone()).unwrap_or_else(|| stack.id.clone());
+                        if self.pocket() == Pocket::KeyItems {
+                            format!("{}{}", marker, name)
+                        } else {
+                            format!("{}{}  x{}", marker, name, stack.quantity)
+                        }
+                    })
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            };
+        }
+
+        if let Some(text) = texts.get_mut(ui.text) {
+            text.text = match &self.phase {
+                BagPhase::List | BagPhase::Target(_) => stacks.get(self.cursor)
+                    .and_then(|stack| library.get_item(&stack.id))
+                    .map(|item| item.description.clone())
+                    .unwrap_or_default(),
+                BagPhase::Actions => BagState::menu(
+                    actions.iter().map(|a| a.label().to_string()).collect(),
+                    self.action_cursor,
+                ),
+                BagPhase::Message => self.message.clone(),
+            };
+        }
+    }
+
+    fn show_message(&mut self, message: String) {
+        self.message = message;
+        self.phase = BagPhase::Message;
+    }
+
+    // Keeps the cursor on an existing item and inside the visible rows.
+    fn clamp_cursor(&mut self, len: usize) {
+        if self.cursor >= len {
+            self.cursor = len.saturating_sub(1);
+        }
+
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + VISIBLE_ROWS {
+            self.scroll = self.cursor + 1 - VISIBLE_ROWS;
+        }
+    }
+
+    fn use_item(&mut self, world: &mut World, id: String) -> SimpleTrans {
+        let (usable, needs_target, ball) = {
+            let library = world.read_resource::<Library>();
+            match library.get_item(&id) {
+                Some(item) => (
+                    if self.context == BagContext::Battle { item.battle } else { item.field },
+                    item.needs_target(),
+                    matches!(item.effect, Some(ItemEffect::Ball(_))),
+                ),
+                None => return Trans::None,
+            }
+        };
+
+        if usable && ball && self.context == BagContext::Battle {
+            return self.throw_ball(world, id);
+        }
+
+        if !usable || !needs_target {
+            let message = if self.context == BagContext::Battle {
+                "You can't use that now!"
+            } else {
+                "There's a time and place for everything!\n\nBut not now."
+            };
+            self.show_message(message.to_string());
+            return Trans::None;
+        }
+
+        self.phase = BagPhase::Target(id);
+        Trans::Push(Box::new(PartyState::choose("Use on which POKEMON?")))
+    }
+
+    // Balls are thrown by the battle engine as the turn's action, as long as a caught pokemon
+    // would have somewhere to go.
+    fn throw_ball(&mut self, world: &mut World, id: String) -> SimpleTrans {
+        let party_full = (&world.read_storage::<Player>()).join()
+            .next()
+            .map(|player| player.party.is_full())
+            .unwrap_or(true);
+
+        if party_full && world.read_resource::<Storage>().is_full() {
+            self.show_message("There's no more room for POKEMON!\n\nThe BOXES are full!".to_string());
+            return Trans::None;
+        }
+
+        self.fetch_game(world).set_trigger(Trigger::BallChosen(id));
+        Trans::Pop
+    }
+
+    // In battle the item is only checked here, and used by the battle engine as the turn's action.
+    fn choose_for_battle(&mut self, world: &mut World, id: String, target: usize) -> SimpleTrans {
+        let has_effect = {
+            let library = world.read_resource::<Library>();
+            let players = world.read_storage::<Player>();
+            let pokemon = (&players).join().next().and_then(|player| player.party.get(target).cloned());
+
+            match (library.get_item(&id), pokemon) {
+                (Some(item), Some(mut pokemon)) => item::use_on(item, &mut pokemon, &library).is_ok(),
+                _ => false,
+            }
+        };
+
+        if !has_effect {
+            self.show_message("It won't have any effect.".to_string());
+            return Trans::None;
+        }
+
+        self.fetch_game(world).set_trigger(Trigger::ItemChosen { item: id, target });
+        Trans::Pop
+    }
+
+    // Applies an item picked from the bag to a party member.
+    fn use_on_member(&mut self, world: &mut World, id: String, target: usize) -> SimpleTrans {
+        if self.context == BagContext::Battle {
+            return self.choose_for_battle(world, id, target);
+        }
+
+        let library = world.read_resource::<Library>();
+        let item = match library.get_item(&id) {
+            Some(item) => item,
+            None => return Trans::None,
+        };
+
+        let mut players = world.write_storage::<Player>();
+        let player = match (&mut players).join().next() {
+            Some(player) => player,
+            None => return Trans::None,
+        };
+
+        let pokemon = match player.party.get_mut(target) {
+            Some(pokemon) => pokemon,
+            None => return Trans::None,
+        };
+
+        let name = pokemon.name(&library);
+        let mut evolves = None;
+        let message = match item::use_on(item, pokemon, &library) {
+            Ok(outcome) => {
+                player.bag.remove(&library, &id, 1).expect("used an item that is not in the bag");
+                match outcome {
+                    ItemOutcome::LevelUp { level, learnt } => {
+                        evolves = evolution::find(&library, pokemon, EvolutionTrigger::LevelUp).cloned();
+                        let mut message = format!("{} grew to Lv. {}!", name, level);
+                        for mv in learnt {
+                            message = format!("{}\n{} learned {}!", message, name, mv);
+                        }
+                        message
+                    }
+                    ItemOutcome::Evolves(_) => {
+                        evolves = evolution::find(&library, pokemon, EvolutionTrigger::Item(&id)).cloned();
+                        "".to_string()
+                    }
+                    ItemOutcome::Healed(amount) => format!("{}'s HP was restored by {} point(s).", name, amount),
+                    ItemOutcome::Cured => format!("{} was cured!", name),
+                    ItemOutcome::Revived => format!("{} was revived!", name),
+                    ItemOutcome::PpRestored => format!("{}'s PP was restored.", name),
+                    ItemOutcome::Learned(mv) => format!("{} learned {}!", name, mv),
+                }
+            }
+            Err(ItemError::CantLearn) => format!("{} can't learn that move.", name),
+            Err(ItemError::NoEffect) => "It won't have any effect.".to_string(),
+        };
+
+        // Evolution stones say nothing before the evolution.
+        if !message.is_empty() {
+            self.show_message(message);
+        }
+        match evolves {
+            Some(evolves) => Trans::Push(Box::new(EvolutionState::new(vec![(target, evolves)]))),
+            None => Trans::None,
+        }
+    }
+
+    fn toss(&mut self, world: &mut World, id: String) {
+        let library = world.read_resource::<Library>();
+        let name = library.get_item(&id).map(|item| item.name.clone()).unwrap_or_default();
+
+        for player in (&mut world.write_storage::<Player>()).join() {
+            if player.bag.remove(&library, &id, 1).is_ok() {
+                self.message = format!("Threw away a {}.", name);
+            }
+        }
+
+        self.phase = BagPhase::Message;
+    }
+}
+
+impl SimpleState for BagState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        {
+            let mut game = self.fetch_game(world);
+            self.was_paused = game.paused;
+            game.paused = true;
+        }
+
+        self.initialize_ui(world);
+        self.refresh(world);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        if let Some(ui) = self.ui.take() {
+            for entity in &ui.entities {
+                world.delete_entity(*entity).expect("failed to delete bag ui");
+            }
+        }
+
+        let was_paused = self.was_paused;
+        self.fetch_game(world).paused = was_paused;
+    }
+
+    fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        let action = match event {
+            StateEvent::Input(InputEvent::ActionPressed(action)) => action,
+            _ => return Trans::None,
+        };
+
+        let world = data.world;
+        let mut trans = Trans::None;
+
+        match self.phase.clone() {
+            BagPhase::List => {
+                let len = BagState::bag(world).pocket(self.pocket()).len();
+                match action.as_str() {
+                    "left" | "right" => {
+                        let count = Pocket::ALL.len();
+                        self.pocket = if action == "left" { (self.pocket + count - 1) % count } else { (self.pocket + 1) % count };
+                        self.cursor = 0;
+                        self.scroll = 0;
+                    }
+                    "up" if self.cursor > 0 => self.cursor -= 1,
+                    "down" if self.cursor + 1 < len => self.cursor += 1,
+                    "menu" => {
+                        let pocket = self.pocket();
+                        let order = self.sort;
+                        let library = world.read_resource::<Library>();
+                        for player in (&mut world.write_storage::<Player>()).join() {
+                            player.bag.sort(&library, pocket, order);
+                        }
+                        self.sort = order.next();
+                    }
+                    "continue" if len > 0 => {
+                        self.action_cursor = 0;
+                        self.phase = BagPhase::Actions;
+                    }
+                    "cancel" => return Trans::Pop,
+                    _ => {}
+                }
+                self.clamp_cursor(len);
+            }
+            BagPhase::Actions => {
+                let actions = self.actions();
+                match action.as_str() {
+                    "up" if self.action_cursor > 0 => self.action_cursor -= 1,
+                    "down" if self.action_cursor + 1 < actions.len() => self.action_cursor += 1,
+                    "continue" => {
+                        if let Some(id) = self.selected_item(world) {
+                            match actions[self.action_cursor] {
+                                BagAction::Use => trans = self.use_item(world, id),
+                                BagAction::Toss => self.toss(world, id),
+                                BagAction::Cancel => self.phase = BagPhase::List,
+                            }
+                        }
+                    }
+                    "cancel" => self.phase = BagPhase::List,
+                    _ => {}
+                }
+            }
+            BagPhase::Message => {
+                if action == "continue" || action == "cancel" {
+                    self.phase = BagPhase::List;
+                    let len = BagState::bag(world).pocket(self.pocket()).len();
+                    self.clamp_cursor(len);
+                }
+            }
+            BagPhase::Target(_) => {}
+        }
+
+        self.refresh(world);
+        trans
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let world = &mut data.world;
+
+        let trigger = {
+            let mut game = self.fetch_game(world);
+            let trigger = game.get_trigger();
+            game.clear_trigger();
+            trigger
+        };
+
+        let mut trans = Trans::None;
+        if let (Some(Trigger::PartyChosen(chosen)), BagPhase::Target(id)) = (trigger, self.phase.clone()) {
+            self.phase = BagPhase::List;
+            if let Some(target) = chosen {
+                trans = self.use_on_member(world, id, target);
+            }
+            self.refresh(world);
+        }
+
+        trans
+    }
+}
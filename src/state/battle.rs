@@ -0,0 +1,749 @@
+use std::collections::VecDeque;
+
+use amethyst::{
+    prelude::*,
+    ecs::{Entity, Join},
+    input::InputEvent,
+    renderer::SpriteRender,
+    shred::FetchMut,
+    shrev::ReaderId,
+    ui::{UiTransform, UiText, UiImage, Anchor, LineMode, Stretch},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::audio::music::Music;
+use crate::battle::{Battle, BattleAction, BattleEvent, BattleOutcome, PLAYER, OPPONENT};
+use crate::battle::ai::{AiTier, BattleAi};
+use crate::entity::actor::player::Player;
+use crate::entity::battle::{ball::BallShake, transition::BattleTransition};
+use crate::entity::dialog::DialogSpritetype;
+use crate::entity::menu::list::{self, ListEntry, ListEvent, ListEventKind, ListLayout, ListWidget};
+use crate::map::{encounter::WildEncounter, stream::MapStream};
+use crate::pokedex::Pokedex;
+use crate::pokemon::{library::Library, party::{Party, PARTY_SIZE}, storage::Storage, Pokemon};
+use crate::state::{Game, Trigger};
+use crate::state::bag::{BagContext, BagState};
+use crate::state::naming::NamingState;
+use crate::state::party::PartyState;
+use crate::trainer::Trainers;
+use crate::utils::{resolve, screen::VirtualScreen};
+
+#[derive(Clone)]
+enum BattleSetup {
+    Wild(WildEncounter),
+    Trainer(String),
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum BattlePhase {
+    Transition,
+    Message,
+    Command,
+    Moves,
+    // A thrown ball is shaking.
+    Throw,
+    // Asking whether to give the caught pokemon a nickname.
+    Nickname,
+}
+
+// Where a caught pokemon was sent.
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Caught {
+    Party(usize),
+    Box(usize, usize),
+    // There was no room anywhere. The bag refuses to throw balls when this could happen.
+    Lost,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Command {
+    Fight,
+    Bag,
+    Pokemon,
+    Run,
+}
+
+impl Command {
+    fn label(&self) -> &'static str {
+        match self {
+            Command::Fight => "FIGHT",
+            Command::Bag => "BAG",
+            Command::Pokemon => "POKEMON",
+            Command::Run => "RUN",
+        }
+    }
+}
+
+const COMMANDS: [Command; 4] = [Command::Fight, Command::Bag, Command::Pokemon, Command::Run];
+
+struct BattleUi {
+    entities: Vec<Entity>,
+    opponent_info: Entity,
+    player_info: Entity,
+    text: Entity,
+}
+
+pub struct BattleState {
+    setup: BattleSetup,
+    battle: Option<Battle>,
+    phase: BattlePhase,
+    messages: VecDeque<String>,
+    // The command or move list, while one is shown.
+    list: Option<Entity>,
+    reader: Option<ReaderId<ListEvent>>,
+    ui: Option<BattleUi>,
+    caught: Option<Caught>,
+    rng: StdRng,
+    ai: Option<Box<dyn BattleAi + Send + Sync>>,
+    was_paused: bool,
+}
+
+impl BattleState {
+    pub fn wild(encounter: WildEncounter) -> Self {
+        BattleState::new(BattleSetup::Wild(encounter))
+    }
+
+    pub fn trainer(trainer: String) -> Self {
+        BattleState::new(BattleSetup::Trainer(trainer))
+    }
+
+    fn new(setup: BattleSetup) -> Self {
+        BattleState {
+            setup,
+            battle: None,
+            phase: BattlePhase::Transition,
+            messages: VecDeque::new(),
+            list: None,
+            reader: None,
+            ui: None,
+            caught: None,
+            rng: StdRng::from_entropy(),
+            ai: None,
+            was_paused: false,
+        }
+    }
+
+    fn fetch_game<'s>(&mut self, world: &'s mut World) -> FetchMut<'s, Game> {
+        world.write_resource::<Game>()
+    }
+
+    // Returns false if there is nothing to battle with, e.g. the party is fainted.
+    fn start_battle(&mut self, world: &mut World) -> bool {
+        let library = world.read_resource::<Library>();
+        let party = (&world.read_storage::<Player>()).join()
+            .next()
+            .map(|player| player.party.members().to_vec())
+            .unwrap_or_default();
+
+        if party.iter().all(|p| p.is_fainted()) {
+            return false;
+        }
+
+        let battle = match &self.setup {
+            BattleSetup::Wild(encounter) => {
+                let wild = match Pokemon::new(&library, &encounter.species, encounter.level) {
+                    Some(wild) => wild,
+                    None => return false,
+                };
+
+                let battle = Battle::wild(party, wild);
+                self.ai = Some(AiTier::Random.build(self.rng.gen()));
+                self.messages.push_back(format!("A wild {} appeared!", battle.sides[OPPONENT].active().name(&library)));
+                battle
+            }
+            BattleSetup::Trainer(id) => {
+                let trainers = world.read_resource::<Trainers>();
+                let trainer = match trainers.get(id) {
+                    Some(trainer) => trainer,
+                    None => return false,
+                };
+
+                let team = trainer.build_team(&library);
+                if team.is_empty() {
+                    return false;
+                }
+
+                let battle = Battle::trainer(party, team);
+                self.ai = Some(trainer.ai.build(self.rng.gen()));
+                self.messages.push_back(format!("{} would like to battle!", trainer.name));
+                self.messages.push_back(format!("{} sent out {}!", trainer.name, battle.sides[OPPONENT].active().name(&library)));
+                battle
+            }
+        };
+
+        let battle = battle.with_weather(world.read_resource::<MapStream>().current().and_then(|map| map.layout.weather));
+        if let Some(weather) = battle.weather {
+            self.messages.push_back(weather.start_message().to_string());
+        }
+
+        self.messages.push_back(format!("Go! {}!", battle.sides[PLAYER].active().name(&library)));
+        world.write_resource::<Pokedex>().observe(&battle);
+        self.battle = Some(battle);
+        self.phase = BattlePhase::Message;
+
+        true
+    }
+
+    fn create_text(&self, world: &mut World, transform: UiTransform, color: [f32; 4]) -> Entity {
+        let font = resolve::load_font_handle(world);
+        let mut text = UiText::new(font, "".to_string(), color, 12.);
+        text.line_mode = LineMode::Wrap;
+        text.align = Anchor::TopLeft;
+
+        world.create_entity()
+            .with(transform)
+            .with(text)
+            .build()
+    }
+
+    fn initialize_ui(&mut self, world: &mut World) {
+        let (width, height) = {
+            let dim = world.read_resource::<VirtualScreen>();
+            (dim.width, dim.height)
+        };
+
+        let background = world.create_entity()
+            .with(UiTransform::new(
+                "battle_background".to_string(),
+                Anchor::Middle,
+                Anchor::Middle,
+                0.,
+                0.,
+                10.,
+                width,
+                height,
+            ).with_stretch(Stretch::XY {
+                x_margin: 0.,
+                y_margin: 0.,
+                keep_aspect_ratio: false,
+            }))
+            .with(UiImage::SolidColor([0.97, 0.97, 0.9, 1.]))
+            .build();
+
+        let sprite_handle = resolve::load_spritesheet_handle(world, "dialogs/sheet".to_string());
+        let dialog_box = world.create_entity()
+            .with(UiTransform::new(
+                "battle_box".to_string(),
+                Anchor::BottomMiddle,
+                Anchor::BottomMiddle,
+                0.,
+                0.,
+                11.,
+                width,
+                48.,
+            ))
+            .with(UiImage::Sprite(SpriteRender {
+                sprite_sheet: sprite_handle,
+                sprite_number: DialogSpritetype::Bottom.to_sprite_index(),
+            }))
+            .build();
+
+        let dark = [0.1, 0.1, 0.1, 1.];
+        let opponent_info = self.create_text(world, UiTransform::new(
+            "battle_opponent_info".to_string(),
+            Anchor::TopLeft,
+            Anchor::TopLeft,
+            10.,
+            -8.,
+            12.,
+            140.,
+            28.,
+        ), dark);
+
+        let player_info = self.create_text(world, UiTransform::new(
+            "battle_player_info".to_string(),
+            Anchor::BottomRight,
+            Anchor::BottomRight,
+            -10.,
+            84.,
+            12.,
+            140.,
+            28.,
+        ), dark);
+
+        let text = self.create_text(world, UiTransform::new(
+            "battle_text".to_string(),
+            Anchor::BottomLeft,
+            Anchor::MiddleLeft,
+            10.,
+            24.,
+            12.,
+            width - 20.,
+            40.,
+        ), [1., 1., 1., 1.]);
+
+        self.ui = Some(BattleUi {
+            entities: vec![background, dialog_box, opponent_info, player_info, text],
+            opponent_info,
+            player_info,
+            text,
+        });
+    }
+
+    fn info(pokemon: &Pokemon, library: &Library) -> String {
+        let status = pokemon.status.map(|s| format!("  {}", s.label())).unwrap_or_default();
+        format!("{}  Lv{}{}\nHP {}/{}", pokemon.name(library), pokemon.level, status, pokemon.hp, pokemon.stats.hp)
+    }
+
+    fn move_entries(&self, library: &Library) -> Vec<ListEntry> {
+        let battle = match &self.battle {
+            Some(battle) => battle,
+            None => return Vec::new(),
+        };
+
+        battle.sides[PLAYER].active().moves.iter()
+            .map(|slot| {
+                let (name, pp) = library.get_move(&slot.id)
+                    .map(|m| (m.name.clone(), m.pp))
+                    .unwrap_or_else(|| (slot.id.clone(), slot.pp));
+                ListEntry::new(format!("{}  PP {}/{}", name, slot.pp, pp))
+            })
+            .collect()
+    }
+
+    // Switches phase, replacing the list shown for the old one.
+    fn set_phase(&mut self, world: &mut World, phase: BattlePhase) {
+        if let Some(list) = self.list.take() {
+            ListWidget::delete(world, list);
+        }
+
+        self.phase = phase;
+        let width = world.read_resource::<VirtualScreen>().width;
+        let list = match phase {
+            BattlePhase::Command => Some((
+                ListLayout::new("battle_commands", Anchor::BottomRight, -8., 4., 12., 80.),
+                COMMANDS.iter().map(|c| ListEntry::new(c.label())).collect(),
+            )),
+            BattlePhase::Moves => Some((
+                ListLayout::new("battle_moves", Anchor::BottomLeft, 8., 4., 12., width - 16.),
+                self.move_entries(&world.read_resource::<Library>()),
+            )),
+            BattlePhase::Nickname => Some((
+                ListLayout::new("battle_nickname", Anchor::BottomRight, -8., 50., 12., 56.),
+                vec![ListEntry::new("YES"), ListEntry::new("NO")],
+            )),
+            BattlePhase::Transition | BattlePhase::Message | BattlePhase::Throw => None,
+        };
+
+        if let Some((layout, entries)) = list {
+            self.list = Some(ListWidget::create(world, &layout, entries));
+        }
+    }
+
+    fn refresh(&self, world: &mut World) {
+        let (ui, battle) = match (&self.ui, &self.battle) {
+            (Some(ui), Some(battle)) => (ui, battle),
+            _ => return,
+        };
+
+        let library = world.read_resource::<Library>();
+        let mut texts = world.write_storage::<UiText>();
+
+        if let Some(text) = texts.get_mut(ui.opponent_info) {
+            text.text = BattleState::info(battle.sides[OPPONENT].active(), &library);
+        }
+
+        if let Some(text) = texts.get_mut(ui.player_info) {
+            text.text = BattleState::info(battle.sides[PLAYER].active(), &library);
+        }
+
+        if let Some(text) = texts.get_mut(ui.text) {
+            text.text = match self.phase {
+                BattlePhase::Transition => "".to_string(),
+                BattlePhase::Message => self.messages.front().cloned().unwrap_or_default(),
+                BattlePhase::Command => format!("What will\n{} do?", battle.sides[PLAYER].active().name(&library)),
+                BattlePhase::Moves => "".to_string(),
+                BattlePhase::Throw => self.messages.front().cloned().unwrap_or_default(),
+                BattlePhase::Nickname => format!(
+                    "Give a nickname to the\ncaught {}?",
+                    battle.sides[OPPONENT].active().name(&library),
+                ),
+            };
+        }
+    }
+
+    fn play(&mut self, world: &mut World, action: BattleAction) {
+        let mut shakes = None;
+        {
+            let library = world.read_resource::<Library>();
+            let mut pokedex = world.write_resource::<Pokedex>();
+            let battle = match &mut self.battle {
+                Some(battle) => battle,
+                None => return,
+            };
+
+            let opponent_action = match &mut self.ai {
+                Some(ai) => ai.choose(battle, OPPONENT, &library),
+                None => BattleAction::Fight(0),
+            };
+            let events = battle.play_turn(&library, [action, opponent_action], &mut self.rng);
+            for event in &events {
+                pokedex.record(event);
+                if let BattleEvent::BallShook(count) = event {
+                    shakes = Some(*count);
+                }
+                if let Some(text) = battle.describe(event, &library) {
+                    self.messages.push_back(text);
+                }
+            }
+        }
+
+        self.sync_party(world);
+
+        // The ball is shown shaking under the throw message before the rest of the turn.
+        if let Some(shakes) = shakes {
+            self.set_phase(world, BattlePhase::Throw);
+            BallShake::create(world, shakes);
+            return;
+        }
+
+        let phase = if self.messages.is_empty() { BattlePhase::Command } else { BattlePhase::Message };
+        self.set_phase(world, phase);
+    }
+
+    fn throw_ball(&mut self, world: &mut World, item: String) {
+        {
+            let library = world.read_resource::<Library>();
+            for player in (&mut world.write_storage::<Player>()).join() {
+                player.bag.remove(&library, &item, 1).expect("threw a ball that is not in the bag");
+            }
+        }
+
+        self.play(world, BattleAction::Throw(item));
+    }
+
+    fn end_throw(&mut self, world: &mut World) {
+        self.messages.pop_front();
+        let phase = if self.messages.is_empty() { BattlePhase::Command } else { BattlePhase::Message };
+        self.set_phase(world, phase);
+    }
+
+    // Sends the caught pokemon to the party, or to the PC when the party is full.
+    fn store_caught(&mut self, world: &mut World) {
+        let battle = match &mut self.battle {
+            Some(battle) => battle,
+            None => return,
+        };
+
+        let pokemon = battle.sides[OPPONENT].active().clone();
+        let team = &mut battle.sides[PLAYER].team;
+        let caught = if team.len() < PARTY_SIZE {
+            team.push(pokemon);
+            Caught::Party(team.len() - 1)
+        } else {
+            match world.write_resource::<Storage>().store(pokemon) {
+                Ok((index, slot)) => Caught::Box(index, slot),
+                Err((_, error)) => {
+                    eprintln!("failed to store a caught pokemon: {:?}", error);
+                    Caught::Lost
+                }
+            }
+        };
+
+        self.caught = Some(caught);
+        self.sync_party(world);
+    }
+
+    // Names the caught pokemon and tells the player where it went. The battle ends once
+    // that has been read.
+    fn name_caught(&mut self, world: &mut World, nickname: Option<String>) -> SimpleTrans {
+        let (name, box_name) = {
+            let library = world.read_resource::<Library>();
+            let mut storage = world.write_resource::<Storage>();
+            let pokemon = match (self.caught, &mut self.battle) {
+                (Some(Caught::Party(index)), Some(battle)) => battle.sides[PLAYER].team.get_mut(index),
+                (Some(Caught::Box(index, slot)), _) => storage.get_mut(index, slot),
+                _ => None,
+            };
+
+            let name = pokemon.map(|pokemon| {
+                if nickname.is_some() {
+                    pokemon.nickname = nickname;
+                }
+                pokemon.name(&library)
+            });
+
+            let box_name = match self.caught {
+                Some(Caught::Box(index, _)) => storage.get_box(index).map(|b| b.name.clone()),
+                _ => None,
+            };
+            (name.unwrap_or_default(), box_name)
+        };
+
+        if let Some(box_name) = box_name {
+            self.messages.push_back(format!("{} was transferred to\n{} on the PC.", name, box_name));
+        }
+
+        self.sync_party(world);
+        if self.messages.is_empty() {
+            return Trans::Pop;
+        }
+
+        self.set_phase(world, BattlePhase::Message);
+        Trans::None
+    }
+
+    // Copies the battling team back to the player, so the party and bag screens opened
+    // during the battle show it as it is.
+    fn sync_party(&self, world: &mut World) {
+        if let Some(battle) = &self.battle {
+            for player in (&mut world.write_storage::<Player>()).join() {
+                player.party = Party::new(battle.sides[PLAYER].team.clone());
+            }
+        }
+    }
+
+    fn use_item(&mut self, world: &mut World, item: String, target: usize) {
+        {
+            let library = world.read_resource::<Library>();
+            for player in (&mut world.write_storage::<Player>()).join() {
+                player.bag.remove(&library, &item, 1).expect("used an item that is not in the bag");
+            }
+        }
+
+        self.play(world, BattleAction::Item { item, target });
+    }
+
+    fn switch(&mut self, world: &mut World, index: usize) {
+        let refusal = {
+            let library = world.read_resource::<Library>();
+            let side = match &self.battle {
+                Some(battle) => &battle.sides[PLAYER],
+                None => return,
+            };
+
+            match side.team.get(index) {
+                Some(pokemon) if index == side.active => Some(format!("{} is already in battle!", pokemon.name(&library))),
+                Some(pokemon) if pokemon.is_fainted() => Some(format!("{} has no energy left to battle!", pokemon.name(&library))),
+                Some(_) => None,
+                None => return,
+            }
+        };
+
+        match refusal {
+            Some(message) => {
+                self.messages.push_back(message);
+                self.set_phase(world, BattlePhase::Message);
+            }
+            None => self.play(world, BattleAction::Switch(index)),
+        }
+    }
+
+    fn handle_list_event(&mut self, world: &mut World, event: ListEvent) -> SimpleTrans {
+        if Some(event.list) != self.list {
+            return Trans::None;
+        }
+
+        match (self.phase, event.kind) {
+            (BattlePhase::Command, ListEventKind::Confirmed(index)) => match COMMANDS[index] {
+                Command::Fight => {
+                    if self.battle.as_ref().map(|battle| battle.must_struggle(PLAYER)).unwrap_or(false) {
+                        self.play(world, BattleAction::Struggle);
+                    } else {
+                        self.set_phase(world, BattlePhase::Moves);
+                    }
+                }
+                Command::Bag => return Trans::Push(Box::new(BagState::new(BagContext::Battle))),
+                Command::Pokemon => return Trans::Push(Box::new(PartyState::choose("Choose a POKEMON."))),
+                Command::Run => self.play(world, BattleAction::Run),
+            },
+            (BattlePhase::Moves, ListEventKind::Confirmed(index)) => self.play(world, BattleAction::Fight(index)),
+            (BattlePhase::Moves, ListEventKind::Cancelled) => self.set_phase(world, BattlePhase::Command),
+            (BattlePhase::Nickname, ListEventKind::Confirmed(0)) => {
+                let species = {
+                    let library = world.read_resource::<Library>();
+                    self.battle.as_ref()
+                        .and_then(|battle| library.get_species(&battle.sides[OPPONENT].active().species))
+                        .map(|species| species.name.clone())
+                        .unwrap_or_default()
+                };
+                return Trans::Push(Box::new(NamingState::new(&format!("{}'s nickname?", species))));
+            }
+            (BattlePhase::Nickname, ListEventKind::Confirmed(_)) | (BattlePhase::Nickname, ListEventKind::Cancelled) => {
+                return self.name_caught(world, None);
+            }
+            _ => return Trans::None,
+        }
+
+        self.refresh(world);
+        Trans::None
+    }
+
+    fn set_list_active(&self, world: &mut World, active: bool) {
+        let mut widgets = world.write_storage::<ListWidget>();
+        if let Some(widget) = self.list.and_then(|list| widgets.get_mut(list)) {
+            widget.set_active(active);
+        }
+    }
+}
+
+impl SimpleState for BattleState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        {
+            let mut game = self.fetch_game(world);
+            self.was_paused = game.paused;
+            game.paused = true;
+        }
+
+        self.reader = Some(list::register_reader(world));
+        world.write_resource::<Music>().set_override(Some("battle".to_string()));
+        BattleTransition::create(world);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        world.write_resource::<Music>().set_override(None);
+
+        if let Some(list) = self.list.take() {
+            ListWidget::delete(world, list);
+        }
+
+        if let Some(ui) = self.ui.take() {
+            for entity in &ui.entities {
+                world.delete_entity(*entity).expect("failed to delete battle ui");
+            }
+        }
+
+        let mut outcome = BattleOutcome::Fled;
+
+        if let Some(battle) = self.battle.take() {
+            outcome = battle.outcome.unwrap_or(BattleOutcome::Fled);
+            let library = world.read_resource::<Library>();
+            let mut party = Party::new(battle.sides[PLAYER].team.clone());
+
+            // Blacking out sends the player back with a restored party.
+            if battle.outcome == Some(BattleOutcome::Lost) {
+                party.restore_all(&library);
+            }
+
+            for player in (&mut world.write_storage::<Player>()).join() {
+                player.party = party.clone();
+            }
+        }
+
+        let mut game = self.fetch_game(world);
+        game.paused = self.was_paused;
+        game.check_evolutions = outcome != BattleOutcome::Lost;
+        game.set_trigger(Trigger::BattleEnd(outcome));
+    }
+
+    fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        let action = match event {
+            StateEvent::Input(InputEvent::ActionPressed(action)) => action,
+            _ => return Trans::None,
+        };
+
+        let world = data.world;
+
+        match self.phase {
+            BattlePhase::Transition => {}
+            BattlePhase::Message => {
+                if action == "continue" {
+                    self.messages.pop_front();
+                    if self.messages.is_empty() {
+                        let outcome = self.battle.as_ref().map(|b| b.outcome);
+                        if outcome == Some(Some(BattleOutcome::Caught)) && self.caught.is_none() {
+                            self.store_caught(world);
+                            let phase = if self.caught == Some(Caught::Lost) {
+                                self.messages.push_back("But there was no room left for it...".to_string());
+                                BattlePhase::Message
+                            } else {
+                                BattlePhase::Nickname
+                            };
+                            self.set_phase(world, phase);
+                            self.refresh(world);
+                            return Trans::None;
+                        }
+
+                        if outcome.map(|o| o.is_some()).unwrap_or(true) {
+                            return Trans::Pop;
+                        }
+                        self.set_phase(world, BattlePhase::Command);
+                    }
+                }
+            }
+            // The lists handle their own input, and nothing can be done while a ball shakes.
+            BattlePhase::Command | BattlePhase::Moves | BattlePhase::Nickname | BattlePhase::Throw => {}
+        }
+
+        self.refresh(world);
+        Trans::None
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let world = &mut data.world;
+
+        let trigger;
+        let dead_entities;
+
+        {
+            let mut game = self.fetch_game(world);
+            trigger = game.get_trigger();
+            dead_entities = game.dead_entities.clone();
+            game.dead_entities.clear();
+            game.clear_trigger();
+        }
+
+        for entity in &dead_entities {
+            world.delete_entity(*entity).expect("failed to delete entity");
+        }
+
+        match trigger {
+            Some(Trigger::BattleTransitionEnd) => {
+                if !self.start_battle(world) {
+                    return Trans::Pop;
+                }
+
+                self.initialize_ui(world);
+                self.refresh(world);
+            }
+            Some(Trigger::ItemChosen { item, target }) => {
+                self.use_item(world, item, target);
+                self.refresh(world);
+            }
+            Some(Trigger::PartyChosen(Some(index))) => {
+                self.switch(world, index);
+                self.refresh(world);
+            }
+            Some(Trigger::BallChosen(item)) => {
+                self.throw_ball(world, item);
+                self.refresh(world);
+            }
+            Some(Trigger::BallShakeEnd) => {
+                self.end_throw(world);
+                self.refresh(world);
+            }
+            Some(Trigger::NameChosen(name)) => {
+                let trans = self.name_caught(world, name);
+                self.refresh(world);
+                if let Trans::Pop = trans {
+                    return trans;
+                }
+            }
+            _ => {}
+        }
+
+        let events = match &mut self.reader {
+            Some(reader) => list::read_events(world, reader),
+            None => return Trans::None,
+        };
+
+        for event in events {
+            match self.handle_list_event(world, event) {
+                Trans::None => {}
+                trans => return trans,
+            }
+        }
+
+        Trans::None
+    }
+
+    fn on_pause(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.set_list_active(data.world, false);
+    }
+
+    fn on_resume(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.set_list_active(data.world, true);
+    }
+}
@@ -0,0 +1,248 @@
+use amethyst::{
+    prelude::*,
+    ecs::Entity,
+    input::{Bindings, Button, InputEvent, InputHandler, StringBindings, VirtualKeyCode},
+    shrev::ReaderId,
+    ui::{UiTransform, UiText, UiImage, Anchor, LineMode, Stretch},
+};
+
+use crate::entity::menu::list::{self, ListEntry, ListEvent, ListEventKind, ListLayout, ListWidget};
+use crate::utils::{controls::{self, Device, ACTIONS}, data, resolve, screen::VirtualScreen};
+
+// The list scrolls to fit between the title and the prompt.
+const VISIBLE_ROWS: usize = 6;
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum ControlsEntry {
+    Action(usize),
+    Reset,
+    Close,
+}
+
+fn entry_at(index: usize) -> ControlsEntry {
+    match index {
+        i if i < ACTIONS.len() => ControlsEntry::Action(i),
+        i if i == ACTIONS.len() => ControlsEntry::Reset,
+        _ => ControlsEntry::Close,
+    }
+}
+
+// Lists the key and gamepad button of every action. Confirming an action waits for the next
+// key or button and binds it; the bindings are written to the user directory on close.
+pub struct ControlsState {
+    entities: Vec<Entity>,
+    prompt: Option<Entity>,
+    list: Option<Entity>,
+    reader: Option<ReaderId<ListEvent>>,
+    // The action waiting for a key or button.
+    capturing: Option<usize>,
+}
+
+impl ControlsState {
+    pub fn new() -> Self {
+        ControlsState {
+            entities: Vec::new(),
+            prompt: None,
+            list: None,
+            reader: None,
+            capturing: None,
+        }
+    }
+
+    fn entries(bindings: &Bindings<StringBindings>) -> Vec<ListEntry> {
+        ACTIONS.iter()
+            .map(|(action, name)| ListEntry::new(format!(
+                "{:<8} {:<10} {}",
+                name,
+                controls::label(bindings, action, Device::Keyboard),
+                controls::label(bindings, action, Device::Gamepad),
+            )))
+            .chain(vec![ListEntry::new("RESET TO DEFAULTS"), ListEntry::new("CLOSE")])
+            .collect()
+    }
+
+    fn initialize_ui(&mut self, world: &mut World) {
+        let (width, height) = {
+            let dim = world.read_resource::<VirtualScreen>();
+            (dim.width, dim.height)
+        };
+
+        let background = world.create_entity()
+            .with(UiTransform::new(
+                "controls_background".to_string(),
+                Anchor::Middle,
+                Anchor::Middle,
+                0.,
+                0.,
+                27.,
+                width,
+                height,
+            ).with_stretch(Stretch::XY {
+                x_margin: 0.,
+                y_margin: 0.,
+                keep_aspect_ratio: false,
+            }))
+            .with(UiImage::SolidColor([0.3, 0.35, 0.6, 1.]))
+            .build();
+
+        let font = resolve::load_font_handle(world);
+        let mut ui_text = UiText::new(font.clone(), "CONTROLS".to_string(), [1., 1., 1., 1.], 12.);
+        ui_text.line_mode = LineMode::Single;
+        ui_text.align = Anchor::TopLeft;
+
+        let title = world.create_entity()
+            .with(UiTransform::new(
+                "controls_title".to_string(),
+                Anchor::TopLeft,
+                Anchor::TopLeft,
+                10.,
+                -8.,
+                28.,
+                width - 20.,
+                14.,
+            ))
+            .with(ui_text)
+            .build();
+
+        let mut prompt_text = UiText::new(font, "".to_string(), [1., 1., 1., 1.], 10.);
+        prompt_text.line_mode = LineMode::Wrap;
+        prompt_text.align = Anchor::TopLeft;
+
+        let prompt = world.create_entity()
+            .with(UiTransform::new(
+                "controls_prompt".to_string(),
+                Anchor::BottomLeft,
+                Anchor::BottomLeft,
+                10.,
+                6.,
+                28.,
+                width - 20.,
+                30.,
+            ))
+            .with(prompt_text)
+            .build();
+
+        let entries = ControlsState::entries(&world.read_resource::<InputHandler<StringBindings>>().bindings);
+        let list = ListWidget::create(
+            world,
+            &ListLayout::new("controls", Anchor::TopLeft, 8., -22., 28., width - 16.)
+                .with_rows(VISIBLE_ROWS)
+                .with_frame(None)
+                .with_color([1., 1., 1., 1.]),
+            entries,
+        );
+
+        self.entities = vec![background, title, prompt];
+        self.prompt = Some(prompt);
+        self.list = Some(list);
+    }
+
+    fn set_prompt(&self, world: &mut World, text: String) {
+        let mut texts = world.write_storage::<UiText>();
+        if let Some(ui_text) = self.prompt.and_then(|prompt| texts.get_mut(prompt)) {
+            ui_text.text = text;
+        }
+    }
+
+    fn refresh(&self, world: &mut World) {
+        let entries = ControlsState::entries(&world.read_resource::<InputHandler<StringBindings>>().bindings);
+        let mut widgets = world.write_storage::<ListWidget>();
+        if let Some(widget) = self.list.and_then(|list| widgets.get_mut(list)) {
+            widget.set_entries(entries);
+        }
+    }
+
+    fn set_capturing(&mut self, world: &mut World, capturing: Option<usize>) {
+        self.capturing = capturing;
+
+        let prompt = match capturing {
+            Some(i) => format!("PRESS A KEY OR BUTTON FOR {}.\nESCAPE KEEPS THE CURRENT ONE.", ACTIONS[i].1),
+            None => "".to_string(),
+        };
+        self.set_prompt(world, prompt);
+
+        let mut widgets = world.write_storage::<ListWidget>();
+        if let Some(widget) = self.list.and_then(|list| widgets.get_mut(list)) {
+            widget.set_active(capturing.is_none());
+        }
+    }
+
+    fn reset(&mut self, world: &mut World) {
+        let path = resolve::get_config_path("input.ron");
+        match data::load_ron::<Bindings<StringBindings>>(&path) {
+            Ok(bindings) => world.write_resource::<InputHandler<StringBindings>>().bindings = bindings,
+            Err(e) => eprintln!("failed to load {}: {}", path.display(), e),
+        }
+        self.refresh(world);
+    }
+}
+
+impl SimpleState for ControlsState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.reader = Some(list::register_reader(data.world));
+        self.initialize_ui(data.world);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        if let Err(e) = controls::write(&world.read_resource::<InputHandler<StringBindings>>().bindings) {
+            eprintln!("failed to write controls: {}", e);
+        }
+
+        if let Some(list) = self.list.take() {
+            ListWidget::delete(world, list);
+        }
+
+        for entity in self.entities.drain(..) {
+            world.delete_entity(entity).expect("failed to delete controls ui");
+        }
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let events = match &mut self.reader {
+            Some(reader) => list::read_events(data.world, reader),
+            None => return Trans::None,
+        };
+
+        let list = self.list;
+        for event in events.into_iter().filter(|e| Some(e.list) == list) {
+            match event.kind {
+                ListEventKind::Confirmed(index) => match entry_at(index) {
+                    ControlsEntry::Action(i) => self.set_capturing(data.world, Some(i)),
+                    ControlsEntry::Reset => self.reset(data.world),
+                    ControlsEntry::Close => return Trans::Pop,
+                },
+                ListEventKind::Cancelled => return Trans::Pop,
+                _ => {}
+            }
+        }
+
+        Trans::None
+    }
+
+    fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        let world = data.world;
+
+        let action = match self.capturing {
+            Some(i) => ACTIONS[i].0,
+            None => return Trans::None,
+        };
+
+        // Keys are bound by their key code rather than their scan code.
+        match event {
+            StateEvent::Input(InputEvent::ButtonPressed(Button::Key(VirtualKeyCode::Escape))) => {
+                self.set_capturing(world, None);
+            }
+            StateEvent::Input(InputEvent::ButtonPressed(button @ Button::Key(_))) |
+            StateEvent::Input(InputEvent::ButtonPressed(button @ Button::Controller(_, _))) => {
+                controls::rebind(&mut world.write_resource::<InputHandler<StringBindings>>().bindings, action, button);
+                self.refresh(world);
+                self.set_capturing(world, None);
+            }
+            _ => {}
+        }
+
+        Trans::None
+    }
+}
@@ -7,15 +7,19 @@ use crate::entity::dialog::talk_dialog::TalkDialog;
 use crate::state::{Game, Trigger};
 
 
-pub struct DialogState {}
+pub struct DialogState {
+    text: Vec<String>
+}
 
 impl DialogState {
-    pub fn new() -> Self {
-        DialogState {}
+    pub fn new(text: Vec<String>) -> Self {
+        DialogState {
+            text
+        }
     }
 
     fn initialize_dialog(&mut self, world: &mut World) {
-        TalkDialog::create(world);
+        TalkDialog::create(world, self.text.clone());
     }
 
     fn fetch_game<'s>(&mut self, world: &'s mut World) -> FetchMut<'s, Game> {
@@ -35,31 +39,23 @@ impl SimpleState for DialogState {
     fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
         let world = &mut data.world;
 
-        let mut trigger = None;
-        let mut dead_entities = Vec::new();
-
-        {
+        let (trigger, dead_entities) = {
             let mut game = self.fetch_game(world);
-            trigger = game.get_trigger();
-            dead_entities = game.dead_entities.clone();
+            let trigger = game.get_trigger();
+            let dead_entities = game.dead_entities.clone();
             game.dead_entities.clear();
             game.clear_trigger();
-        }
+            (trigger, dead_entities)
+        };
 
         for entity in &dead_entities {
-            world.delete_entity(*entity);
+            world.delete_entity(*entity).expect("failed to delete dialog entity");
         }
 
-        if trigger.is_some() {
-            let mut trans = Trans::None;
-
-            match trigger.unwrap() {
-                Trigger::DialogEnd => { trans = Trans::Pop; }
-            };
-
-            trans
-        } else {
-            Trans::None
+        // Only the end of the dialog concerns this state; other triggers are dropped.
+        match trigger {
+            Some(Trigger::DialogEnd) => Trans::Pop,
+            _ => Trans::None,
         }
     }
 }
\ No newline at end of file
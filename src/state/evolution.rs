@@ -0,0 +1,329 @@
+use std::collections::VecDeque;
+
+use amethyst::{
+    prelude::*,
+    ecs::{Entity, Join},
+    input::InputEvent,
+    renderer::SpriteRender,
+    shred::FetchMut,
+    ui::{UiTransform, UiText, UiImage, Anchor, LineMode, Stretch},
+};
+
+use crate::entity::actor::player::Player;
+use crate::entity::dialog::DialogSpritetype;
+use crate::entity::evolution::animation::EvolutionAnimation;
+use crate::pokedex::Pokedex;
+use crate::pokemon::{evolution::{self, Evolution}, library::Library, Pokemon};
+use crate::state::{Game, Trigger};
+use crate::utils::{resolve, screen::VirtualScreen};
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum EvolutionPhase {
+    // Announcing the evolution, before the animation.
+    Intro,
+    Animating,
+    // The pokemon evolved or was stopped; the next one starts once the messages are read.
+    Done,
+}
+
+// Evolves party members one after the other. Each gets an animation that the player can
+// stop with "cancel", leaving the pokemon as it was.
+pub struct EvolutionState {
+    // Party members and the evolution they are going through, the first being the current one.
+    queue: VecDeque<(usize, Evolution)>,
+    phase: EvolutionPhase,
+    messages: VecDeque<String>,
+    entities: Vec<Entity>,
+    text: Option<Entity>,
+    animation: Option<Entity>,
+    was_paused: bool,
+}
+
+impl EvolutionState {
+    pub fn new(queue: Vec<(usize, Evolution)>) -> Self {
+        EvolutionState {
+            queue: queue.into_iter().collect(),
+            phase: EvolutionPhase::Intro,
+            messages: VecDeque::new(),
+            entities: Vec::new(),
+            text: None,
+            animation: None,
+            was_paused: false,
+        }
+    }
+
+    // Party members that a battle or level up lets evolve.
+    pub fn pending(world: &World) -> Vec<(usize, Evolution)> {
+        let library = world.read_resource::<Library>();
+        let players = world.read_storage::<Player>();
+
+        (&players).join()
+            .next()
+            .map(|player| player.party.members().iter()
+                .enumerate()
+                .filter(|(_, pokemon)| !pokemon.is_fainted())
+                .filter_map(|(i, pokemon)| {
+                    evolution::find(&library, pokemon, evolution::EvolutionTrigger::LevelUp).map(|e| (i, e.clone()))
+                })
+                .collect())
+            .unwrap_or_default()
+    }
+
+    fn fetch_game<'s>(&mut self, world: &'s mut World) -> FetchMut<'s, Game> {
+        world.write_resource::<Game>()
+    }
+
+    fn member(world: &World, index: usize) -> Option<Pokemon> {
+        (&world.read_storage::<Player>()).join()
+            .next()
+            .and_then(|player| player.party.get(index).cloned())
+    }
+
+    fn icon(library: &Library, species: &str) -> usize {
+        library.get_species(species)
+            .and_then(|s| s.types.first().cloned())
+            .map(|t| t.icon_index())
+            .unwrap_or(0)
+    }
+
+    fn initialize_ui(&mut self, world: &mut World) {
+        let (width, height) = {
+            let dim = world.read_resource::<VirtualScreen>();
+            (dim.width, dim.height)
+        };
+
+        let background = world.create_entity()
+            .with(UiTransform::new(
+                "evolution_background".to_string(),
+                Anchor::Middle,
+                Anchor::Middle,
+                0.,
+                0.,
+                27.,
+                width,
+                height,
+            ).with_stretch(Stretch::XY {
+                x_margin: 0.,
+                y_margin: 0.,
+                keep_aspect_ratio: false,
+            }))
+            .with(UiImage::SolidColor([0.1, 0.1, 0.15, 1.]))
+            .build();
+
+        let sprite_sheet = resolve::load_spritesheet_handle(world, "dialogs/sheet".to_string());
+        let dialog_box = world.create_entity()
+            .with(UiTransform::new(
+                "evolution_dialog_box".to_string(),
+                Anchor::BottomMiddle,
+                Anchor::BottomMiddle,
+                0.,
+                0.,
+                28.,
+                width,
+                48.,
+            ))
+            .with(UiImage::Sprite(SpriteRender {
+                sprite_sheet,
+                sprite_number: DialogSpritetype::Bottom.to_sprite_index(),
+            }))
+            .build();
+
+        let font = resolve::load_font_handle(world);
+        let mut ui_text = UiText::new(font, "".to_string(), [1., 1., 1., 1.], 12.);
+        ui_text.line_mode = LineMode::Wrap;
+        ui_text.align = Anchor::TopLeft;
+
+        let text = world.create_entity()
+            .with(UiTransform::new(
+                "evolution_text".to_string(),
+                Anchor::BottomLeft,
+                Anchor::MiddleLeft,
+                10.,
+                24.,
+                29.,
+                width - 20.,
+                40.,
+            ))
+            .with(ui_text)
+            .build();
+
+        self.text = Some(text);
+        self.entities = vec![background, dialog_box, text];
+    }
+
+    fn refresh(&self, world: &mut World) {
+        let message = self.messages.front().cloned().unwrap_or_default();
+        let mut texts = world.write_storage::<UiText>();
+        if let Some(text) = self.text.and_then(|e| texts.get_mut(e)) {
+            text.text = message;
+        }
+    }
+
+    fn delete_animation(&mut self, world: &mut World) {
+        if let Some(animation) = self.animation.take() {
+            world.delete_entity(animation).expect("failed to delete evolution animation");
+        }
+    }
+
+    // Announces the evolution at the front of the queue, or pops when there is none left.
+    fn start_next(&mut self, world: &mut World) -> SimpleTrans {
+        self.delete_animation(world);
+
+        let (member, _) = match self.queue.front() {
+            Some(entry) => entry.clone(),
+            None => return Trans::Pop,
+        };
+        let name = match EvolutionState::member(world, member) {
+            Some(pokemon) => pokemon.name(&world.read_resource::<Library>()),
+            None => {
+                self.queue.pop_front();
+                return self.start_next(world);
+            }
+        };
+
+        self.messages.push_back(format!("What?\n{} is evolving!", name));
+        self.phase = EvolutionPhase::Intro;
+        Trans::None
+    }
+
+    fn animate(&mut self, world: &mut World) {
+        let (member, evolution) = match self.queue.front() {
+            Some(entry) => entry.clone(),
+            None => return,
+        };
+
+        let (from, into) = {
+            let library = world.read_resource::<Library>();
+            let from = EvolutionState::member(world, member)
+                .map(|pokemon| EvolutionState::icon(&library, &pokemon.species))
+                .unwrap_or(0);
+            (from, EvolutionState::icon(&library, &evolution.into))
+        };
+
+        self.animation = Some(EvolutionAnimation::create(world, from, into));
+        self.phase = EvolutionPhase::Animating;
+    }
+
+    fn cancel(&mut self, world: &mut World) {
+        let member = self.queue.pop_front().map(|(member, _)| member);
+        let name = member
+            .and_then(|member| EvolutionState::member(world, member))
+            .map(|pokemon| pokemon.name(&world.read_resource::<Library>()))
+            .unwrap_or_default();
+
+        self.delete_animation(world);
+        self.messages.push_back(format!("Huh?\n{} stopped evolving!", name));
+        self.phase = EvolutionPhase::Done;
+    }
+
+    fn finish(&mut self, world: &mut World) {
+        let (member, evolution) = match self.queue.pop_front() {
+            Some(entry) => entry,
+            None => return,
+        };
+
+        {
+            let library = world.read_resource::<Library>();
+            let mut pokedex = world.write_resource::<Pokedex>();
+            let mut players = world.write_storage::<Player>();
+
+            if let Some(pokemon) = (&mut players).join().next().and_then(|player| player.party.get_mut(member)) {
+                let before = pokemon.name(&library);
+                let learnt = evolution::evolve(&library, pokemon, &evolution);
+                let species = library.get_species(&evolution.into).map(|s| s.name.clone()).unwrap_or_default();
+                let after = pokemon.name(&library);
+
+                pokedex.catch(&evolution.into);
+                self.messages.push_back(format!("Congratulations! Your {}\nevolved into {}!", before, species));
+                for id in learnt {
+                    let name = library.get_move(&id).map(|m| m.name.clone()).unwrap_or(id);
+                    self.messages.push_back(format!("{} learned\n{}!", after, name));
+                }
+            }
+        }
+
+        self.phase = EvolutionPhase::Done;
+    }
+}
+
+impl SimpleState for EvolutionState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        {
+            let mut game = self.fetch_game(world);
+            self.was_paused = game.paused;
+            game.paused = true;
+        }
+
+        self.initialize_ui(world);
+        self.start_next(world);
+        self.refresh(world);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        self.delete_animation(world);
+        for entity in self.entities.drain(..) {
+            world.delete_entity(entity).expect("failed to delete evolution screen");
+        }
+
+        let was_paused = self.was_paused;
+        self.fetch_game(world).paused = was_paused;
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let world = &mut data.world;
+
+        if self.queue.is_empty() && self.messages.is_empty() {
+            return Trans::Pop;
+        }
+
+        let ended = {
+            let mut game = self.fetch_game(world);
+            match game.get_trigger() {
+                Some(Trigger::EvolutionEnd) => {
+                    game.clear_trigger();
+                    true
+                }
+                _ => false,
+            }
+        };
+
+        if ended && self.phase == EvolutionPhase::Animating {
+            self.finish(world);
+            self.refresh(world);
+        }
+
+        Trans::None
+    }
+
+    fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        let action = match event {
+            StateEvent::Input(InputEvent::ActionPressed(action)) => action,
+            _ => return Trans::None,
+        };
+
+        let world = data.world;
+        let mut trans = Trans::None;
+
+        match (self.phase, action.as_str()) {
+            (EvolutionPhase::Animating, "cancel") => self.cancel(world),
+            (EvolutionPhase::Animating, _) => {}
+            (_, "continue") => {
+                self.messages.pop_front();
+                if self.messages.is_empty() {
+                    match self.phase {
+                        EvolutionPhase::Intro => self.animate(world),
+                        _ => trans = self.start_next(world),
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        self.refresh(world);
+        trans
+    }
+}
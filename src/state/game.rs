@@ -1,47 +1,232 @@
 use amethyst::{
     prelude::*,
-    window::ScreenDimensions,
-    core::{transform::Transform},
+    core::{transform::Transform, math::Vector2},
+    ecs::Join,
+    input::InputEvent,
     renderer::camera::{Camera, Projection},
 };
+use rand::{rngs::StdRng, SeedableRng};
 
-use crate::entity::actor::player::Player;
-use crate::utils::debug;
-use crate::entity::tile::{tile::Tile};
+use crate::clock::Clock;
+use crate::entity::actor::{world_position, npc::Npc, player::Player};
+use crate::entity::camera::{framing, CameraFollow};
+use crate::entity::map::tint::DayTint;
+use crate::item::shop::Shops;
+use crate::utils::{debug, resolve, screen::VirtualScreen};
+use crate::entity::tile::{TileClass, animation::TileAnimations};
+use crate::map::{encounter::EncounterTable, loader, stream::MapStream};
+use crate::pokedex::Pokedex;
+use crate::pokemon::{library::Library, moves::FieldMove};
+use crate::save::SaveData;
+use crate::trainer::Trainers;
+use crate::utils::settings::Settings;
 
+use crate::state::{Game, Trigger};
+use crate::state::battle::BattleState;
 use crate::state::dialog::DialogState;
+use crate::state::evolution::EvolutionState;
+use crate::state::pc::PcState;
+use crate::state::shop::ShopState;
+use crate::state::start_menu::StartMenuState;
+use crate::state::trainer::TrainerState;
+use crate::state::warp::WarpState;
 
-pub struct GameState {}
+pub struct GameState {
+    map: String,
+    intro_shown: bool,
+    // The save the game was started from, consumed while the world is built.
+    save: Option<SaveData>,
+    // A trigger taken while evolutions were pending, handled once they are done.
+    held_trigger: Option<Trigger>,
+    rng: StdRng
+}
 
 impl GameState {
     pub fn new() -> Self {
-        GameState {}
+        GameState {
+            map: "route-1".to_string(),
+            intro_shown: false,
+            save: None,
+            held_trigger: None,
+            rng: StdRng::from_entropy()
+        }
+    }
+
+    fn load_save(&mut self, world: &mut World) {
+        world.insert(Settings::load());
+
+        self.save = SaveData::load().unwrap_or_else(|e| {
+            eprintln!("failed to load the save file, starting a new game: {}", e);
+            None
+        });
+
+        if let Some(save) = &self.save {
+            self.map = save.map.clone();
+            if let Some(time) = save.time {
+                world.write_resource::<Clock>().resume(time);
+            }
+            // The intro is only for new games.
+            self.intro_shown = true;
+        }
+
+        world.write_resource::<Game>().map = self.map.clone();
+    }
+
+    fn initialize_library(&mut self, world: &mut World) {
+        let library = Library::load(&resolve::get_data_path(""))
+            .expect("failed to load species and move data");
+        world.insert(library);
+    }
+
+    fn initialize_trainers(&mut self, world: &mut World) {
+        let trainers = Trainers::load(&resolve::get_data_path("trainers.ron"))
+            .expect("failed to load trainer data");
+        world.insert(trainers);
+
+        let story = self.save.as_ref().map(|save| save.story.clone()).unwrap_or_default();
+        world.insert(story);
+    }
+
+    fn initialize_storage(&mut self, world: &mut World) {
+        let storage = self.save.as_ref().map(|save| save.storage.clone()).unwrap_or_default();
+        world.insert(storage);
+    }
+
+    fn initialize_pokedex(&mut self, world: &mut World) {
+        let pokedex = self.save.as_ref().map(|save| save.pokedex.clone()).unwrap_or_default();
+        world.insert(pokedex);
+    }
+
+    fn initialize_shops(&mut self, world: &mut World) {
+        let shops = Shops::load(&resolve::get_data_path("shops.ron"))
+            .expect("failed to load shop data");
+        world.insert(shops);
+    }
+
+    fn initialize_tile_animations(&mut self, world: &mut World) {
+        let animations = TileAnimations::load(&resolve::get_data_path("tile_animations.ron"))
+            .expect("failed to load tile animations");
+        world.insert(animations);
     }
 
     fn initialize_camera(&mut self, world: &mut World) {
         let (width, height) = {
-            let dim = world.read_resource::<ScreenDimensions>();
-            (dim.width(), dim.height())
+            let dim = world.read_resource::<VirtualScreen>();
+            (dim.width, dim.height)
+        };
+
+        // The camera follows the player, starting right over it.
+        let (player, position) = {
+            let entities = world.entities();
+            let players = world.read_storage::<Player>();
+            match (&entities, &players).join().next() {
+                Some((entity, player)) => (Some(entity), world_position(player.grid_pos)),
+                None => (None, (0.0, 0.0)),
+            }
         };
 
+        let translation = framing(Vector2::new(position.0, position.1), width, height);
         let mut transform = Transform::default();
-        transform.set_translation_xyz(0.0, height, 10.0);
+        transform.set_translation_xyz(translation[0], translation[1], 10.0);
 
+        // The viewport system widens the projection to the window around the virtual screen.
         let mut camera = Camera::standard_3d(width, height);
         camera.set_projection(Projection::orthographic(0.0, width, 0.0, height, 0.0, 20.0));
 
+        world.write_resource::<Game>().camera_trans = *transform.translation();
         world.create_entity()
             .with(camera)
             .with(transform)
+            .with(CameraFollow::new(player))
             .build();
     }
 
     fn initialize_player(&mut self, world: &mut World) {
-        Player::create(world, "nate".to_string());
+        let save = self.save.take().map(|save| save.player);
+        let new_game = save.is_none();
+        Player::create(world, "nate".to_string(), save);
+
+        // The starting party is registered as caught.
+        if new_game {
+            let species: Vec<String> = (&world.read_storage::<Player>()).join()
+                .flat_map(|player| player.party.members().iter().map(|p| p.species.clone()).collect::<Vec<_>>())
+                .collect();
+            let mut pokedex = world.write_resource::<Pokedex>();
+            for id in &species {
+                pokedex.catch(id);
+            }
+        }
     }
 
-    fn initialize_sample_tile(&mut self, world: &mut World) {
-        Tile::create(world, "tileset-bw".to_string());
+    // Talks to whoever stands in the cell the player faces, or uses what is there.
+    fn interact(&mut self, world: &mut World) -> SimpleTrans {
+        if world.read_resource::<Game>().paused {
+            return Trans::None;
+        }
+
+        let (target, facing) = match (&world.read_storage::<Player>()).join().next() {
+            Some(player) => {
+                let (dx, dy) = player.attrs.direction.to_delta();
+                (Vector2::new(player.grid_pos[0] + dx, player.grid_pos[1] + dy), player.attrs.direction)
+            }
+            None => return Trans::None,
+        };
+
+        for npc in (&mut world.write_storage::<Npc>()).join() {
+            if npc.grid_pos != target || !npc.is_idle() {
+                continue;
+            }
+
+            if let Some(shop) = npc.shop.clone() {
+                npc.attrs.direction = facing.opposite();
+                return Trans::Push(Box::new(ShopState::new(shop)));
+            }
+        }
+
+        let pc = world.read_resource::<MapStream>().any_at((target[0], target[1]), |class| class == TileClass::Pc);
+        if pc {
+            return Trans::Push(Box::new(PcState::new()));
+        }
+
+        Trans::None
+    }
+
+    fn use_field_move(&mut self, world: &mut World, member: usize, field_move: FieldMove) -> SimpleTrans {
+        let (name, grid_pos) = match (&world.read_storage::<Player>()).join().next() {
+            Some(player) => {
+                let library = world.read_resource::<Library>();
+                let name = player.party.get(member).map(|p| p.name(&library)).unwrap_or_default();
+                (name, player.grid_pos)
+            }
+            None => return Trans::None,
+        };
+
+        match field_move {
+            FieldMove::SweetScent => {
+                let on_grass = world.read_resource::<MapStream>().any_at((grid_pos[0], grid_pos[1]), |class| class.is_grass());
+
+                let time = world.read_resource::<Clock>().time_of_day();
+                let encounter = if on_grass {
+                    world.read_resource::<EncounterTable>().pick(time, &mut self.rng)
+                } else {
+                    None
+                };
+
+                match encounter {
+                    Some(encounter) => Trans::Push(Box::new(BattleState::wild(encounter))),
+                    None => Trans::Push(Box::new(DialogState::new(vec![
+                        format!("{} used SWEET SCENT!\n\nLooks like there's nothing here...", name),
+                    ]))),
+                }
+            }
+        }
+    }
+
+    fn intro_text(&self) -> Vec<String> {
+        vec![
+            "Hello, I'm Professor Oak.\n\nWelcome to the world of Pokemon!".to_string(),
+            "Your objective is simple.\n\nScrew your rival and the elite four and get to the hall of fame. Screw your rival and the elite four and get to the hall of fame.".to_string(),
+        ]
     }
 }
 
@@ -49,15 +234,64 @@ impl SimpleState for GameState {
     fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
         let world = data.world;
 
-        self.initialize_camera(world);
+        self.load_save(world);
+        self.initialize_library(world);
+        self.initialize_trainers(world);
+        self.initialize_storage(world);
+        self.initialize_pokedex(world);
+        self.initialize_shops(world);
+        self.initialize_tile_animations(world);
         self.initialize_player(world);
-        self.initialize_sample_tile(world);
+        self.initialize_camera(world);
+        loader::load(world, &self.map);
+        DayTint::create(world);
 
         debug::display_dbg(world);
     }
 
-    fn update(&mut self, _data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
-        println!("GameState: update()");
-        Trans::Push(Box::new(DialogState::new()))
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        loader::stream(data.world);
+
+        if !self.intro_shown {
+            self.intro_shown = true;
+            return Trans::Push(Box::new(DialogState::new(self.intro_text())));
+        }
+
+        let trigger = self.held_trigger.take().or_else(|| {
+            let mut game = data.world.write_resource::<Game>();
+            let trigger = game.get_trigger();
+            game.clear_trigger();
+            trigger
+        });
+
+        // Party members that can evolve do so once the battle is over. The evolution state uses
+        // the trigger slot itself, so the trigger is held until it is popped.
+        if std::mem::take(&mut data.world.write_resource::<Game>().check_evolutions) {
+            let pending = EvolutionState::pending(data.world);
+            if !pending.is_empty() {
+                self.held_trigger = trigger;
+                return Trans::Push(Box::new(EvolutionState::new(pending)));
+            }
+        }
+
+        // Triggers left over from states that were popped, e.g. BattleEnd of a wild battle,
+        // are dropped here so that they do not block new ones.
+        match trigger {
+            Some(Trigger::WildEncounter(encounter)) => Trans::Push(Box::new(BattleState::wild(encounter))),
+            Some(Trigger::TrainerSpotted(npc)) => Trans::Push(Box::new(TrainerState::new(npc))),
+            Some(Trigger::FieldMove { member, field_move }) => self.use_field_move(data.world, member, field_move),
+            Some(Trigger::Warp(warp)) => Trans::Push(Box::new(WarpState::new(warp))),
+            _ => Trans::None
+        }
     }
-}
\ No newline at end of file
+
+    fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        match event {
+            StateEvent::Input(InputEvent::ActionPressed(action)) if action == "menu" => {
+                Trans::Push(Box::new(StartMenuState::new()))
+            }
+            StateEvent::Input(InputEvent::ActionPressed(action)) if action == "continue" => self.interact(data.world),
+            _ => Trans::None
+        }
+    }
+}
@@ -1,21 +1,63 @@
 pub mod game;
 pub mod dialog;
+pub mod battle;
+pub mod trainer;
+pub mod party;
+pub mod bag;
+pub mod start_menu;
+pub mod trainer_card;
+pub mod options;
+pub mod controls;
+pub mod naming;
+pub mod pc;
+pub mod pokedex;
+pub mod shop;
+pub mod evolution;
+pub mod warp;
 
 use amethyst::{
     core::math::{Vector3},
     ecs::{Entity},
 };
 
+use crate::battle::BattleOutcome;
+use crate::map::{encounter::WildEncounter, layout::Warp};
+use crate::pokemon::moves::FieldMove;
+
 #[derive(Clone)]
 pub enum Trigger {
-    DialogEnd
+    DialogEnd,
+    WildEncounter(WildEncounter),
+    TrainerSpotted(Entity),
+    BattleTransitionEnd,
+    BattleEnd(BattleOutcome),
+    // A field move picked from the party screen, by the party member at `member`.
+    FieldMove { member: usize, field_move: FieldMove },
+    // The member picked on a party screen opened to choose one, or None if it was cancelled.
+    PartyChosen(Option<usize>),
+    // An item picked from the bag in battle, to be used on the party member at `target`.
+    ItemChosen { item: String, target: usize },
+    // A ball picked from the bag in battle, to be thrown at the wild pokemon.
+    BallChosen(String),
+    BallShakeEnd,
+    // The name entered on a naming screen, or None if it was left empty.
+    NameChosen(Option<String>),
+    EvolutionEnd,
+    // The player stepped onto a warp of the current map.
+    Warp(Warp),
+    FadeEnd
 }
 
 pub struct Game {
     trigger: Option<Trigger>,
     pub dead_entities: Vec<Entity>,
-    pub camera_trans: Vector3<f32>
-
+    pub camera_trans: Vector3<f32>,
+    // The map the player is on.
+    pub map: String,
+    // Set while a state stacked on top of the overworld owns the input, e.g. during battles.
+    pub paused: bool,
+    // Set at the end of a battle, for the overworld to check the party for evolutions.
+    pub check_evolutions: bool
 }
 
 impl Game {
@@ -41,7 +83,10 @@ impl Default for Game {
         Game {
             trigger: None,
             dead_entities: Vec::new(),
-            camera_trans: Vector3::new(0., 0., 0.)
+            camera_trans: Vector3::new(0., 0., 0.),
+            map: String::new(),
+            paused: false,
+            check_evolutions: false
         }
     }
 }
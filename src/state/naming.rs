@@ -0,0 +1,202 @@
+use amethyst::{
+    prelude::*,
+    ecs::Entity,
+    input::InputEvent,
+    shred::FetchMut,
+    ui::{UiTransform, UiText, UiImage, Anchor, LineMode, Stretch},
+};
+
+use crate::state::{Game, Trigger};
+use crate::utils::{resolve, screen::VirtualScreen};
+
+pub const MAX_NAME: usize = 10;
+
+const LETTERS: [&str; 4] = ["ABCDEFGHIJ", "KLMNOPQRST", "UVWXYZ.-'!", "0123456789"];
+const DELETE: &str = "DEL";
+const DONE: &str = "END";
+
+// A grid of characters to type a name with. Continue types the highlighted character, cancel
+// deletes the last one and menu finishes. The name is reported with `Trigger::NameChosen`,
+// None when it was left empty.
+pub struct NamingState {
+    prompt: String,
+    name: String,
+    grid: Vec<Vec<String>>,
+    cursor: (usize, usize),
+    entities: Vec<Entity>,
+    name_text: Option<Entity>,
+    rows: Vec<Entity>,
+}
+
+impl NamingState {
+    pub fn new(prompt: &str) -> Self {
+        let mut grid: Vec<Vec<String>> = LETTERS.iter()
+            .map(|row| row.chars().map(|c| c.to_string()).collect())
+            .collect();
+        grid.push(vec![DELETE.to_string(), DONE.to_string()]);
+
+        NamingState {
+            prompt: prompt.to_string(),
+            name: String::new(),
+            grid,
+            cursor: (0, 0),
+            entities: Vec::new(),
+            name_text: None,
+            rows: Vec::new(),
+        }
+    }
+
+    fn fetch_game<'s>(&mut self, world: &'s mut World) -> FetchMut<'s, Game> {
+        world.write_resource::<Game>()
+    }
+
+    fn create_text(world: &mut World, id: String, y: f32, height: f32, text: String) -> Entity {
+        let width = world.read_resource::<VirtualScreen>().width;
+        let font = resolve::load_font_handle(world);
+        let mut ui_text = UiText::new(font, text, [0.1, 0.1, 0.1, 1.], 10.);
+        ui_text.line_mode = LineMode::Wrap;
+        ui_text.align = Anchor::TopLeft;
+
+        world.create_entity()
+            .with(UiTransform::new(id, Anchor::TopLeft, Anchor::TopLeft, 10., y, 25., width - 20., height))
+            .with(ui_text)
+            .build()
+    }
+
+    fn initialize_ui(&mut self, world: &mut World) {
+        let (width, height) = {
+            let dim = world.read_resource::<VirtualScreen>();
+            (dim.width, dim.height)
+        };
+
+        let background = world.create_entity()
+            .with(UiTransform::new(
+                "naming_background".to_string(),
+                Anchor::Middle,
+                Anchor::Middle,
+                0.,
+                0.,
+                24.,
+                width,
+                height,
+            ).with_stretch(Stretch::XY {
+                x_margin: 0.,
+                y_margin: 0.,
+                keep_aspect_ratio: false,
+            }))
+            .with(UiImage::SolidColor([0.85, 0.9, 0.95, 1.]))
+            .build();
+
+        let prompt = NamingState::create_text(world, "naming_prompt".to_string(), -6., 26., self.prompt.clone());
+        let name_text = NamingState::create_text(world, "naming_name".to_string(), -34., 14., "".to_string());
+        let help = NamingState::create_text(
+            world,
+            "naming_help".to_string(),
+            -128.,
+            26.,
+            "Z: type   X: delete\nENTER: done".to_string(),
+        );
+
+        self.rows = (0..self.grid.len())
+            .map(|row| NamingState::create_text(world, format!("naming_row_{}", row), -52. - 14. * row as f32, 14., "".to_string()))
+            .collect();
+
+        self.name_text = Some(name_text);
+        self.entities = vec![background, prompt, name_text, help];
+        self.entities.extend(self.rows.iter().cloned());
+    }
+
+    fn refresh(&self, world: &mut World) {
+        let mut texts = world.write_storage::<UiText>();
+
+        if let Some(text) = self.name_text.and_then(|e| texts.get_mut(e)) {
+            let blanks = "_".repeat(MAX_NAME - self.name.chars().count());
+            text.text = format!("{}{}", self.name, blanks);
+        }
+
+        for (row, entity) in self.rows.iter().enumerate() {
+            if let Some(text) = texts.get_mut(*entity) {
+                text.text = self.grid[row].iter()
+                    .enumerate()
+                    .map(|(column, cell)| {
+                        if (row, column) == self.cursor { format!("[{}]", cell) } else { format!(" {} ", cell) }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+            }
+        }
+    }
+
+    fn move_cursor(&mut self, action: &str) {
+        let (row, column) = self.cursor;
+        let rows = self.grid.len();
+
+        let (row, column) = match action {
+            "up" => ((row + rows - 1) % rows, column),
+            "down" => ((row + 1) % rows, column),
+            "left" => (row, (column + self.grid[row].len() - 1) % self.grid[row].len()),
+            "right" => (row, (column + 1) % self.grid[row].len()),
+            _ => return,
+        };
+
+        self.cursor = (row, column.min(self.grid[row].len() - 1));
+    }
+
+    fn finish(&mut self, world: &mut World) -> SimpleTrans {
+        let name = self.name.trim().to_string();
+        let chosen = if name.is_empty() { None } else { Some(name) };
+        self.fetch_game(world).set_trigger(Trigger::NameChosen(chosen));
+        Trans::Pop
+    }
+
+    fn type_cell(&mut self, world: &mut World) -> SimpleTrans {
+        let cell = self.grid[self.cursor.0][self.cursor.1].clone();
+        match cell.as_str() {
+            DELETE => {
+                self.name.pop();
+            }
+            DONE => return self.finish(world),
+            _ if self.name.chars().count() < MAX_NAME => self.name.push_str(&cell),
+            _ => {}
+        }
+
+        Trans::None
+    }
+}
+
+impl SimpleState for NamingState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.initialize_ui(data.world);
+        self.refresh(data.world);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        for entity in self.entities.drain(..) {
+            data.world.delete_entity(entity).expect("failed to delete naming screen");
+        }
+    }
+
+    fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        let action = match event {
+            StateEvent::Input(InputEvent::ActionPressed(action)) => action,
+            _ => return Trans::None,
+        };
+
+        let world = data.world;
+        let trans = match action.as_str() {
+            "continue" => self.type_cell(world),
+            "cancel" => {
+                self.name.pop();
+                Trans::None
+            }
+            "menu" => self.finish(world),
+            _ => {
+                self.move_cursor(&action);
+                Trans::None
+            }
+        };
+
+        self.refresh(world);
+        trans
+    }
+}
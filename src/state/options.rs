@@ -0,0 +1,218 @@
+use amethyst::{
+    prelude::*,
+    ecs::Entity,
+    input::InputEvent,
+    shrev::ReaderId,
+    ui::{UiTransform, UiText, UiImage, Anchor, LineMode, Stretch},
+};
+
+use crate::state::controls::ControlsState;
+use crate::entity::menu::list::{self, ListEntry, ListEvent, ListEventKind, ListLayout, ListWidget};
+use crate::utils::{resolve, screen::VirtualScreen, settings::{Settings, TextSpeed, MAX_VOLUME}};
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum OptionEntry {
+    TextSpeed,
+    MusicVolume,
+    SoundVolume,
+    Controls,
+    Close,
+}
+
+const ENTRIES: [OptionEntry; 5] = [
+    OptionEntry::TextSpeed,
+    OptionEntry::MusicVolume,
+    OptionEntry::SoundVolume,
+    OptionEntry::Controls,
+    OptionEntry::Close,
+];
+
+// Edits the Settings resource with left/right, and writes it to the user directory on close.
+// Volumes take effect right away, so that they can be heard while they are set.
+pub struct OptionsState {
+    entities: Vec<Entity>,
+    list: Option<Entity>,
+    reader: Option<ReaderId<ListEvent>>,
+}
+
+impl OptionsState {
+    pub fn new() -> Self {
+        OptionsState {
+            entities: Vec::new(),
+            list: None,
+            reader: None,
+        }
+    }
+
+    fn entries(settings: &Settings) -> Vec<ListEntry> {
+        ENTRIES.iter()
+            .map(|entry| match entry {
+                OptionEntry::TextSpeed => ListEntry::new(format!("TEXT SPEED   < {} >", settings.text_speed.label())),
+                OptionEntry::MusicVolume => ListEntry::new(format!("MUSIC        < {} >", settings.music_volume)),
+                OptionEntry::SoundVolume => ListEntry::new(format!("SOUND        < {} >", settings.sound_volume)),
+                OptionEntry::Controls => ListEntry::new("CONTROLS"),
+                OptionEntry::Close => ListEntry::new("CLOSE"),
+            })
+            .collect()
+    }
+
+    fn step_volume(volume: u8, step: isize) -> u8 {
+        (volume as isize + step).clamp(0, MAX_VOLUME as isize) as u8
+    }
+
+    fn initialize_ui(&mut self, world: &mut World) {
+        let (width, height) = {
+            let dim = world.read_resource::<VirtualScreen>();
+            (dim.width, dim.height)
+        };
+
+        let background = world.create_entity()
+            .with(UiTransform::new(
+                "options_background".to_string(),
+                Anchor::Middle,
+                Anchor::Middle,
+                0.,
+                0.,
+                25.,
+                width,
+                height,
+            ).with_stretch(Stretch::XY {
+                x_margin: 0.,
+                y_margin: 0.,
+                keep_aspect_ratio: false,
+            }))
+            .with(UiImage::SolidColor([0.3, 0.35, 0.6, 1.]))
+            .build();
+
+        let font = resolve::load_font_handle(world);
+        let mut ui_text = UiText::new(font, "OPTIONS".to_string(), [1., 1., 1., 1.], 12.);
+        ui_text.line_mode = LineMode::Single;
+        ui_text.align = Anchor::TopLeft;
+
+        let title = world.create_entity()
+            .with(UiTransform::new(
+                "options_title".to_string(),
+                Anchor::TopLeft,
+                Anchor::TopLeft,
+                14.,
+                -10.,
+                26.,
+                width - 28.,
+                14.,
+            ))
+            .with(ui_text)
+            .build();
+
+        let entries = OptionsState::entries(&world.read_resource::<Settings>());
+        let list = ListWidget::create(
+            world,
+            &ListLayout::new("options", Anchor::TopLeft, 6., -30., 26., width - 12.)
+                .with_frame(None)
+                .with_color([1., 1., 1., 1.]),
+            entries,
+        );
+
+        self.entities = vec![background, title];
+        self.list = Some(list);
+    }
+
+    fn selected(&self, world: &World) -> Option<OptionEntry> {
+        let list = self.list?;
+        world.read_storage::<ListWidget>().get(list).map(|w| ENTRIES[w.selection.index()])
+    }
+
+    fn set_active(&self, world: &mut World, active: bool) {
+        let mut widgets = world.write_storage::<ListWidget>();
+        if let Some(widget) = self.list.and_then(|list| widgets.get_mut(list)) {
+            widget.set_active(active);
+        }
+    }
+
+    // Text speeds wrap around, volumes stop at muted and at full volume.
+    fn cycle(&mut self, world: &mut World, entry: OptionEntry, step: isize) {
+        let entries = {
+            let mut settings = world.write_resource::<Settings>();
+            match entry {
+                OptionEntry::TextSpeed => {
+                    let count = TextSpeed::ALL.len() as isize;
+                    let current = TextSpeed::ALL.iter().position(|s| *s == settings.text_speed).unwrap_or(0) as isize;
+                    settings.text_speed = TextSpeed::ALL[(current + step).rem_euclid(count) as usize];
+                }
+                OptionEntry::MusicVolume => settings.music_volume = OptionsState::step_volume(settings.music_volume, step),
+                OptionEntry::SoundVolume => settings.sound_volume = OptionsState::step_volume(settings.sound_volume, step),
+                _ => return,
+            }
+            OptionsState::entries(&settings)
+        };
+
+        let mut widgets = world.write_storage::<ListWidget>();
+        if let Some(widget) = self.list.and_then(|list| widgets.get_mut(list)) {
+            widget.set_entries(entries);
+        }
+    }
+}
+
+impl SimpleState for OptionsState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.reader = Some(list::register_reader(data.world));
+        self.initialize_ui(data.world);
+    }
+
+    fn on_pause(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.set_active(data.world, false);
+    }
+
+    fn on_resume(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.set_active(data.world, true);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        if let Err(e) = world.read_resource::<Settings>().write() {
+            eprintln!("failed to write settings: {}", e);
+        }
+
+        if let Some(list) = self.list.take() {
+            ListWidget::delete(world, list);
+        }
+
+        for entity in self.entities.drain(..) {
+            world.delete_entity(entity).expect("failed to delete options ui");
+        }
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let events = match &mut self.reader {
+            Some(reader) => list::read_events(data.world, reader),
+            None => return Trans::None,
+        };
+
+        for event in events.into_iter().filter(|e| Some(e.list) == self.list) {
+            match event.kind {
+                ListEventKind::Confirmed(index) if ENTRIES[index] == OptionEntry::Controls => {
+                    return Trans::Push(Box::new(ControlsState::new()));
+                }
+                ListEventKind::Confirmed(index) if ENTRIES[index] == OptionEntry::Close => return Trans::Pop,
+                ListEventKind::Cancelled => return Trans::Pop,
+                _ => {}
+            }
+        }
+
+        Trans::None
+    }
+
+    fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        let world = data.world;
+
+        if let StateEvent::Input(InputEvent::ActionPressed(action)) = event {
+            match (self.selected(world), action.as_str()) {
+                (Some(entry), "left") => self.cycle(world, entry, -1),
+                (Some(entry), "right") => self.cycle(world, entry, 1),
+                _ => {}
+            }
+        }
+
+        Trans::None
+    }
+}
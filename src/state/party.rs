@@ -0,0 +1,524 @@
+use amethyst::{
+    prelude::*,
+    ecs::{Entity, Join},
+    input::InputEvent,
+    renderer::SpriteRender,
+    shred::FetchMut,
+    ui::{UiTransform, UiText, UiImage, Anchor, LineMode, Stretch},
+};
+
+use crate::entity::actor::player::Player;
+use crate::entity::dialog::DialogSpritetype;
+use crate::entity::screen::VirtualLayout;
+use crate::pokemon::{library::Library, moves::FieldMove, party::Party, Pokemon};
+use crate::state::{Game, Trigger};
+use crate::utils::{resolve, screen::VirtualScreen};
+
+const ROW_TOP: f32 = -2.;
+const ROW_STEP: f32 = 18.;
+const HP_BAR_WIDTH: f32 = 72.;
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum PartyPhase {
+    Select,
+    Actions,
+    // Picking where to move the member at the given index.
+    Moving(usize),
+    Summary,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum PartyAction {
+    Summary,
+    Field(FieldMove),
+    Switch,
+    Cancel,
+}
+
+struct MemberRow {
+    name: Entity,
+    hp_fill: Entity,
+    hp_text: Entity,
+}
+
+struct PartyUi {
+    entities: Vec<Entity>,
+    rows: Vec<MemberRow>,
+    text: Entity,
+}
+
+// The party screen: lists the party with HP and status, and lets the player reorder it,
+// read a member's summary and use field moves. Opened with `choose`, it only picks a member
+// for another state and reports it with `Trigger::PartyChosen`.
+pub struct PartyState {
+    // Set when the screen was opened to choose a member.
+    prompt: Option<String>,
+    phase: PartyPhase,
+    // The highlighted member, kept while browsing a member's actions.
+    selected: usize,
+    cursor: usize,
+    ui: Option<PartyUi>,
+    summary: Vec<Entity>,
+    was_paused: bool,
+}
+
+impl PartyState {
+    pub fn new() -> Self {
+        PartyState {
+            prompt: None,
+            phase: PartyPhase::Select,
+            selected: 0,
+            cursor: 0,
+            ui: None,
+            summary: Vec::new(),
+            was_paused: false,
+        }
+    }
+
+    pub fn choose(prompt: &str) -> Self {
+        PartyState {
+            prompt: Some(prompt.to_string()),
+            ..PartyState::new()
+        }
+    }
+
+    fn fetch_game<'s>(&mut self, world: &'s mut World) -> FetchMut<'s, Game> {
+        world.write_resource::<Game>()
+    }
+
+    fn party(world: &World) -> Party {
+        (&world.read_storage::<Player>()).join()
+            .next()
+            .map(|player| player.party.clone())
+            .unwrap_or_default()
+    }
+
+    fn create_text(world: &mut World, transform: UiTransform, color: [f32; 4], size: f32) -> Entity {
+        let font = resolve::load_font_handle(world);
+        let mut text = UiText::new(font, "".to_string(), color, size);
+        text.line_mode = LineMode::Wrap;
+        text.align = Anchor::TopLeft;
+
+        world.create_entity()
+            .with(transform)
+            .with(text)
+            .build()
+    }
+
+    fn create_image(world: &mut World, transform: UiTransform, image: UiImage) -> Entity {
+        world.create_entity()
+            .with(transform)
+            .with(image)
+            .build()
+    }
+
+    fn top_left(id: String, x: f32, y: f32, z: f32, width: f32, height: f32) -> UiTransform {
+        UiTransform::new(id, Anchor::TopLeft, Anchor::TopLeft, x, y, z, width, height)
+    }
+
+    fn initialize_ui(&mut self, world: &mut World) {
+        let party = PartyState::party(world);
+        let icons: Vec<usize> = {
+            let library = world.read_resource::<Library>();
+            party.members().iter()
+                .map(|pokemon| library.get_species(&pokemon.species)
+                    .and_then(|s| s.types.first().cloned())
+                    .map(|t| t.icon_index())
+                    .unwrap_or(0))
+                .collect()
+        };
+        let (width, height) = {
+            let dim = world.read_resource::<VirtualScreen>();
+            (dim.width, dim.height)
+        };
+        let dialog_sheet = resolve::load_spritesheet_handle(world, "dialogs/sheet".to_string());
+        let icon_sheet = resolve::load_spritesheet_handle(world, "pokemon/icons".to_string());
+
+        let mut entities = Vec::new();
+        let mut rows = Vec::new();
+
+        entities.push(PartyState::create_image(world, UiTransform::new(
+            "party_background".to_string(),
+            Anchor::Middle,
+            Anchor::Middle,
+            0.,
+            0.,
+            20.,
+            width,
+            height,
+        ).with_stretch(Stretch::XY {
+            x_margin: 0.,
+            y_margin: 0.,
+            keep_aspect_ratio: false,
+        }), UiImage::SolidColor([0.35, 0.55, 0.75, 1.])));
+
+        for (i, icon) in icons.into_iter().enumerate() {
+            let y = ROW_TOP - i as f32 * ROW_STEP;
+
+            entities.push(PartyState::create_image(
+                world,
+                PartyState::top_left(format!("party_row_{}", i), 6., y, 21., width - 12., 17.),
+                UiImage::Sprite(SpriteRender {
+                    sprite_sheet: dialog_sheet.clone(),
+                    sprite_number: DialogSpritetype::Bottom.to_sprite_index(),
+                }),
+            ));
+
+            entities.push(PartyState::create_image(
+                world,
+                PartyState::top_left(format!("party_icon_{}", i), 10., y - 1., 22., 15., 15.),
+                UiImage::Sprite(SpriteRender {
+                    sprite_sheet: icon_sheet.clone(),
+                    sprite_number: icon,
+                }),
+            ));
+
+            let white = [1., 1., 1., 1.];
+            let name = PartyState::create_text(
+                world,
+                PartyState::top_left(format!("party_name_{}", i), 30., y - 3., 22., 100., 14.),
+                white,
+                10.,
+            );
+
+            let hp_back = PartyState::create_image(
+                world,
+                PartyState::top_left(format!("party_hp_back_{}", i), width - HP_BAR_WIDTH - 12., y - 3., 22., HP_BAR_WIDTH, 4.),
+                UiImage::SolidColor([0.2, 0.2, 0.2, 1.]),
+            );
+
+            let hp_fill = PartyState::create_image(
+                world,
+                PartyState::top_left(format!("party_hp_fill_{}", i), width - HP_BAR_WIDTH - 12., y - 3., 23., HP_BAR_WIDTH, 4.),
+                UiImage::SolidColor([0.3, 0.8, 0.4, 1.]),
+            );
+
+            let hp_text = PartyState::create_text(
+                world,
+                PartyState::top_left(format!("party_hp_text_{}", i), width - HP_BAR_WIDTH - 12., y - 7., 22., HP_BAR_WIDTH, 10.),
+                white,
+                8.,
+            );
+
+            entities.extend(vec![name, hp_back, hp_fill, hp_text]);
+            rows.push(MemberRow { name, hp_fill, hp_text });
+        }
+
+        entities.push(PartyState::create_image(world, UiTransform::new(
+            "party_box".to_string(),
+            Anchor::BottomMiddle,
+            Anchor::BottomMiddle,
+            0.,
+            0.,
+            21.,
+            width,
+            48.,
+        ), UiImage::Sprite(SpriteRender {
+            sprite_sheet: dialog_sheet,
+            sprite_number: DialogSpritetype::Bottom.to_sprite_index(),
+        })));
+
+        let text = PartyState::create_text(world, UiTransform::new(
+            "party_text".to_string(),
+            Anchor::BottomLeft,
+            Anchor::MiddleLeft,
+            10.,
+            24.,
+            22.,
+            width - 20.,
+            40.,
+        ), [1., 1., 1., 1.], 12.);
+        entities.push(text);
+
+        self.ui = Some(PartyUi { entities, rows, text });
+    }
+
+    fn hp_color(ratio: f32) -> [f32; 4] {
+        if ratio > 0.5 {
+            [0.3, 0.8, 0.4, 1.]
+        } else if ratio > 0.2 {
+            [0.95, 0.8, 0.2, 1.]
+        } else {
+            [0.9, 0.25, 0.2, 1.]
+        }
+    }
+
+    fn actions(pokemon: &Pokemon, library: &Library) -> Vec<(PartyAction, String)> {
+        let mut actions = vec![(PartyAction::Summary, "SUMMARY".to_string())];
+
+        if !pokemon.is_fainted() {
+            for slot in &pokemon.moves {
+                if let Some((field, name)) = library.get_move(&slot.id).and_then(|m| m.field.map(|f| (f, m.name.clone()))) {
+                    actions.push((PartyAction::Field(field), name));
+                }
+            }
+        }
+
+        actions.push((PartyAction::Switch, "SWITCH".to_string()));
+        actions.push((PartyAction::Cancel, "CANCEL".to_string()));
+        actions
+    }
+
+    fn menu(labels: Vec<String>, cursor: usize) -> String {
+        labels.iter().enumerate()
+            .map(|(i, label)| if i == cursor { format!("> {}", label) } else { format!("  {}", label) })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn refresh(&self, world: &mut World) {
+        let ui = match &self.ui {
+            Some(ui) => ui,
+            None => return,
+        };
+
+        let party = PartyState::party(world);
+        let library = world.read_resource::<Library>();
+        let mut texts = world.write_storage::<UiText>();
+        let transforms = world.read_storage::<UiTransform>();
+        let mut layouts = world.write_storage::<VirtualLayout>();
+        let mut images = world.write_storage::<UiImage>();
+
+        for (i, (row, pokemon)) in ui.rows.iter().zip(party.members()).enumerate() {
+            let marker = match self.phase {
+                PartyPhase::Moving(from) if from == i => "* ",
+                PartyPhase::Select | PartyPhase::Moving(_) if self.cursor == i => "> ",
+                PartyPhase::Actions | PartyPhase::Summary if self.selected == i => "> ",
+                _ => "  ",
+            };
+
+            let status = pokemon.status.map(|s| format!("  {}", s.label())).unwrap_or_default();
+            if let Some(text) = texts.get_mut(row.name) {
+                text.text = format!("{}{}\n  Lv{}{}", marker, pokemon.name(&library), pokemon.level, status);
+            }
+
+            if let Some(text) = texts.get_mut(row.hp_text) {
+                text.text = format!("HP {}/{}", pokemon.hp, pokemon.stats.hp);
+            }
+
+            let ratio = pokemon.hp as f32 / pokemon.stats.hp.max(1) as f32;
+            if let Some(layout) = VirtualLayout::get_mut(&mut layouts, &transforms, row.hp_fill) {
+                layout.width = HP_BAR_WIDTH * ratio;
+            }
+
+            if let Some(image) = images.get_mut(row.hp_fill) {
+                *image = UiImage::SolidColor(PartyState::hp_color(ratio));
+            }
+        }
+
+        if let Some(text) = texts.get_mut(ui.text) {
+            text.text = match self.phase {
+                PartyPhase::Select | PartyPhase::Summary => self.prompt.clone()
+                    .unwrap_or_else(|| "Choose a POKEMON.".to_string()),
+                PartyPhase::Moving(_) => "Move to where?".to_string(),
+                PartyPhase::Actions => match party.get(self.selected) {
+                    Some(pokemon) => PartyState::menu(
+                        PartyState::actions(pokemon, &library).into_iter().map(|(_, label)| label).collect(),
+                        self.cursor,
+                    ),
+                    None => "".to_string(),
+                },
+            };
+        }
+    }
+
+    fn summary_text(pokemon: &Pokemon, library: &Library) -> String {
+        let types = library.get_species(&pokemon.species)
+            .map(|s| s.types.iter().map(|t| format!("{:?}", t).to_uppercase()).collect::<Vec<String>>().join("/"))
+            .unwrap_or_default();
+        let status = pokemon.status.map(|s| s.label()).unwrap_or("OK");
+        let stats = &pokemon.stats;
+
+        let moves = pokemon.moves.iter()
+            .map(|slot| {
+                let (name, pp) = library.get_move(&slot.id)
+                    .map(|m| (m.name.clone(), m.pp))
+                    .unwrap_or_else(|| (slot.id.clone(), slot.pp));
+                format!("{}  PP {}/{}", name, slot.pp, pp)
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        format!(
+            "{}  Lv{}\nTYPE {}   STATUS {}\nHP {}/{}\n\nATTACK {}   DEFENSE {}\nSP. ATK {}   SP. DEF {}\nSPEED {}\n\nMOVES\n{}",
+            pokemon.name(library), pokemon.level, types, status, pokemon.hp, stats.hp,
+            stats.attack, stats.defense, stats.sp_attack, stats.sp_defense, stats.speed, moves,
+        )
+    }
+
+    fn open_summary(&mut self, world: &mut World) {
+        let party = PartyState::party(world);
+        let text = match party.get(self.selected) {
+            Some(pokemon) => PartyState::summary_text(pokemon, &world.read_resource::<Library>()),
+            None => return,
+        };
+        let (width, height) = {
+            let dim = world.read_resource::<VirtualScreen>();
+            (dim.width, dim.height)
+        };
+
+        let background = PartyState::create_image(world, UiTransform::new(
+            "party_summary_background".to_string(),
+            Anchor::Middle,
+            Anchor::Middle,
+            0.,
+            0.,
+            30.,
+            width,
+            height,
+        ).with_stretch(Stretch::XY {
+            x_margin: 0.,
+            y_margin: 0.,
+            keep_aspect_ratio: false,
+        }), UiImage::SolidColor([0.97, 0.97, 0.9, 1.]));
+
+        let body = PartyState::create_text(
+            world,
+            PartyState::top_left("party_summary_text".to_string(), 8., -8., 31., width - 16., height - 16.),
+            [0.1, 0.1, 0.1, 1.],
+            9.,
+        );
+
+        if let Some(ui_text) = world.write_storage::<UiText>().get_mut(body) {
+            ui_text.text = text;
+        }
+
+        self.summary = vec![background, body];
+        self.phase = PartyPhase::Summary;
+    }
+
+    fn close_summary(&mut self, world: &mut World) {
+        for entity in self.summary.drain(..) {
+            world.delete_entity(entity).expect("failed to delete party summary");
+        }
+        self.phase = PartyPhase::Actions;
+    }
+
+    fn swap(&mut self, world: &mut World, a: usize, b: usize) {
+        for player in (&mut world.write_storage::<Player>()).join() {
+            player.party.swap(a, b);
+        }
+    }
+
+    fn move_cursor(&mut self, action: &str, len: usize) {
+        match action {
+            "up" if self.cursor > 0 => self.cursor -= 1,
+            "down" if self.cursor + 1 < len => self.cursor += 1,
+            _ => {}
+        }
+    }
+}
+
+impl SimpleState for PartyState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        {
+            let mut game = self.fetch_game(world);
+            self.was_paused = game.paused;
+            game.paused = true;
+        }
+
+        self.initialize_ui(world);
+        self.refresh(world);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        for entity in self.summary.drain(..) {
+            world.delete_entity(entity).expect("failed to delete party summary");
+        }
+
+        if let Some(ui) = self.ui.take() {
+            for entity in &ui.entities {
+                world.delete_entity(*entity).expect("failed to delete party ui");
+            }
+        }
+
+        let was_paused = self.was_paused;
+        self.fetch_game(world).paused = was_paused;
+    }
+
+    fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        let action = match event {
+            StateEvent::Input(InputEvent::ActionPressed(action)) => action,
+            _ => return Trans::None,
+        };
+
+        let world = data.world;
+        let party = PartyState::party(world);
+
+        match self.phase {
+            PartyPhase::Select if self.prompt.is_some() => {
+                self.move_cursor(&action, party.len());
+                if action == "continue" && !party.is_empty() {
+                    let chosen = self.cursor;
+                    self.fetch_game(world).set_trigger(Trigger::PartyChosen(Some(chosen)));
+                    return Trans::Pop;
+                } else if action == "cancel" {
+                    self.fetch_game(world).set_trigger(Trigger::PartyChosen(None));
+                    return Trans::Pop;
+                }
+            }
+            PartyPhase::Select => {
+                self.move_cursor(&action, party.len());
+                if action == "continue" && !party.is_empty() {
+                    self.selected = self.cursor;
+                    self.cursor = 0;
+                    self.phase = PartyPhase::Actions;
+                } else if action == "cancel" {
+                    return Trans::Pop;
+                }
+            }
+            PartyPhase::Actions => {
+                let actions = match party.get(self.selected) {
+                    Some(pokemon) => PartyState::actions(pokemon, &world.read_resource::<Library>()),
+                    None => return Trans::Pop,
+                };
+
+                self.move_cursor(&action, actions.len());
+                if action == "continue" {
+                    match actions[self.cursor].0 {
+                        PartyAction::Summary => self.open_summary(world),
+                        PartyAction::Field(field_move) => {
+                            let member = self.selected;
+                            self.fetch_game(world).set_trigger(Trigger::FieldMove { member, field_move });
+                            return Trans::Pop;
+                        }
+                        PartyAction::Switch => {
+                            self.phase = PartyPhase::Moving(self.selected);
+                            self.cursor = self.selected;
+                        }
+                        PartyAction::Cancel => {
+                            self.phase = PartyPhase::Select;
+                            self.cursor = self.selected;
+                        }
+                    }
+                } else if action == "cancel" {
+                    self.phase = PartyPhase::Select;
+                    self.cursor = self.selected;
+                }
+            }
+            PartyPhase::Moving(from) => {
+                self.move_cursor(&action, party.len());
+                if action == "continue" {
+                    self.swap(world, from, self.cursor);
+                    self.phase = PartyPhase::Select;
+                } else if action == "cancel" {
+                    self.phase = PartyPhase::Select;
+                    self.cursor = from;
+                }
+            }
+            PartyPhase::Summary => {
+                if action == "continue" || action == "cancel" {
+                    self.close_summary(world);
+                    self.cursor = 0;
+                }
+            }
+        }
+
+        self.refresh(world);
+        Trans::None
+    }
+}
@@ -0,0 +1,597 @@
+use amethyst::{
+    prelude::*,
+    ecs::{Entity, Join},
+    input::InputEvent,
+    renderer::SpriteRender,
+    shred::FetchMut,
+    shrev::ReaderId,
+    ui::{UiTransform, UiText, UiImage, Anchor, LineMode},
+};
+
+use crate::entity::actor::player::Player;
+use crate::entity::dialog::DialogSpritetype;
+use crate::entity::menu::list::{self, ListEntry, ListEvent, ListEventKind, ListLayout, ListWidget};
+use crate::pokemon::{library::Library, party::Party, storage::{Storage, StorageError, Wallpaper}, Pokemon};
+use crate::state::{Game, Trigger};
+use crate::state::dialog::DialogState;
+use crate::state::naming::NamingState;
+use crate::utils::resolve;
+
+const MENU: [&str; 7] = ["WITHDRAW", "DEPOSIT", "MOVE", "RELEASE", "WALLPAPER", "NAME BOX", "LOG OFF"];
+const MENU_ROWS: usize = 6;
+const BOX_ROWS: usize = 7;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum BoxMode {
+    Withdraw,
+    Move,
+    Release,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum PcPhase {
+    Greeting,
+    Menu,
+    // Browsing the boxes, left and right switching between them.
+    Box(BoxMode),
+    // Picking a party member to deposit into the current box.
+    Party,
+    // Asking whether to release the pokemon in the given slot of the current box.
+    ConfirmRelease(usize),
+    Wallpaper,
+}
+
+// The storage system, opened from a PC tile. Pokemon move between the party and the boxes
+// through `Storage`, which refuses anything that would leave the party without a pokemon
+// able to battle.
+pub struct PcState {
+    phase: PcPhase,
+    // Entered when the dialog pushed by `say` is closed.
+    after_dialog: Option<PcPhase>,
+    menu: Option<Entity>,
+    slots: Option<Entity>,
+    party: Option<Entity>,
+    confirm: Option<Entity>,
+    wallpapers: Option<Entity>,
+    // Wallpaper backdrop and title of the current box.
+    header: Vec<Entity>,
+    info: Vec<Entity>,
+    // The box and slot of the pokemon picked up to be moved.
+    held: Option<(usize, usize)>,
+    reader: Option<ReaderId<ListEvent>>,
+    was_paused: bool,
+}
+
+impl PcState {
+    pub fn new() -> Self {
+        PcState {
+            phase: PcPhase::Greeting,
+            after_dialog: None,
+            menu: None,
+            slots: None,
+            party: None,
+            confirm: None,
+            wallpapers: None,
+            header: Vec::new(),
+            info: Vec::new(),
+            held: None,
+            reader: None,
+            was_paused: false,
+        }
+    }
+
+    fn fetch_game<'s>(&mut self, world: &'s mut World) -> FetchMut<'s, Game> {
+        world.write_resource::<Game>()
+    }
+
+    fn party(world: &World) -> Party {
+        (&world.read_storage::<Player>()).join()
+            .next()
+            .map(|player| player.party.clone())
+            .unwrap_or_default()
+    }
+
+    fn pokemon_name(world: &World, pokemon: &Pokemon) -> String {
+        pokemon.name(&world.read_resource::<Library>())
+    }
+
+    fn current_box(world: &World) -> (usize, String) {
+        let storage = world.read_resource::<Storage>();
+        let name = storage.get_box(storage.current).map(|b| b.name.clone()).unwrap_or_default();
+        (storage.current, name)
+    }
+
+    fn slot_entries(&self, world: &World) -> Vec<ListEntry> {
+        let library = world.read_resource::<Library>();
+        let storage = world.read_resource::<Storage>();
+        let placing = self.phase == PcPhase::Box(BoxMode::Move) && self.held.is_some();
+
+        storage.get_box(storage.current)
+            .map(|pokemon_box| pokemon_box.slots().iter()
+                .enumerate()
+                .map(|(slot, pokemon)| match pokemon {
+                    Some(pokemon) => {
+                        let marker = if self.held == Some((storage.current, slot)) { "*" } else { "" };
+                        ListEntry::new(format!("{}{}  Lv{}", marker, pokemon.name(&library), pokemon.level))
+                    }
+                    // Empty slots are only of use as somewhere to put a held pokemon.
+                    None if placing => ListEntry::new("-----"),
+                    None => ListEntry::disabled("-----"),
+                })
+                .collect())
+            .unwrap_or_default()
+    }
+
+    fn party_entries(world: &World) -> Vec<ListEntry> {
+        let library = world.read_resource::<Library>();
+        let mut entries: Vec<ListEntry> = PcState::party(world).members().iter()
+            .map(|pokemon| ListEntry::new(format!("{}  Lv{}", pokemon.name(&library), pokemon.level)))
+            .collect();
+        entries.push(ListEntry::new("CANCEL"));
+        entries
+    }
+
+    fn set_entries(world: &mut World, list: Option<Entity>, entries: Vec<ListEntry>) {
+        let mut widgets = world.write_storage::<ListWidget>();
+        if let Some(widget) = list.and_then(|list| widgets.get_mut(list)) {
+            widget.set_entries(entries);
+        }
+    }
+
+    fn set_active(world: &mut World, list: Option<Entity>, active: bool) {
+        let mut widgets = world.write_storage::<ListWidget>();
+        if let Some(widget) = list.and_then(|list| widgets.get_mut(list)) {
+            widget.set_active(active);
+        }
+    }
+
+    fn delete_list(world: &mut World, list: &mut Option<Entity>) {
+        if let Some(entity) = list.take() {
+            ListWidget::delete(world, entity);
+        }
+    }
+
+    fn delete_entities(world: &mut World, entities: &mut Vec<Entity>) {
+        for entity in entities.drain(..) {
+            world.delete_entity(entity).expect("failed to delete pc ui");
+        }
+    }
+
+    fn create_text(world: &mut World, id: &str, anchor: Anchor, x: f32, y: f32, width: f32, height: f32) -> Entity {
+        let font = resolve::load_font_handle(world);
+        let mut text = UiText::new(font, "".to_string(), [0.1, 0.1, 0.1, 1.], 10.);
+        text.line_mode = LineMode::Wrap;
+        text.align = anchor;
+
+        world.create_entity()
+            .with(UiTransform::new(id.to_string(), Anchor::TopLeft, Anchor::TopLeft, x, y, 14., width, height))
+            .with(text)
+            .build()
+    }
+
+    fn create_header(world: &mut World) -> Vec<Entity> {
+        let backdrop = world.create_entity()
+            .with(UiTransform::new("pc_header_backdrop".to_string(), Anchor::TopLeft, Anchor::TopLeft, 8., -8., 13., 136., 22.))
+            .with(UiImage::SolidColor(Wallpaper::Forest.color()))
+            .build();
+        let title = PcState::create_text(world, "pc_header_text", Anchor::Middle, 8., -8., 136., 22.);
+
+        vec![backdrop, title]
+    }
+
+    fn create_info(world: &mut World) -> Vec<Entity> {
+        let sprite_sheet = resolve::load_spritesheet_handle(world, "dialogs/sheet".to_string());
+        let frame = world.create_entity()
+            .with(UiTransform::new("pc_info_frame".to_string(), Anchor::TopRight, Anchor::TopRight, -8., -8., 13., 84., 90.))
+            .with(UiImage::Sprite(SpriteRender {
+                sprite_sheet,
+                sprite_number: DialogSpritetype::OptionBox.to_sprite_index(),
+            }))
+            .build();
+        let text = PcState::create_text(world, "pc_info_text", Anchor::TopLeft, 156., -16., 68., 74.);
+
+        vec![frame, text]
+    }
+
+    // Redraws the box title and wallpaper, and the details of the highlighted pokemon.
+    fn refresh_box(&self, world: &mut World) {
+        let (name, color) = {
+            let storage = world.read_resource::<Storage>();
+            storage.get_box(storage.current)
+                .map(|b| (b.name.clone(), b.wallpaper.color()))
+                .unwrap_or_else(|| (String::new(), Wallpaper::Forest.color()))
+        };
+
+        if let Some(backdrop) = self.header.first() {
+            if let Some(image) = world.write_storage::<UiImage>().get_mut(*backdrop) {
+                *image = UiImage::SolidColor(color);
+            }
+        }
+        if let Some(title) = self.header.last() {
+            if let Some(text) = world.write_storage::<UiText>().get_mut(*title) {
+                text.text = format!("<  {}  >", name);
+            }
+        }
+
+        let info = self.info_text(world);
+        if let Some(entity) = self.info.last() {
+            if let Some(text) = world.write_storage::<UiText>().get_mut(*entity) {
+                text.text = info;
+            }
+        }
+    }
+
+    fn info_text(&self, world: &World) -> String {
+        let slot = self.slots
+            .and_then(|list| world.read_storage::<ListWidget>().get(list).map(|w| w.selection.index()));
+        let library = world.read_resource::<Library>();
+        let storage = world.read_resource::<Storage>();
+
+        let mut text = match slot.and_then(|slot| storage.get(storage.current, slot)) {
+            Some(pokemon) => {
+                let status = pokemon.status.map(|s| s.label()).unwrap_or("OK");
+                format!("{}\nLv{}  {}\nHP {}/{}", pokemon.name(&library), pokemon.level, status, pokemon.hp, pokemon.stats.hp)
+            }
+            None => "".to_string(),
+        };
+
+        if let Some(held) = self.held.and_then(|(index, slot)| storage.get(index, slot)) {
+            text = format!("{}\n\nHOLDING\n{}", text, held.name(&library));
+        }
+        text
+    }
+
+    // Enters a phase, showing the lists and boxes it needs and removing the others.
+    fn enter(&mut self, world: &mut World, phase: PcPhase) {
+        let browsing = matches!(phase, PcPhase::Box(_) | PcPhase::ConfirmRelease(_));
+
+        if phase != PcPhase::Box(BoxMode::Move) {
+            self.held = None;
+        }
+        if !browsing {
+            PcState::delete_list(world, &mut self.slots);
+            PcState::delete_entities(world, &mut self.info);
+        }
+        if !browsing && phase != PcPhase::Wallpaper {
+            PcState::delete_entities(world, &mut self.header);
+        }
+        if phase != PcPhase::Party {
+            PcState::delete_list(world, &mut self.party);
+        }
+        if phase != PcPhase::Wallpaper {
+            PcState::delete_list(world, &mut self.wallpapers);
+        }
+        PcState::delete_list(world, &mut self.confirm);
+
+        self.phase = phase.clone();
+
+        if self.header.is_empty() && (browsing || phase == PcPhase::Wallpaper) {
+            self.header = PcState::create_header(world);
+        }
+
+        match &phase {
+            PcPhase::Box(_) => {
+                let entries = self.slot_entries(world);
+                match self.slots {
+                    Some(_) => PcState::set_entries(world, self.slots, entries),
+                    None => {
+                        self.info = PcState::create_info(world);
+                        self.slots = Some(ListWidget::create(
+                            world,
+                            &ListLayout::new("pc_slots", Anchor::TopLeft, 8., -34., 13., 136.).with_rows(BOX_ROWS),
+                            entries,
+                        ));
+                    }
+                }
+            }
+            PcPhase::Party => {
+                let entries = PcState::party_entries(world);
+                match self.party {
+                    Some(_) => PcState::set_entries(world, self.party, entries),
+                    None => {
+                        self.party = Some(ListWidget::create(
+                            world,
+                            &ListLayout::new("pc_party", Anchor::TopLeft, 8., -8., 13., 136.),
+                            entries,
+                        ));
+                    }
+                }
+            }
+            PcPhase::ConfirmRelease(_) => {
+                self.confirm = Some(ListWidget::create(
+                    world,
+                    &ListLayout::new("pc_confirm", Anchor::TopRight, -8., -62., 13., 56.),
+                    vec![ListEntry::new("YES"), ListEntry::new("NO")],
+                ));
+            }
+            PcPhase::Wallpaper => {
+                self.wallpapers = Some(ListWidget::create(
+                    world,
+                    &ListLayout::new("pc_wallpapers", Anchor::TopLeft, 8., -34., 13., 136.).with_rows(BOX_ROWS),
+                    Wallpaper::ALL.iter().map(|w| ListEntry::new(w.label())).collect(),
+                ));
+            }
+            PcPhase::Greeting | PcPhase::Menu => {}
+        }
+
+        let (menu, slots, party) = (self.menu, self.slots, self.party);
+        PcState::set_active(world, menu, phase == PcPhase::Menu);
+        PcState::set_active(world, slots, matches!(phase, PcPhase::Box(_)));
+        PcState::set_active(world, party, phase == PcPhase::Party);
+
+        if !self.header.is_empty() {
+            self.refresh_box(world);
+        }
+    }
+
+    // Shows a message and enters the given phase once it is read.
+    fn say(&mut self, world: &mut World, text: String, next: PcPhase) -> SimpleTrans {
+        self.push(world, DialogState::new(vec![text]), next)
+    }
+
+    // Pushes a state over the PC and enters the given phase once it pops.
+    fn push<S: SimpleState + 'static>(&mut self, world: &mut World, state: S, next: PcPhase) -> SimpleTrans {
+        for list in [self.menu, self.slots, self.party, self.confirm, self.wallpapers].iter() {
+            PcState::set_active(world, *list, false);
+        }
+
+        self.after_dialog = Some(next);
+        Trans::Push(Box::new(state))
+    }
+
+    fn rejection(world: &World, error: StorageError) -> String {
+        match error {
+            StorageError::PartyFull => "Your party is full!".to_string(),
+            StorageError::BoxFull => format!("{} is full.", PcState::current_box(world).1),
+            StorageError::StorageFull => "There is no more room in the boxes.".to_string(),
+            StorageError::LastPokemon => "That's your last POKEMON\nthat can battle!".to_string(),
+            StorageError::InvalidBox | StorageError::InvalidSlot | StorageError::EmptySlot => {
+                "There is nothing there.".to_string()
+            }
+        }
+    }
+
+    // Runs an operation on the storage and the player's party together.
+    fn with_party<R>(world: &mut World, f: impl FnOnce(&mut Storage, &mut Party) -> Result<R, StorageError>) -> Result<R, StorageError> {
+        let mut storage = world.write_resource::<Storage>();
+        let mut players = world.write_storage::<Player>();
+        match (&mut players).join().next() {
+            Some(player) => f(&mut storage, &mut player.party),
+            None => Err(StorageError::InvalidSlot),
+        }
+    }
+
+    fn select_menu(&mut self, world: &mut World, index: usize) -> SimpleTrans {
+        match index {
+            0 => self.enter(world, PcPhase::Box(BoxMode::Withdraw)),
+            1 => self.enter(world, PcPhase::Party),
+            2 => self.enter(world, PcPhase::Box(BoxMode::Move)),
+            3 => self.enter(world, PcPhase::Box(BoxMode::Release)),
+            4 => self.enter(world, PcPhase::Wallpaper),
+            // The name is applied in `update` once chosen.
+            5 => return self.push(world, NamingState::new("Name this box?"), PcPhase::Menu),
+            _ => return Trans::Pop,
+        }
+
+        Trans::None
+    }
+
+    fn select_slot(&mut self, world: &mut World, mode: BoxMode, slot: usize) -> SimpleTrans {
+        let (current, _) = PcState::current_box(world);
+
+        match mode {
+            BoxMode::Withdraw => {
+                let name = world.read_resource::<Storage>().get(current, slot)
+                    .map(|pokemon| PcState::pokemon_name(world, pokemon))
+                    .unwrap_or_default();
+                let message = match PcState::with_party(world, |storage, party| storage.withdraw(party, current, slot)) {
+                    Ok(_) => format!("{} was taken out.\n\nIt's now in your party.", name),
+                    Err(error) => PcState::rejection(world, error),
+                };
+                return self.say(world, message, PcPhase::Box(mode));
+            }
+            BoxMode::Move => match self.held.take() {
+                None => {
+                    if world.read_resource::<Storage>().get(current, slot).is_some() {
+                        self.held = Some((current, slot));
+                    }
+                    self.enter(world, PcPhase::Box(mode));
+                }
+                Some(held) => {
+                    if let Err(error) = world.write_resource::<Storage>().move_pokemon(held, (current, slot)) {
+                        eprintln!("failed to move a pokemon between boxes: {:?}", error);
+                    }
+                    self.enter(world, PcPhase::Box(mode));
+                }
+            },
+            BoxMode::Release => {
+                if world.read_resource::<Storage>().get(current, slot).is_some() {
+                    self.enter(world, PcPhase::ConfirmRelease(slot));
+                }
+            }
+        }
+
+        Trans::None
+    }
+
+    fn deposit(&mut self, world: &mut World, member: usize) -> SimpleTrans {
+        let pokemon = match PcState::party(world).get(member) {
+            Some(pokemon) => PcState::pokemon_name(world, pokemon),
+            // The last entry is CANCEL.
+            None => {
+                self.enter(world, PcPhase::Menu);
+                return Trans::None;
+            }
+        };
+
+        let (current, box_name) = PcState::current_box(world);
+        match PcState::with_party(world, |storage, party| storage.deposit(party, member, current)) {
+            Ok(_) => self.say(world, format!("{} was stored in\n{}.", pokemon, box_name), PcPhase::Party),
+            Err(error) => self.say(world, PcState::rejection(world, error), PcPhase::Party),
+        }
+    }
+
+    fn release(&mut self, world: &mut World, slot: usize) -> SimpleTrans {
+        let (current, _) = PcState::current_box(world);
+        let released = world.write_resource::<Storage>().release(current, slot);
+
+        match released {
+            Ok(pokemon) => {
+                let name = PcState::pokemon_name(world, &pokemon);
+                self.say(world, format!("{} was released outside.\n\nBye-bye, {}!", name, name), PcPhase::Box(BoxMode::Release))
+            }
+            Err(error) => self.say(world, PcState::rejection(world, error), PcPhase::Box(BoxMode::Release)),
+        }
+    }
+
+    fn switch_box(&mut self, world: &mut World, forward: bool) {
+        {
+            let mut storage = world.write_resource::<Storage>();
+            let count = storage.boxes().len();
+            storage.current = if forward { (storage.current + 1) % count } else { (storage.current + count - 1) % count };
+        }
+
+        let phase = self.phase.clone();
+        self.enter(world, phase);
+    }
+
+    fn handle_list_event(&mut self, world: &mut World, event: ListEvent) -> SimpleTrans {
+        let list = Some(event.list);
+
+        match (self.phase.clone(), event.kind) {
+            (PcPhase::Menu, ListEventKind::Confirmed(index)) if list == self.menu => return self.select_menu(world, index),
+            (PcPhase::Menu, ListEventKind::Cancelled) if list == self.menu => return Trans::Pop,
+            (PcPhase::Box(mode), ListEventKind::Confirmed(slot)) if list == self.slots => return self.select_slot(world, mode, slot),
+            (PcPhase::Box(_), ListEventKind::Moved(_)) if list == self.slots => self.refresh_box(world),
+            (PcPhase::Box(mode), ListEventKind::Cancelled) if list == self.slots => {
+                // Cancelling while holding a pokemon only puts it back down.
+                if self.held.take().is_some() {
+                    self.enter(world, PcPhase::Box(mode));
+                } else {
+                    self.enter(world, PcPhase::Menu);
+                }
+            }
+            (PcPhase::Party, ListEventKind::Confirmed(member)) if list == self.party => return self.deposit(world, member),
+            (PcPhase::Party, ListEventKind::Cancelled) if list == self.party => self.enter(world, PcPhase::Menu),
+            (PcPhase::ConfirmRelease(slot), ListEventKind::Confirmed(0)) if list == self.confirm => return self.release(world, slot),
+            (PcPhase::ConfirmRelease(_), ListEventKind::Confirmed(_)) | (PcPhase::ConfirmRelease(_), ListEventKind::Cancelled) if list == self.confirm => {
+                self.enter(world, PcPhase::Box(BoxMode::Release));
+            }
+            (PcPhase::Wallpaper, ListEventKind::Confirmed(index)) if list == self.wallpapers => {
+                {
+                    let mut storage = world.write_resource::<Storage>();
+                    let current = storage.current;
+                    if let Err(error) = storage.set_wallpaper(current, Wallpaper::ALL[index]) {
+                        eprintln!("failed to set the box wallpaper: {:?}", error);
+                    }
+                }
+                self.enter(world, PcPhase::Menu);
+            }
+            (PcPhase::Wallpaper, ListEventKind::Cancelled) if list == self.wallpapers => self.enter(world, PcPhase::Menu),
+            _ => {}
+        }
+
+        Trans::None
+    }
+}
+
+impl SimpleState for PcState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        {
+            let mut game = self.fetch_game(world);
+            self.was_paused = game.paused;
+            game.paused = true;
+        }
+
+        self.reader = Some(list::register_reader(world));
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        PcState::delete_list(world, &mut self.menu);
+        PcState::delete_list(world, &mut self.slots);
+        PcState::delete_list(world, &mut self.party);
+        PcState::delete_list(world, &mut self.confirm);
+        PcState::delete_list(world, &mut self.wallpapers);
+        PcState::delete_entities(world, &mut self.header);
+        PcState::delete_entities(world, &mut self.info);
+
+        let was_paused = self.was_paused;
+        self.fetch_game(world).paused = was_paused;
+    }
+
+    fn on_resume(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        if let Some(next) = self.after_dialog.take() {
+            self.enter(data.world, next);
+        }
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let world = &mut data.world;
+
+        if self.phase == PcPhase::Greeting {
+            self.menu = Some(ListWidget::create(
+                world,
+                &ListLayout::new("pc_menu", Anchor::TopLeft, 8., -8., 13., 88.).with_rows(MENU_ROWS),
+                MENU.iter().map(|label| ListEntry::new(*label)).collect(),
+            ));
+
+            let name = (&world.read_storage::<Player>()).join().next().map(|p| p.name.clone()).unwrap_or_default();
+            return self.say(world, format!("{} booted up the PC.", name), PcPhase::Menu);
+        }
+
+        let named = {
+            let mut game = self.fetch_game(world);
+            match game.get_trigger() {
+                Some(Trigger::NameChosen(name)) => {
+                    game.clear_trigger();
+                    Some(name)
+                }
+                _ => None,
+            }
+        };
+
+        // Leaving the name empty keeps the old one.
+        if let Some(name) = named.flatten() {
+            let mut storage = world.write_resource::<Storage>();
+            let current = storage.current;
+            if let Err(error) = storage.rename(current, &name) {
+                eprintln!("failed to rename the box: {:?}", error);
+            }
+        }
+
+        let events = match &mut self.reader {
+            Some(reader) => list::read_events(world, reader),
+            None => return Trans::None,
+        };
+
+        for event in events {
+            match self.handle_list_event(world, event) {
+                Trans::None => {}
+                trans => return trans,
+            }
+        }
+
+        Trans::None
+    }
+
+    fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        let action = match event {
+            StateEvent::Input(InputEvent::ActionPressed(action)) => action,
+            _ => return Trans::None,
+        };
+
+        if let PcPhase::Box(_) = self.phase {
+            match action.as_str() {
+                "left" => self.switch_box(data.world, false),
+                "right" => self.switch_box(data.world, true),
+                _ => {}
+            }
+        }
+
+        Trans::None
+    }
+}
@@ -0,0 +1,330 @@
+use amethyst::{
+    prelude::*,
+    ecs::Entity,
+    input::InputEvent,
+    renderer::SpriteRender,
+    shred::FetchMut,
+    shrev::ReaderId,
+    ui::{UiTransform, UiText, UiImage, Anchor, LineMode, Stretch},
+};
+
+use crate::entity::menu::list::{self, ListEntry, ListEvent, ListEventKind, ListLayout, ListWidget};
+use crate::entity::screen::VirtualLayout;
+use crate::pokedex::Pokedex;
+use crate::pokemon::{library::Library, species::Species};
+use crate::state::Game;
+use crate::utils::{resolve, screen::VirtualScreen};
+
+const LIST_ROWS: usize = 8;
+// Where the icon of the highlighted species sits, right of the list.
+const ICON_X: f32 = 168.;
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum PokedexPhase {
+    List,
+    // Reading the entry of a caught species.
+    Detail,
+}
+
+// Lists every species with a Pokedex number. Unseen species are left blank, seen ones show
+// their name, types and icon, and caught ones can be opened for their full entry.
+pub struct PokedexState {
+    phase: PokedexPhase,
+    // Species ids in Pokedex order, matching the rows of the list.
+    species: Vec<String>,
+    list: Option<Entity>,
+    entities: Vec<Entity>,
+    icon: Option<Entity>,
+    summary: Option<Entity>,
+    detail: Vec<Entity>,
+    reader: Option<ReaderId<ListEvent>>,
+    was_paused: bool,
+}
+
+impl PokedexState {
+    pub fn new() -> Self {
+        PokedexState {
+            phase: PokedexPhase::List,
+            species: Vec::new(),
+            list: None,
+            entities: Vec::new(),
+            icon: None,
+            summary: None,
+            detail: Vec::new(),
+            reader: None,
+            was_paused: false,
+        }
+    }
+
+    fn fetch_game<'s>(&mut self, world: &'s mut World) -> FetchMut<'s, Game> {
+        world.write_resource::<Game>()
+    }
+
+    fn number(species: &Species) -> u16 {
+        species.dex.as_ref().map(|dex| dex.number).unwrap_or(0)
+    }
+
+    fn types(species: &Species) -> String {
+        species.types.iter()
+            .map(|t| format!("{:?}", t).to_uppercase())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    fn entries(world: &World) -> Vec<ListEntry> {
+        let library = world.read_resource::<Library>();
+        let pokedex = world.read_resource::<Pokedex>();
+
+        library.dex_species().into_iter()
+            .map(|species| {
+                let number = PokedexState::number(species);
+                if pokedex.is_caught(&species.id) {
+                    ListEntry::new(format!("No.{:03} {} *", number, species.name))
+                } else if pokedex.is_seen(&species.id) {
+                    ListEntry::new(format!("No.{:03} {}", number, species.name))
+                } else {
+                    ListEntry::disabled(format!("No.{:03} -----", number))
+                }
+            })
+            .collect()
+    }
+
+    fn top_left(id: &str, x: f32, y: f32, z: f32, width: f32, height: f32) -> UiTransform {
+        UiTransform::new(id.to_string(), Anchor::TopLeft, Anchor::TopLeft, x, y, z, width, height)
+    }
+
+    fn create_text(world: &mut World, transform: UiTransform, text: String) -> Entity {
+        let font = resolve::load_font_handle(world);
+        let mut ui_text = UiText::new(font, text, [0.1, 0.1, 0.1, 1.], 10.);
+        ui_text.line_mode = LineMode::Wrap;
+        ui_text.align = Anchor::TopLeft;
+
+        world.create_entity()
+            .with(transform)
+            .with(ui_text)
+            .build()
+    }
+
+    fn create_background(world: &mut World, id: &str, z: f32, margin: f32, color: [f32; 4]) -> Entity {
+        let (width, height) = {
+            let dim = world.read_resource::<VirtualScreen>();
+            (dim.width, dim.height)
+        };
+
+        world.create_entity()
+            .with(UiTransform::new(
+                id.to_string(),
+                Anchor::Middle,
+                Anchor::Middle,
+                0.,
+                0.,
+                z,
+                width,
+                height,
+            ).with_stretch(Stretch::XY {
+                x_margin: margin,
+                y_margin: margin,
+                keep_aspect_ratio: false,
+            }))
+            .with(UiImage::SolidColor(color))
+            .build()
+    }
+
+    fn initialize_ui(&mut self, world: &mut World) {
+        self.species = world.read_resource::<Library>().dex_species().iter().map(|s| s.id.clone()).collect();
+
+        let counts = {
+            let pokedex = world.read_resource::<Pokedex>();
+            format!("POKEDEX   SEEN {}  OWN {}", pokedex.seen_count(), pokedex.caught_count())
+        };
+
+        let background = PokedexState::create_background(world, "pokedex_background", 18., 0., [0.8, 0.25, 0.25, 1.]);
+        let header = PokedexState::create_text(world, PokedexState::top_left("pokedex_header", 8., -6., 19., 224., 14.), counts);
+        let summary = PokedexState::create_text(
+            world,
+            PokedexState::top_left("pokedex_summary", 148., -78., 19., 88., 40.),
+            "".to_string(),
+        );
+
+        let sprite_sheet = resolve::load_spritesheet_handle(world, "pokemon/icons".to_string());
+        let icon = world.create_entity()
+            .with(PokedexState::top_left("pokedex_icon", ICON_X, -26., 19., 48., 48.))
+            .with(UiImage::Sprite(SpriteRender { sprite_sheet, sprite_number: 0 }))
+            .build();
+
+        let entries = PokedexState::entries(world);
+        self.list = Some(ListWidget::create(
+            world,
+            &ListLayout::new("pokedex_list", Anchor::TopLeft, 8., -22., 19., 136.).with_rows(LIST_ROWS),
+            entries,
+        ));
+
+        self.icon = Some(icon);
+        self.summary = Some(summary);
+        self.entities = vec![background, header, summary, icon];
+    }
+
+    fn selected(&self, world: &World) -> Option<usize> {
+        self.list.and_then(|list| world.read_storage::<ListWidget>().get(list).map(|w| w.selection.index()))
+    }
+
+    // Shows the icon and name of the highlighted species, if it has been seen.
+    fn refresh(&self, world: &mut World, index: Option<usize>) {
+        let shown = {
+            let library = world.read_resource::<Library>();
+            let pokedex = world.read_resource::<Pokedex>();
+            index.and_then(|i| self.species.get(i))
+                .filter(|id| pokedex.is_seen(id))
+                .and_then(|id| library.get_species(id))
+                .map(|species| (
+                    species.types.first().map(|t| t.icon_index()).unwrap_or(0),
+                    format!("{}\n{}", species.name, PokedexState::types(species)),
+                ))
+        };
+
+        let hidden = shown.is_none();
+        let (sprite, text) = shown.unwrap_or((0, "".to_string()));
+
+        if let Some(icon) = self.icon {
+            let transforms = world.read_storage::<UiTransform>();
+            let mut layouts = world.write_storage::<VirtualLayout>();
+            if let Some(layout) = VirtualLayout::get_mut(&mut layouts, &transforms, icon) {
+                // Off screen while there is nothing to show.
+                layout.x = if hidden { -1000. } else { ICON_X };
+            }
+            let mut images = world.write_storage::<UiImage>();
+            if let Some(UiImage::Sprite(render)) = images.get_mut(icon) {
+                render.sprite_number = sprite;
+            }
+        }
+
+        if let Some(text_entity) = self.summary {
+            if let Some(ui_text) = world.write_storage::<UiText>().get_mut(text_entity) {
+                ui_text.text = text;
+            }
+        }
+    }
+
+    fn detail_text(world: &World, id: &str) -> Option<String> {
+        let library = world.read_resource::<Library>();
+        let species = library.get_species(id)?;
+        let dex = species.dex.as_ref()?;
+
+        Some(format!(
+            "No.{:03}  {}\n{} POKEMON\n{}\n\nHT {:.1} m    WT {:.1} kg\n\n{}",
+            dex.number,
+            species.name,
+            dex.category.to_uppercase(),
+            PokedexState::types(species),
+            dex.height,
+            dex.weight,
+            dex.flavor,
+        ))
+    }
+
+    fn open_detail(&mut self, world: &mut World, index: usize) {
+        let id = match self.species.get(index) {
+            Some(id) if world.read_resource::<Pokedex>().is_caught(id) => id.clone(),
+            // Only the species that were caught have their full entry.
+            _ => return,
+        };
+        let text = match PokedexState::detail_text(world, &id) {
+            Some(text) => text,
+            None => return,
+        };
+
+        let background = PokedexState::create_background(world, "pokedex_detail_background", 20., 8., [0.95, 0.95, 0.9, 1.]);
+        let body = PokedexState::create_text(world, PokedexState::top_left("pokedex_detail_text", 16., -16., 21., 208., 128.), text);
+
+        PokedexState::set_active(world, self.list, false);
+        self.detail = vec![background, body];
+        self.phase = PokedexPhase::Detail;
+    }
+
+    fn close_detail(&mut self, world: &mut World) {
+        for entity in self.detail.drain(..) {
+            world.delete_entity(entity).expect("failed to delete pokedex entry");
+        }
+
+        PokedexState::set_active(world, self.list, true);
+        self.phase = PokedexPhase::List;
+    }
+
+    fn set_active(world: &mut World, list: Option<Entity>, active: bool) {
+        let mut widgets = world.write_storage::<ListWidget>();
+        if let Some(widget) = list.and_then(|list| widgets.get_mut(list)) {
+            widget.set_active(active);
+        }
+    }
+}
+
+impl SimpleState for PokedexState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        {
+            let mut game = self.fetch_game(world);
+            self.was_paused = game.paused;
+            game.paused = true;
+        }
+
+        self.reader = Some(list::register_reader(world));
+        self.initialize_ui(world);
+        let index = self.selected(world);
+        self.refresh(world, index);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        self.close_detail(world);
+        if let Some(list) = self.list.take() {
+            ListWidget::delete(world, list);
+        }
+        for entity in self.entities.drain(..) {
+            world.delete_entity(entity).expect("failed to delete pokedex");
+        }
+
+        let was_paused = self.was_paused;
+        self.fetch_game(world).paused = was_paused;
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let world = &mut data.world;
+
+        let events = match &mut self.reader {
+            Some(reader) => list::read_events(world, reader),
+            None => return Trans::None,
+        };
+
+        for event in events {
+            if Some(event.list) != self.list || self.phase != PokedexPhase::List {
+                continue;
+            }
+
+            match event.kind {
+                ListEventKind::Moved(index) => self.refresh(world, Some(index)),
+                ListEventKind::Confirmed(index) => self.open_detail(world, index),
+                ListEventKind::Cancelled => return Trans::Pop,
+            }
+        }
+
+        Trans::None
+    }
+
+    fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        let action = match event {
+            StateEvent::Input(InputEvent::ActionPressed(action)) => action,
+            _ => return Trans::None,
+        };
+
+        match (self.phase, action.as_str()) {
+            (PokedexPhase::Detail, "continue") | (PokedexPhase::Detail, "cancel") => {
+                self.close_detail(data.world);
+                Trans::None
+            }
+            (PokedexPhase::List, "menu") => Trans::Pop,
+            _ => Trans::None,
+        }
+    }
+}
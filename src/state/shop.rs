@@ -0,0 +1,473 @@
+use amethyst::{
+    prelude::*,
+    ecs::{Entity, Join},
+    input::InputEvent,
+    renderer::SpriteRender,
+    shred::FetchMut,
+    shrev::ReaderId,
+    ui::{UiTransform, UiText, UiImage, Anchor, LineMode},
+};
+
+use crate::entity::actor::player::Player;
+use crate::entity::dialog::DialogSpritetype;
+use crate::entity::menu::list::{self, ListEntry, ListEvent, ListEventKind, ListLayout, ListWidget};
+use crate::item::{bag::Bag, shop::{self, Shops, TransactionError}, Pocket};
+use crate::pokemon::library::Library;
+use crate::state::Game;
+use crate::state::dialog::DialogState;
+use crate::utils::resolve;
+
+const MENU: [&str; 3] = ["BUY", "SELL", "QUIT"];
+const ITEM_ROWS: usize = 4;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum ShopMode {
+    Buy,
+    Sell,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum ShopPhase {
+    Greeting,
+    Menu,
+    Items(ShopMode),
+    // Choosing how many of the item to buy or sell, up to the given maximum.
+    Quantity { mode: ShopMode, item: String, max: u16 },
+    // Answering the question asked about the transaction.
+    Confirm { mode: ShopMode, item: String, quantity: u16 },
+    Exit,
+}
+
+// A Poke Mart counter: buys items from the shop's list and sells items from the bag. The
+// clerk talks through TalkDialog, and the next phase is entered once the dialog is closed.
+pub struct ShopState {
+    shop: String,
+    phase: ShopPhase,
+    // Entered when the dialog pushed by `say` is closed.
+    after_dialog: Option<ShopPhase>,
+    menu: Option<Entity>,
+    items: Option<Entity>,
+    // Item ids listed by `items`, in order.
+    listed: Vec<String>,
+    confirm: Option<Entity>,
+    quantity: u16,
+    quantity_ui: Vec<Entity>,
+    money_ui: Vec<Entity>,
+    reader: Option<ReaderId<ListEvent>>,
+    was_paused: bool,
+}
+
+impl ShopState {
+    pub fn new(shop: String) -> Self {
+        ShopState {
+            shop,
+            phase: ShopPhase::Greeting,
+            after_dialog: None,
+            menu: None,
+            items: None,
+            listed: Vec::new(),
+            confirm: None,
+            quantity: 1,
+            quantity_ui: Vec::new(),
+            money_ui: Vec::new(),
+            reader: None,
+            was_paused: false,
+        }
+    }
+
+    fn fetch_game<'s>(&mut self, world: &'s mut World) -> FetchMut<'s, Game> {
+        world.write_resource::<Game>()
+    }
+
+    fn wallet(world: &World) -> (Bag, u32) {
+        (&world.read_storage::<Player>()).join()
+            .next()
+            .map(|player| (player.bag.clone(), player.money))
+            .unwrap_or_default()
+    }
+
+    fn set_wallet(world: &mut World, bag: Bag, money: u32) {
+        for player in (&mut world.write_storage::<Player>()).join() {
+            player.bag = bag.clone();
+            player.money = money;
+        }
+    }
+
+    fn item_name(world: &World, id: &str) -> String {
+        world.read_resource::<Library>().get_item(id)
+            .map(|item| item.name.clone())
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    fn create_box(world: &mut World, id: &str, x: f32, y: f32, width: f32, height: f32) -> Vec<Entity> {
+        let sprite_sheet = resolve::load_spritesheet_handle(world, "dialogs/sheet".to_string());
+        let frame = world.create_entity()
+            .with(UiTransform::new(format!("{}_frame", id), Anchor::TopLeft, Anchor::TopLeft, x, y, 13., width, height))
+            .with(UiImage::Sprite(SpriteRender {
+                sprite_sheet,
+                sprite_number: DialogSpritetype::OptionBox.to_sprite_index(),
+            }))
+            .build();
+
+        let font = resolve::load_font_handle(world);
+        let mut text = UiText::new(font, "".to_string(), [0.1, 0.1, 0.1, 1.], 12.);
+        text.line_mode = LineMode::Wrap;
+        text.align = Anchor::TopLeft;
+
+        let text = world.create_entity()
+            .with(UiTransform::new(format!("{}_text", id), Anchor::TopLeft, Anchor::TopLeft, x + 8., y - 8., 14., width - 16., height - 16.))
+            .with(text)
+            .build();
+
+        vec![frame, text]
+    }
+
+    // Boxes made by `create_box` keep their text last.
+    fn set_box_text(world: &mut World, entities: &[Entity], text: String) {
+        let mut texts = world.write_storage::<UiText>();
+        if let Some(ui_text) = entities.last().and_then(|e| texts.get_mut(*e)) {
+            ui_text.text = text;
+        }
+    }
+
+    fn refresh_money(&self, world: &mut World) {
+        let (_, money) = ShopState::wallet(world);
+        ShopState::set_box_text(world, &self.money_ui, format!("MONEY\n${}", money));
+    }
+
+    fn price(world: &World, mode: ShopMode, id: &str) -> u32 {
+        world.read_resource::<Library>().get_item(id)
+            .map(|item| match mode {
+                ShopMode::Buy => item.price,
+                ShopMode::Sell => shop::sell_price(item),
+            })
+            .unwrap_or(0)
+    }
+
+    fn refresh_quantity(&self, world: &mut World) {
+        let total = match &self.phase {
+            ShopPhase::Quantity { mode, item, .. } => ShopState::price(world, *mode, item) * self.quantity as u32,
+            _ => return,
+        };
+        ShopState::set_box_text(world, &self.quantity_ui, format!("x {}\n${}", self.quantity, total));
+    }
+
+    fn item_entries(&mut self, world: &World, mode: ShopMode) -> Vec<ListEntry> {
+        let library = world.read_resource::<Library>();
+
+        let entries: Vec<(String, ListEntry)> = match mode {
+            ShopMode::Buy => world.read_resource::<Shops>().get(&self.shop)
+                .map(|shop| shop.items.iter()
+                    .filter_map(|id| library.get_item(id))
+                    .map(|item| (item.id.clone(), ListEntry::new(format!("{}  ${}", item.name, item.price))))
+                    .collect())
+                .unwrap_or_default(),
+            ShopMode::Sell => {
+                let (bag, _) = ShopState::wallet(world);
+                Pocket::ALL.iter()
+                    .filter(|pocket| **pocket != Pocket::KeyItems)
+                    .flat_map(|pocket| bag.pocket(*pocket).to_vec())
+                    .filter_map(|stack| library.get_item(&stack.id).map(|item| {
+                        let label = format!("{} x{}  ${}", item.name, stack.quantity, shop::sell_price(item));
+                        let entry = if shop::can_sell(item) { ListEntry::new(label) } else { ListEntry::disabled(label) };
+                        (item.id.clone(), entry)
+                    }))
+                    .collect()
+            }
+        };
+
+        let (listed, mut list_entries): (Vec<String>, Vec<ListEntry>) = entries.into_iter().unzip();
+        list_entries.push(ListEntry::new("CANCEL"));
+
+        self.listed = listed;
+        list_entries
+    }
+
+    fn delete_list(world: &mut World, list: &mut Option<Entity>) {
+        if let Some(entity) = list.take() {
+            ListWidget::delete(world, entity);
+        }
+    }
+
+    fn set_active(world: &mut World, list: Option<Entity>, active: bool) {
+        let mut widgets = world.write_storage::<ListWidget>();
+        if let Some(widget) = list.and_then(|list| widgets.get_mut(list)) {
+            widget.set_active(active);
+        }
+    }
+
+    // Enters a phase, showing the lists and boxes it needs and turning the others off.
+    fn enter(&mut self, world: &mut World, phase: ShopPhase) {
+        for entity in self.quantity_ui.drain(..) {
+            world.delete_entity(entity).expect("failed to delete quantity box");
+        }
+        ShopState::delete_list(world, &mut self.confirm);
+
+        match &phase {
+            ShopPhase::Menu | ShopPhase::Greeting | ShopPhase::Exit => ShopState::delete_list(world, &mut self.items),
+            ShopPhase::Items(mode) if self.items.is_none() => {
+                ShopState::delete_list(world, &mut self.items);
+                let entries = self.item_entries(world, *mode);
+                self.items = Some(ListWidget::create(
+                    world,
+                    &ListLayout::new("shop_items", Anchor::TopRight, -8., -8., 13., 144.).with_rows(ITEM_ROWS),
+                    entries,
+                ));
+            }
+            ShopPhase::Quantity { .. } => {
+                self.quantity = 1;
+                self.quantity_ui = ShopState::create_box(world, "shop_quantity", 160., -84., 72., 40.);
+            }
+            ShopPhase::Confirm { .. } => {
+                self.confirm = Some(ListWidget::create(
+                    world,
+                    &ListLayout::new("shop_confirm", Anchor::TopRight, -8., -66., 15., 56.),
+                    vec![ListEntry::new("YES"), ListEntry::new("NO")],
+                ));
+            }
+            ShopPhase::Items(_) => {}
+        }
+
+        let (menu, items) = (self.menu, self.items);
+        ShopState::set_active(world, menu, phase == ShopPhase::Menu);
+        ShopState::set_active(world, items, matches!(phase, ShopPhase::Items(_)));
+
+        self.phase = phase;
+        self.refresh_quantity(world);
+        self.refresh_money(world);
+    }
+
+    // Shows the clerk's line and enters the given phase once it is read.
+    fn say(&mut self, world: &mut World, text: String, next: ShopPhase) -> SimpleTrans {
+        let (menu, items) = (self.menu, self.items);
+        ShopState::set_active(world, menu, false);
+        ShopState::set_active(world, items, false);
+
+        self.after_dialog = Some(next);
+        Trans::Push(Box::new(DialogState::new(vec![text])))
+    }
+
+    fn rejection(error: TransactionError) -> &'static str {
+        match error {
+            TransactionError::NotEnoughMoney => "You don't have enough money.",
+            TransactionError::BagFull => "You have no more room for items.",
+            TransactionError::NotEnoughItems => "You don't have that many.",
+            TransactionError::CantSell => "Oh, no. I can't buy that.",
+            TransactionError::UnknownItem | TransactionError::NoQuantity => "Sorry, I can't do that.",
+        }
+    }
+
+    fn choose_item(&mut self, world: &mut World, mode: ShopMode, index: usize) -> SimpleTrans {
+        let id = match self.listed.get(index) {
+            Some(id) => id.clone(),
+            // The last entry is CANCEL.
+            None => {
+                self.enter(world, ShopPhase::Menu);
+                return Trans::None;
+            }
+        };
+
+        let (bag, money) = ShopState::wallet(world);
+        let library = world.read_resource::<Library>();
+        let (max, error) = match mode {
+            ShopMode::Buy => (shop::max_buy(&library, &bag, money, &id), shop::buy(&library, &bag, money, &id, 1).err()),
+            ShopMode::Sell => (bag.count(&library, &id), shop::sell(&library, &bag, money, &id, 1).err()),
+        };
+        drop(library);
+
+        match error {
+            Some(error) => self.say(world, ShopState::rejection(error).to_string(), ShopPhase::Items(mode)),
+            None => {
+                self.enter(world, ShopPhase::Quantity { mode, item: id, max });
+                Trans::None
+            }
+        }
+    }
+
+    fn ask(&mut self, world: &mut World) -> SimpleTrans {
+        let (mode, item) = match &self.phase {
+            ShopPhase::Quantity { mode, item, .. } => (*mode, item.clone()),
+            _ => return Trans::None,
+        };
+
+        let name = ShopState::item_name(world, &item);
+        let total = ShopState::price(world, mode, &item) * self.quantity as u32;
+        let question = match mode {
+            ShopMode::Buy => format!("{}, and you want {}?\n\nThat will be ${}. OK?", name, self.quantity, total),
+            ShopMode::Sell => format!("I can pay ${} for {} {}.\n\nWould that be OK?", total, self.quantity, name),
+        };
+
+        let quantity = self.quantity;
+        for entity in self.quantity_ui.drain(..) {
+            world.delete_entity(entity).expect("failed to delete quantity box");
+        }
+        self.say(world, question, ShopPhase::Confirm { mode, item, quantity })
+    }
+
+    fn trade(&mut self, world: &mut World) -> SimpleTrans {
+        let (mode, item, quantity) = match &self.phase {
+            ShopPhase::Confirm { mode, item, quantity } => (*mode, item.clone(), *quantity),
+            _ => return Trans::None,
+        };
+
+        let (bag, money) = ShopState::wallet(world);
+        let result = {
+            let library = world.read_resource::<Library>();
+            match mode {
+                ShopMode::Buy => shop::buy(&library, &bag, money, &item, quantity),
+                ShopMode::Sell => shop::sell(&library, &bag, money, &item, quantity),
+            }
+        };
+
+        let message = match result {
+            Ok(transaction) => {
+                let earned = transaction.money.saturating_sub(money);
+                ShopState::set_wallet(world, transaction.bag, transaction.money);
+                self.refresh_money(world);
+                match mode {
+                    ShopMode::Buy => "Here you are!\n\nThank you!".to_string(),
+                    ShopMode::Sell => format!("Turned over the {} and received ${}.", ShopState::item_name(world, &item), earned),
+                }
+            }
+            Err(error) => ShopState::rejection(error).to_string(),
+        };
+
+        ShopState::delete_list(world, &mut self.confirm);
+        // The sell list is rebuilt, since quantities changed.
+        if mode == ShopMode::Sell {
+            ShopState::delete_list(world, &mut self.items);
+        }
+        self.say(world, message, ShopPhase::Items(mode))
+    }
+
+    fn handle_list_event(&mut self, world: &mut World, event: ListEvent) -> SimpleTrans {
+        let list = Some(event.list);
+
+        match (self.phase.clone(), event.kind) {
+            (ShopPhase::Menu, ListEventKind::Confirmed(0)) if list == self.menu => self.enter(world, ShopPhase::Items(ShopMode::Buy)),
+            (ShopPhase::Menu, ListEventKind::Confirmed(1)) if list == self.menu => {
+                let (bag, _) = ShopState::wallet(world);
+                let sellable = Pocket::ALL.iter().any(|p| *p != Pocket::KeyItems && !bag.pocket(*p).is_empty());
+                if !sellable {
+                    return self.say(world, "You don't have anything to sell.".to_string(), ShopPhase::Menu);
+                }
+                self.enter(world, ShopPhase::Items(ShopMode::Sell));
+            }
+            (ShopPhase::Menu, ListEventKind::Confirmed(_)) | (ShopPhase::Menu, ListEventKind::Cancelled) if list == self.menu => {
+                return self.say(world, "Please come again!".to_string(), ShopPhase::Exit);
+            }
+            (ShopPhase::Items(mode), ListEventKind::Confirmed(index)) if list == self.items => return self.choose_item(world, mode, index),
+            (ShopPhase::Items(_), ListEventKind::Cancelled) if list == self.items => self.enter(world, ShopPhase::Menu),
+            (ShopPhase::Confirm { .. }, ListEventKind::Confirmed(0)) if list == self.confirm => return self.trade(world),
+            (ShopPhase::Confirm { mode, .. }, ListEventKind::Confirmed(_)) | (ShopPhase::Confirm { mode, .. }, ListEventKind::Cancelled) if list == self.confirm => {
+                self.enter(world, ShopPhase::Items(mode));
+            }
+            _ => {}
+        }
+
+        Trans::None
+    }
+
+    fn change_quantity(&mut self, world: &mut World, action: &str) {
+        let max = match &self.phase {
+            ShopPhase::Quantity { max, .. } => *max,
+            _ => return,
+        };
+
+        self.quantity = match action {
+            "up" if self.quantity >= max => 1,
+            "up" => self.quantity + 1,
+            "down" if self.quantity <= 1 => max,
+            "down" => self.quantity - 1,
+            "right" => (self.quantity + 10).min(max),
+            "left" => self.quantity.saturating_sub(10).max(1),
+            _ => return,
+        };
+        self.refresh_quantity(world);
+    }
+}
+
+impl SimpleState for ShopState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        {
+            let mut game = self.fetch_game(world);
+            self.was_paused = game.paused;
+            game.paused = true;
+        }
+
+        self.reader = Some(list::register_reader(world));
+        self.money_ui = ShopState::create_box(world, "shop_money", 8., -8., 80., 40.);
+        self.refresh_money(world);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        ShopState::delete_list(world, &mut self.menu);
+        ShopState::delete_list(world, &mut self.items);
+        ShopState::delete_list(world, &mut self.confirm);
+        for entity in self.quantity_ui.drain(..).chain(self.money_ui.drain(..)) {
+            world.delete_entity(entity).expect("failed to delete shop ui");
+        }
+
+        let was_paused = self.was_paused;
+        self.fetch_game(world).paused = was_paused;
+    }
+
+    fn on_resume(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        if let Some(next) = self.after_dialog.take() {
+            self.enter(data.world, next);
+        }
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let world = &mut data.world;
+
+        match self.phase {
+            ShopPhase::Greeting => {
+                self.menu = Some(ListWidget::create(
+                    world,
+                    &ListLayout::new("shop_menu", Anchor::TopLeft, 8., -50., 13., 64.),
+                    MENU.iter().map(|label| ListEntry::new(*label)).collect(),
+                ));
+                return self.say(world, "Hi there!\n\nMay I help you?".to_string(), ShopPhase::Menu);
+            }
+            ShopPhase::Exit => return Trans::Pop,
+            _ => {}
+        }
+
+        let events = match &mut self.reader {
+            Some(reader) => list::read_events(world, reader),
+            None => return Trans::None,
+        };
+
+        for event in events {
+            match self.handle_list_event(world, event) {
+                Trans::None => {}
+                trans => return trans,
+            }
+        }
+
+        Trans::None
+    }
+
+    fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        let action = match event {
+            StateEvent::Input(InputEvent::ActionPressed(action)) => action,
+            _ => return Trans::None,
+        };
+
+        let world = data.world;
+        if let ShopPhase::Quantity { mode, .. } = self.phase {
+            match action.as_str() {
+                "continue" => return self.ask(world),
+                "cancel" => self.enter(world, ShopPhase::Items(mode)),
+                _ => self.change_quantity(world, &action),
+            }
+        }
+
+        Trans::None
+    }
+}
@@ -0,0 +1,315 @@
+use amethyst::{
+    prelude::*,
+    ecs::{Entity, Join},
+    input::InputEvent,
+    renderer::SpriteRender,
+    shred::FetchMut,
+    shrev::ReaderId,
+    ui::{UiTransform, UiText, UiImage, Anchor, LineMode},
+};
+
+use crate::entity::actor::player::Player;
+use crate::entity::dialog::DialogSpritetype;
+use crate::entity::menu::list::{self, ListEntry, ListEvent, ListEventKind, ListLayout, ListWidget};
+use crate::save::SaveData;
+use crate::state::{Game, Trigger};
+use crate::state::bag::{BagContext, BagState};
+use crate::state::options::OptionsState;
+use crate::state::party::PartyState;
+use crate::state::pokedex::PokedexState;
+use crate::state::trainer_card::TrainerCardState;
+use crate::utils::{resolve, screen::VirtualScreen};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum StartMenuEntry {
+    Pokedex,
+    Pokemon,
+    Bag,
+    TrainerCard,
+    Save,
+    Options,
+    Exit,
+}
+
+impl StartMenuEntry {
+    const ALL: [StartMenuEntry; 7] = [
+        StartMenuEntry::Pokedex,
+        StartMenuEntry::Pokemon,
+        StartMenuEntry::Bag,
+        StartMenuEntry::TrainerCard,
+        StartMenuEntry::Save,
+        StartMenuEntry::Options,
+        StartMenuEntry::Exit,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            StartMenuEntry::Pokedex => "POKEDEX",
+            StartMenuEntry::Pokemon => "POKEMON",
+            StartMenuEntry::Bag => "BAG",
+            StartMenuEntry::TrainerCard => "CARD",
+            StartMenuEntry::Save => "SAVE",
+            StartMenuEntry::Options => "OPTIONS",
+            StartMenuEntry::Exit => "EXIT",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum StartMenuPhase {
+    Menu,
+    // Asking whether to save, with a YES/NO box.
+    ConfirmSave,
+    // Showing the result of saving until it is dismissed.
+    Saved,
+}
+
+struct ConfirmUi {
+    entities: Vec<Entity>,
+    text: Entity,
+    options: Entity,
+}
+
+// The overworld side menu. Movement stays paused while it or anything opened from it is shown.
+pub struct StartMenuState {
+    phase: StartMenuPhase,
+    menu: Option<Entity>,
+    confirm: Option<ConfirmUi>,
+    reader: Option<ReaderId<ListEvent>>,
+    was_paused: bool,
+}
+
+impl StartMenuState {
+    pub fn new() -> Self {
+        StartMenuState {
+            phase: StartMenuPhase::Menu,
+            menu: None,
+            confirm: None,
+            reader: None,
+            was_paused: false,
+        }
+    }
+
+    fn fetch_game<'s>(&mut self, world: &'s mut World) -> FetchMut<'s, Game> {
+        world.write_resource::<Game>()
+    }
+
+    fn player_name(world: &World) -> String {
+        (&world.read_storage::<Player>()).join()
+            .next()
+            .map(|player| player.name.clone())
+            .unwrap_or_default()
+    }
+
+    fn entries() -> Vec<ListEntry> {
+        StartMenuEntry::ALL.iter()
+            .map(|entry| ListEntry::new(entry.label()))
+            .collect()
+    }
+
+    fn set_text(world: &mut World, entity: Entity, text: String) {
+        if let Some(ui_text) = world.write_storage::<UiText>().get_mut(entity) {
+            ui_text.text = text;
+        }
+    }
+
+    fn set_active(world: &mut World, list: Option<Entity>, active: bool) {
+        let mut widgets = world.write_storage::<ListWidget>();
+        if let Some(widget) = list.and_then(|list| widgets.get_mut(list)) {
+            widget.set_active(active);
+        }
+    }
+
+    fn open_confirm(&mut self, world: &mut World) {
+        let width = world.read_resource::<VirtualScreen>().width;
+        let sprite_sheet = resolve::load_spritesheet_handle(world, "dialogs/sheet".to_string());
+        let dialog_box = world.create_entity()
+            .with(UiTransform::new(
+                "start_menu_confirm_box".to_string(),
+                Anchor::BottomMiddle,
+                Anchor::BottomMiddle,
+                0.,
+                0.,
+                13.,
+                width,
+                48.,
+            ))
+            .with(UiImage::Sprite(SpriteRender {
+                sprite_sheet,
+                sprite_number: DialogSpritetype::Bottom.to_sprite_index(),
+            }))
+            .build();
+
+        let font = resolve::load_font_handle(world);
+        let mut ui_text = UiText::new(font, "Would you like to save the game?".to_string(), [1., 1., 1., 1.], 12.);
+        ui_text.line_mode = LineMode::Wrap;
+        ui_text.align = Anchor::TopLeft;
+
+        let text = world.create_entity()
+            .with(UiTransform::new(
+                "start_menu_confirm_text".to_string(),
+                Anchor::BottomLeft,
+                Anchor::MiddleLeft,
+                10.,
+                24.,
+                14.,
+                width - 20.,
+                40.,
+            ))
+            .with(ui_text)
+            .build();
+
+        let options = ListWidget::create(
+            world,
+            &ListLayout::new("start_menu_confirm", Anchor::BottomRight, -8., 50., 13., 56.),
+            vec![ListEntry::new("YES"), ListEntry::new("NO")],
+        );
+
+        StartMenuState::set_active(world, self.menu, false);
+        self.confirm = Some(ConfirmUi { entities: vec![dialog_box, text], text, options });
+        self.phase = StartMenuPhase::ConfirmSave;
+    }
+
+    fn close_confirm(&mut self, world: &mut World) {
+        if let Some(confirm) = self.confirm.take() {
+            for entity in confirm.entities {
+                world.delete_entity(entity).expect("failed to delete save prompt");
+            }
+            if world.is_alive(confirm.options) {
+                ListWidget::delete(world, confirm.options);
+            }
+        }
+
+        StartMenuState::set_active(world, self.menu, true);
+        self.phase = StartMenuPhase::Menu;
+    }
+
+    fn save(&mut self, world: &mut World) {
+        let message = match SaveData::capture(world).map(|save| save.write()) {
+            Some(Ok(())) => format!("{} saved the game.", StartMenuState::player_name(world)),
+            Some(Err(e)) => {
+                eprintln!("failed to save the game: {}", e);
+                "The game could not be saved.".to_string()
+            }
+            None => "The game could not be saved.".to_string(),
+        };
+
+        if let Some(confirm) = &self.confirm {
+            let (text, options) = (confirm.text, confirm.options);
+            StartMenuState::set_text(world, text, message);
+            ListWidget::delete(world, options);
+        }
+        self.phase = StartMenuPhase::Saved;
+    }
+
+    fn select(&mut self, world: &mut World, entry: StartMenuEntry) -> SimpleTrans {
+        match entry {
+            StartMenuEntry::Pokedex => Trans::Push(Box::new(PokedexState::new())),
+            StartMenuEntry::Pokemon => Trans::Push(Box::new(PartyState::new())),
+            StartMenuEntry::Bag => Trans::Push(Box::new(BagState::new(BagContext::Field))),
+            StartMenuEntry::TrainerCard => Trans::Push(Box::new(TrainerCardState::new())),
+            StartMenuEntry::Save => {
+                self.open_confirm(world);
+                Trans::None
+            }
+            StartMenuEntry::Options => Trans::Push(Box::new(OptionsState::new())),
+            StartMenuEntry::Exit => Trans::Pop,
+        }
+    }
+
+    fn handle_list_event(&mut self, world: &mut World, event: ListEvent) -> SimpleTrans {
+        let options = self.confirm.as_ref().map(|c| c.options);
+
+        if Some(event.list) == self.menu && self.phase == StartMenuPhase::Menu {
+            match event.kind {
+                ListEventKind::Confirmed(index) => return self.select(world, StartMenuEntry::ALL[index]),
+                ListEventKind::Cancelled => return Trans::Pop,
+                ListEventKind::Moved(_) => {}
+            }
+        } else if Some(event.list) == options && self.phase == StartMenuPhase::ConfirmSave {
+            match event.kind {
+                ListEventKind::Confirmed(0) => self.save(world),
+                ListEventKind::Confirmed(_) | ListEventKind::Cancelled => self.close_confirm(world),
+                ListEventKind::Moved(_) => {}
+            }
+        }
+
+        Trans::None
+    }
+}
+
+impl SimpleState for StartMenuState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        {
+            let mut game = self.fetch_game(world);
+            self.was_paused = game.paused;
+            game.paused = true;
+        }
+
+        self.reader = Some(list::register_reader(world));
+        self.menu = Some(ListWidget::create(
+            world,
+            &ListLayout::new("start_menu", Anchor::TopRight, -8., -8., 13., 88.).with_wrap(),
+            StartMenuState::entries(),
+        ));
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        self.close_confirm(world);
+
+        if let Some(menu) = self.menu.take() {
+            ListWidget::delete(world, menu);
+        }
+
+        let was_paused = self.was_paused;
+        self.fetch_game(world).paused = was_paused;
+    }
+
+    fn on_pause(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        StartMenuState::set_active(data.world, self.menu, false);
+    }
+
+    fn on_resume(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        StartMenuState::set_active(data.world, self.menu, self.phase == StartMenuPhase::Menu);
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let world = &mut data.world;
+
+        // A field move picked from the party screen is left for the overworld to handle.
+        if let Some(Trigger::FieldMove { .. }) = self.fetch_game(world).get_trigger() {
+            return Trans::Pop;
+        }
+
+        let events = match &mut self.reader {
+            Some(reader) => list::read_events(world, reader),
+            None => return Trans::None,
+        };
+
+        for event in events {
+            match self.handle_list_event(world, event) {
+                Trans::None => {}
+                trans => return trans,
+            }
+        }
+
+        Trans::None
+    }
+
+    fn handle_event(&mut self, _data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        let action = match event {
+            StateEvent::Input(InputEvent::ActionPressed(action)) => action,
+            _ => return Trans::None,
+        };
+
+        match (self.phase, action.as_str()) {
+            (StartMenuPhase::Menu, "menu") => Trans::Pop,
+            (StartMenuPhase::Saved, "continue") | (StartMenuPhase::Saved, "cancel") => Trans::Pop,
+            _ => Trans::None,
+        }
+    }
+}
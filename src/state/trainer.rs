@@ -0,0 +1,225 @@
+use amethyst::{
+    prelude::*,
+    core::{transform::Transform, Time},
+    ecs::{Entity, Join},
+    shred::FetchMut,
+    ui::{UiTransform, UiText, Anchor},
+};
+
+use crate::battle::BattleOutcome;
+use crate::entity::actor::{npc::Npc, player::Player};
+use crate::entity::camera::CameraFollow;
+use crate::state::{Game, Trigger};
+use crate::state::battle::BattleState;
+use crate::state::dialog::DialogState;
+use crate::story::Story;
+use crate::trainer::Trainers;
+use crate::utils::{resolve, screen::VirtualScreen};
+
+// Seconds the exclamation mark shows before the trainer walks up.
+const SPOTTED_TIME: f32 = 0.5;
+// Seconds the camera takes to pan over to the trainer and back.
+pub const PAN_TIME: f32 = 0.25;
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum TrainerPhase {
+    Spotted,
+    Approach,
+    Intro,
+    Battle,
+    Defeat,
+}
+
+// Runs a trainer encounter: exclamation mark, the trainer walking up, the pre-battle line,
+// the battle itself and the line said after losing.
+pub struct TrainerState {
+    npc: Entity,
+    trainer: Option<String>,
+    phase: TrainerPhase,
+    // Seconds since the trainer spotted the player.
+    elapsed: f32,
+    exclamation: Option<Entity>,
+}
+
+impl TrainerState {
+    pub fn new(npc: Entity) -> Self {
+        TrainerState {
+            npc,
+            trainer: None,
+            phase: TrainerPhase::Spotted,
+            elapsed: 0.0,
+            exclamation: None,
+        }
+    }
+
+    fn fetch_game<'s>(&mut self, world: &'s mut World) -> FetchMut<'s, Game> {
+        world.write_resource::<Game>()
+    }
+
+    fn create_exclamation(&mut self, world: &mut World) {
+        let translation = match world.read_storage::<Transform>().get(self.npc) {
+            Some(transform) => *transform.translation(),
+            None => return,
+        };
+
+        // Convert the npc's world position into screen space, relative to the bottom-left corner.
+        // The camera's translation is the top-left corner.
+        let camera = world.read_resource::<Game>().camera_trans;
+        let height = world.read_resource::<VirtualScreen>().height;
+        let x = translation[0] - camera[0];
+        let y = translation[1] - camera[1] + height + 24.0;
+
+        let font = resolve::load_font_handle(world);
+        let text = UiText::new(font, "!".to_string(), [0.9, 0.1, 0.1, 1.], 16.);
+        let transform = UiTransform::new(
+            "trainer_exclamation".to_string(),
+            Anchor::BottomLeft,
+            Anchor::Middle,
+            x,
+            y,
+            5.,
+            12.,
+            16.,
+        );
+
+        self.exclamation = Some(world.create_entity()
+            .with(transform)
+            .with(text)
+            .build());
+    }
+
+    fn approach(&mut self, world: &mut World) {
+        let mut npcs = world.write_storage::<Npc>();
+        let mut players = world.write_storage::<Player>();
+
+        let npc = match npcs.get_mut(self.npc) {
+            Some(npc) => npc,
+            None => return,
+        };
+
+        for player in (&mut players).join() {
+            let d = player.grid_pos - npc.grid_pos;
+            let distance = (d[0].abs() + d[1].abs()) as usize;
+
+            // Stop on the cell next to the player and face each other.
+            npc.steps = distance.saturating_sub(1);
+            player.attrs.direction = npc.attrs.direction.opposite();
+        }
+    }
+
+    // Pans the camera over to the trainer that spotted the player, or back to the player.
+    fn pan_camera(&self, world: &mut World, to_trainer: bool) {
+        let translation = match world.read_storage::<Transform>().get(self.npc) {
+            Some(transform) => *transform.translation(),
+            None => return,
+        };
+
+        for follow in (&mut world.write_storage::<CameraFollow>()).join() {
+            if to_trainer {
+                follow.pan_to((translation[0], translation[1]), PAN_TIME);
+            } else {
+                follow.release(PAN_TIME);
+            }
+        }
+    }
+
+    fn is_npc_idle(&self, world: &World) -> bool {
+        world.read_storage::<Npc>().get(self.npc)
+            .map(|npc| npc.is_idle())
+            .unwrap_or(true)
+    }
+
+    fn lines(&self, world: &World, intro: bool) -> Vec<String> {
+        let trainers = world.read_resource::<Trainers>();
+        self.trainer.as_ref()
+            .and_then(|id| trainers.get(id))
+            .map(|t| if intro { t.intro.clone() } else { t.defeat.clone() })
+            .unwrap_or_default()
+    }
+}
+
+impl SimpleState for TrainerState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        self.fetch_game(world).paused = true;
+
+        self.trainer = world.read_storage::<Npc>().get(self.npc).and_then(|npc| npc.trainer.clone());
+        self.create_exclamation(world);
+        if self.trainer.is_some() {
+            self.pan_camera(world, true);
+        }
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        if let Some(exclamation) = self.exclamation.take() {
+            world.delete_entity(exclamation).expect("failed to delete exclamation mark");
+        }
+
+        self.fetch_game(world).paused = false;
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let world = &mut data.world;
+
+        let trainer = match &self.trainer {
+            Some(trainer) => trainer.clone(),
+            None => return Trans::Pop,
+        };
+
+        match self.phase {
+            TrainerPhase::Spotted => {
+                self.elapsed += world.read_resource::<Time>().delta_seconds();
+                if self.elapsed >= SPOTTED_TIME {
+                    if let Some(exclamation) = self.exclamation.take() {
+                        world.delete_entity(exclamation).expect("failed to delete exclamation mark");
+                    }
+
+                    self.approach(world);
+                    self.pan_camera(world, false);
+                    self.phase = TrainerPhase::Approach;
+                }
+            }
+            TrainerPhase::Approach => {
+                if self.is_npc_idle(world) {
+                    self.phase = TrainerPhase::Intro;
+
+                    let intro = self.lines(world, true);
+                    if !intro.is_empty() {
+                        return Trans::Push(Box::new(DialogState::new(intro)));
+                    }
+                }
+            }
+            TrainerPhase::Intro => {
+                self.phase = TrainerPhase::Battle;
+                return Trans::Push(Box::new(BattleState::trainer(trainer)));
+            }
+            TrainerPhase::Battle => {
+                let trigger = {
+                    let mut game = self.fetch_game(world);
+                    let trigger = game.get_trigger();
+                    game.clear_trigger();
+                    trigger
+                };
+
+                if let Some(Trigger::BattleEnd(BattleOutcome::Won)) = trigger {
+                    world.write_resource::<Story>().defeat_trainer(&trainer);
+                    self.phase = TrainerPhase::Defeat;
+
+                    let defeat = self.lines(world, false);
+                    if !defeat.is_empty() {
+                        return Trans::Push(Box::new(DialogState::new(defeat)));
+                    }
+                }
+
+                return Trans::Pop;
+            }
+            TrainerPhase::Defeat => {
+                return Trans::Pop;
+            }
+        }
+
+        Trans::None
+    }
+}
@@ -0,0 +1,99 @@
+use amethyst::{
+    prelude::*,
+    ecs::{Entity, Join},
+    input::InputEvent,
+    ui::{UiTransform, UiText, UiImage, Anchor, LineMode, Stretch},
+};
+
+use crate::entity::actor::player::Player;
+use crate::story::Story;
+use crate::utils::resolve;
+
+// Shows the player's name, ID, money and progress until it is dismissed.
+pub struct TrainerCardState {
+    entities: Vec<Entity>,
+}
+
+impl TrainerCardState {
+    pub fn new() -> Self {
+        TrainerCardState {
+            entities: Vec::new(),
+        }
+    }
+
+    fn card_text(world: &World) -> String {
+        let players = world.read_storage::<Player>();
+        let player = match (&players).join().next() {
+            Some(player) => player,
+            None => return "".to_string(),
+        };
+        let story = world.read_resource::<Story>();
+
+        format!(
+            "TRAINER CARD\n\nIDNo. {:05}\n\nNAME    {}\nMONEY   ${}\nPOKEMON   {}\nTRAINERS DEFEATED   {}",
+            player.id, player.name, player.money, player.party.len(), story.trainers_defeated(),
+        )
+    }
+
+    fn initialize_ui(&mut self, world: &mut World) {
+        let text = TrainerCardState::card_text(world);
+
+        let background = world.create_entity()
+            .with(UiTransform::new(
+                "trainer_card_background".to_string(),
+                Anchor::Middle,
+                Anchor::Middle,
+                0.,
+                0.,
+                25.,
+                216.,
+                144.,
+            ).with_stretch(Stretch::XY {
+                x_margin: 12.,
+                y_margin: 8.,
+                keep_aspect_ratio: false,
+            }))
+            .with(UiImage::SolidColor([0.95, 0.75, 0.35, 1.]))
+            .build();
+
+        let font = resolve::load_font_handle(world);
+        let mut ui_text = UiText::new(font, text, [0.1, 0.1, 0.1, 1.], 12.);
+        ui_text.line_mode = LineMode::Wrap;
+        ui_text.align = Anchor::TopLeft;
+
+        let body = world.create_entity()
+            .with(UiTransform::new(
+                "trainer_card_text".to_string(),
+                Anchor::Middle,
+                Anchor::Middle,
+                0.,
+                0.,
+                26.,
+                196.,
+                128.,
+            ))
+            .with(ui_text)
+            .build();
+
+        self.entities = vec![background, body];
+    }
+}
+
+impl SimpleState for TrainerCardState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.initialize_ui(data.world);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        for entity in self.entities.drain(..) {
+            data.world.delete_entity(entity).expect("failed to delete trainer card");
+        }
+    }
+
+    fn handle_event(&mut self, _data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        match event {
+            StateEvent::Input(InputEvent::ActionPressed(action)) if action == "continue" || action == "cancel" => Trans::Pop,
+            _ => Trans::None,
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use amethyst::{
+    prelude::*,
+    core::{math::Vector2, transform::Transform},
+    ecs::{Entity, Join},
+    renderer::camera::Camera,
+    shred::FetchMut,
+};
+
+use crate::audio::{Sound, SoundQueue};
+use crate::entity::actor::{world_position, player::Player};
+use crate::entity::camera::framing;
+use crate::entity::map::fade::MapFade;
+use crate::map::{layout::Warp, loader};
+use crate::state::{Game, Trigger};
+use crate::utils::screen::VirtualScreen;
+
+// Moves the player through a warp: the screen fades out, the old map is replaced by the
+// destination map while it is black, and it fades back in on the destination cell.
+pub struct WarpState {
+    warp: Warp,
+    fade: Option<Entity>,
+    // Set once the player is on the destination map and the screen is fading back in.
+    arrived: bool,
+    was_paused: bool,
+}
+
+impl WarpState {
+    pub fn new(warp: Warp) -> Self {
+        WarpState {
+            warp,
+            fade: None,
+            arrived: false,
+            was_paused: false,
+        }
+    }
+
+    fn fetch_game<'s>(&mut self, world: &'s mut World) -> FetchMut<'s, Game> {
+        world.write_resource::<Game>()
+    }
+
+    fn delete_fade(&mut self, world: &mut World) {
+        if let Some(fade) = self.fade.take() {
+            world.delete_entity(fade).expect("failed to delete map fade");
+        }
+    }
+
+    fn travel(&mut self, world: &mut World) {
+        loader::unload(world);
+        loader::load(world, &self.warp.map);
+
+        let (x, y) = self.warp.destination;
+        let position = world_position(Vector2::new(x, y));
+
+        {
+            let mut players = world.write_storage::<Player>();
+            let mut transforms = world.write_storage::<Transform>();
+            for (player, transform) in (&mut players, &mut transforms).join() {
+                player.grid_pos = Vector2::new(x, y);
+                player.step_offset = Vector2::zeros();
+                player.attrs.direction = self.warp.direction;
+
+                transform.set_translation_x(position.0);
+                transform.set_translation_y(position.1);
+            }
+        }
+
+        // Put the camera over the destination right away, so that the right tiles are spawned.
+        // The camera follow system takes it from there.
+        let (width, height) = {
+            let dim = world.read_resource::<VirtualScreen>();
+            (dim.width, dim.height)
+        };
+        let translation = framing(Vector2::new(position.0, position.1), width, height);
+        {
+            let cameras = world.read_storage::<Camera>();
+            let mut transforms = world.write_storage::<Transform>();
+            for (_, transform) in (&cameras, &mut transforms).join() {
+                transform.set_translation_x(translation[0]);
+                transform.set_translation_y(translation[1]);
+                world.write_resource::<Game>().camera_trans = *transform.translation();
+            }
+        }
+
+        loader::stream(world);
+    }
+}
+
+impl SimpleState for WarpState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        {
+            let mut game = self.fetch_game(world);
+            self.was_paused = game.paused;
+            game.paused = true;
+        }
+
+        world.write_resource::<SoundQueue>().play(Sound::Door);
+        self.fade = Some(MapFade::create(world, false));
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        self.delete_fade(world);
+
+        let was_paused = self.was_paused;
+        self.fetch_game(world).paused = was_paused;
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let world = &mut data.world;
+
+        // The camera may still settle inside the bounds of the new map while fading in.
+        if self.arrived {
+            loader::stream(world);
+        }
+
+        let faded = {
+            let mut game = self.fetch_game(world);
+            match game.get_trigger() {
+                Some(Trigger::FadeEnd) => {
+                    game.clear_trigger();
+                    true
+                }
+                _ => false,
+            }
+        };
+
+        if !faded {
+            return Trans::None;
+        }
+
+        self.delete_fade(world);
+        if self.arrived {
+            return Trans::Pop;
+        }
+
+        self.travel(world);
+        self.fade = Some(MapFade::create(world, true));
+        self.arrived = true;
+        Trans::None
+    }
+}
@@ -0,0 +1,24 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+// Progress through the game that outlives a single map or battle.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Story {
+    // Kept sorted so that saves come out the same every time.
+    defeated_trainers: BTreeSet<String>,
+}
+
+impl Story {
+    pub fn defeat_trainer(&mut self, id: &str) {
+        self.defeated_trainers.insert(id.to_string());
+    }
+
+    pub fn is_trainer_defeated(&self, id: &str) -> bool {
+        self.defeated_trainers.contains(id)
+    }
+
+    pub fn trainers_defeated(&self) -> usize {
+        self.defeated_trainers.len()
+    }
+}
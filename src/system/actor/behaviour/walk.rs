@@ -1,59 +1,63 @@
-use amethyst::{
-    core::transform::Transform,
-    ecs::prelude::Read,
-    input::{InputHandler, StringBindings},
-};
+use amethyst::core::math::Vector2;
 
-use crate::entity::actor::{ActorAction, ActorDirection};
+use crate::audio::{Sound, SoundQueue};
+use crate::entity::actor::{ActorAction, ActorDirection, CELL_SIZE};
 use crate::entity::actor::player::Player;
-use crate::system::actor::player::PlayerBehaviour;
-use crate::utils::resolve::get_fps;
-
-#[derive(Eq, PartialEq)]
-enum SpriteTransitionType {
-    Walk,
-    Turn,
-    None,
+use crate::system::actor::player::{PlayerBehaviour, PlayerInput};
+use crate::utils::resolve;
+
+// Walk states:
+//
+//   Idle     --direction faced-------------------------> Walking
+//   Idle     --other direction, right after a step-----> Walking  (the walk keeps its momentum)
+//   Idle     --other direction-------------------------> Turning  (faces it)
+//   Turning  --tap threshold passed, direction held----> Walking
+//   Turning  --tap threshold passed, nothing held------> Idle     (a tap only turns)
+//   Walking  --cell reached, direction buffered/held---> Walking  (chained without a gap)
+//   Walking  --cell reached, nothing held--------------> Idle
+//
+// Walking into a blocked cell is Bumping instead: the player walks in place for a step,
+// then carries on like Walking.
+//
+// Directions pressed while turning or walking are buffered and taken once the cell is
+// reached, so that a tap is never dropped, even one shorter than a frame.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum WalkState {
+    Idle,
+    // Seconds since the player turned.
+    Turning(f32),
+    // Seconds into the step.
+    Walking(f32),
+    // Seconds into the step in place.
+    Bumping(f32),
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
-enum WalkKey {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-type Event = Option<WalkKey>;
-
 pub struct Walk {
-    counter: usize,
-    direction: ActorDirection,
-    sprite_transition: SpriteTransitionType,
+    state: WalkState,
+    // The direction pressed last while busy, taken at the end of the turn or step.
+    buffered: Option<ActorDirection>,
+    // Seconds since the last step ended, for the walk's momentum.
+    since_step: f32,
     action_state: ActorAction,
-    bypass_counter: usize,
-    key_event: Event,
-    active: bool,
     speed: f32,
-    transition: f32,
-    transition_slice: f32,
-    capframes: f32,
+    // Seconds taken by one step.
+    step_time: f32,
+    tap_to_turn: f32,
+    // Sounds to play, handed over at the end of the frame.
+    sounds: Vec<Sound>,
 }
 
 impl Walk {
     pub fn new() -> Box<Self> {
         let mut walk = Walk {
-            counter: 0,
-            direction: ActorDirection::None,
-            sprite_transition: SpriteTransitionType::None,
+            state: WalkState::Idle,
+            buffered: None,
+            since_step: f32::INFINITY,
             action_state: ActorAction::Stand,
-            bypass_counter: 0,
-            key_event: None,
-            active: false,
             speed: 3.0,
-            transition: 0.0,
-            transition_slice: 0.0,
-            capframes: 0.0,
+            step_time: 0.0,
+            tap_to_turn: 0.0,
+            sounds: Vec::new(),
         };
 
         walk.init();
@@ -61,217 +65,346 @@ impl Walk {
     }
 
     pub fn init(&mut self) {
-        self.capframes = get_fps() as f32 / self.speed;
+        self.step_time = 1.0 / self.speed;
+        self.tap_to_turn = resolve::get_timing().tap_to_turn;
     }
 
-    fn cycle_completed(&self) -> bool {
-        self.counter == 0
+    fn pre_walk(&mut self) {
+        if self.action_state == ActorAction::Walk1 {
+            self.action_state = ActorAction::Walk2;
+        } else {
+            self.action_state = ActorAction::Walk1;
+        }
     }
 
-    fn apply_grid_transition(&mut self, player: &mut Player) {
-        if self.active {
-            match self.direction {
-                ActorDirection::North => { player.grid_pos[1] += 1 }
-                ActorDirection::South => { player.grid_pos[1] -= 1  }
-                ActorDirection::East => { player.grid_pos[0] += 1  }
-                ActorDirection::West => { player.grid_pos[0] -= 1  }
-                _ => {}
-            };
-        }
+    // Takes the next direction to go in: the one buffered, or else the one held.
+    fn next(&mut self, input: &PlayerInput) -> Option<ActorDirection> {
+        self.buffered.take().or(input.held)
     }
 
-    fn apply_camera_transition(&mut self, transform: &mut Transform) {
-        let mut slice = self.transition_slice;
-        if self.transition > 0.0 {
-            if self.transition < slice {
-                slice = self.transition;
-            }
+    fn is_blocked(player: &Player, input: &PlayerInput, direction: ActorDirection) -> bool {
+        let (dx, dy) = direction.to_delta();
+        input.collision.is_blocked((player.grid_pos[0] + dx, player.grid_pos[1] + dy))
+    }
 
-            match self.direction {
-                ActorDirection::North => { transform.prepend_translation_y(slice); }
-                ActorDirection::South => { transform.prepend_translation_y(-slice); }
-                ActorDirection::East => { transform.prepend_translation_x(slice); }
-                ActorDirection::West => { transform.prepend_translation_x(-slice); }
-                _ => {}
-            };
-            self.transition -= slice;
+    // Leaves Idle, or chains on from a finished turn or step, starting `elapsed` seconds in.
+    fn start(&mut self, player: &mut Player, input: &PlayerInput, direction: ActorDirection, elapsed: f32) {
+        let momentum = self.since_step < self.step_time;
+        let walk = direction == player.attrs.direction || momentum;
+
+        player.attrs.direction = direction;
+        self.pre_walk();
+        self.state = if !walk {
+            WalkState::Turning(elapsed)
+        } else if Walk::is_blocked(player, input, direction) {
+            self.sounds.push(Sound::Bump);
+            WalkState::Bumping(elapsed)
         } else {
-            self.transition = 0.0;
-        }
+            WalkState::Walking(elapsed)
+        };
     }
 
-    fn handle(&mut self) {
-        self.key_event = None;
-    }
+    fn turn(&mut self, player: &mut Player, input: &PlayerInput, elapsed: f32) {
+        if elapsed < self.tap_to_turn {
+            self.state = WalkState::Turning(elapsed);
+            return;
+        }
 
-    fn get_event(&self, input: &Read<InputHandler<StringBindings>>) -> Event {
-        let left = input.action_is_down("left").unwrap_or(false);
-        let right = input.action_is_down("right").unwrap_or(false);
-        let up = input.action_is_down("up").unwrap_or(false);
-        let down = input.action_is_down("down").unwrap_or(false);
-
-        if left {
-            Some(WalkKey::Left)
-        } else if right {
-            Some(WalkKey::Right)
-        } else if up {
-            Some(WalkKey::Up)
-        } else if down {
-            Some(WalkKey::Down)
-        } else {
-            None
+        let leftover = elapsed - self.tap_to_turn;
+        self.state = WalkState::Idle;
+        if let Some(direction) = self.next(input) {
+            self.start(player, input, direction, leftover);
         }
     }
 
-    fn set_transition(&mut self, player: &mut Player, direction: ActorDirection) {
-        if player.attrs.direction == direction || self.active {
-            let dx: f32 = 32.0;
-            let dy: f32 = 32.0;
+    fn walk(&mut self, player: &mut Player, input: &PlayerInput, elapsed: f32) {
+        if elapsed < self.step_time {
+            self.state = WalkState::Walking(elapsed);
+            return;
+        }
 
-            self.transition = match direction {
-                ActorDirection::North | ActorDirection::South => dy,
-                ActorDirection::East | ActorDirection::West => dx,
-                _ => 0.0
-            };
+        let (dx, dy) = player.attrs.direction.to_delta();
+        player.grid_pos += Vector2::new(dx, dy);
 
-            self.transition_slice = self.transition / self.capframes;
+        self.since_step = 0.0;
+        self.finish_step(player, input, elapsed - self.step_time);
+    }
 
-            self.sprite_transition = SpriteTransitionType::Walk;
-            player.attrs.direction = direction.clone();
-        } else {
-            self.transition = 0.0;
-            self.transition_slice = 0.0;
-            self.sprite_transition = SpriteTransitionType::Turn;
+    fn bump(&mut self, player: &mut Player, input: &PlayerInput, elapsed: f32) {
+        if elapsed < self.step_time {
+            self.state = WalkState::Bumping(elapsed);
+            return;
         }
 
-        self.direction = direction.clone();
+        self.finish_step(player, input, elapsed - self.step_time);
     }
 
-    fn apply_sprite_transition(&mut self, player: &mut Player, direction: ActorDirection) {
-        let capframes = self.capframes;
-        // Turns need to be faster than walks.
-        if self.sprite_transition == SpriteTransitionType::Walk {
-            // Sprite transition for walks:- (Stand) -> Walk_i -> Stand
-            // Frame Definition:
-            // 0..F/2 :- Walk_i
-            // F/2..F :- Stand
-
-            let slice = capframes / 2.0;
-            let f1_slice = slice;
-            let f2_slice = capframes;
-
-            if self.counter < f1_slice as usize {
-                player.attrs.action = self.action_state.clone();
-            } else if self.counter < f2_slice as usize {
-                player.attrs.action = ActorAction::Stand;
-            }
-
+    fn finish_step(&mut self, player: &mut Player, input: &PlayerInput, leftover: f32) {
+        self.state = WalkState::Idle;
+        if let Some(direction) = self.next(input) {
+            self.start(player, input, direction, leftover);
+        }
+    }
 
-            if self.counter == (f2_slice - 1.0) as usize {
-                self.handle();
-            }
-        } else if self.sprite_transition == SpriteTransitionType::Turn {
-            // Sprite transition for turn:- (Stand) -> Walk_i -> Stand
-            // Frame Definition:
-            // 0..F/3 :- Walk_i
-            // F/3..2F/3 :- Stand
-            // handle keydown event.
-
-            let slice = capframes / 3.0;
-            let f1_slice = slice;
-            let f2_slice = 2.0 * slice;
-
-            // Change direction.
-            player.attrs.direction = direction;
-
-            if self.counter < f1_slice as usize {
-                player.attrs.action = self.action_state.clone();
-            } else if self.counter < f2_slice as usize {
-                player.attrs.action = ActorAction::Stand;
+    // Moves the state machine on by the time the input covers.
+    fn advance(&mut self, player: &mut Player, input: &PlayerInput) {
+        if self.state != WalkState::Idle {
+            if let Some(direction) = input.pressed.last() {
+                self.buffered = Some(*direction);
             }
+        }
 
-            if self.counter == (f2_slice - 1.0) as usize {
-                self.sprite_transition = SpriteTransitionType::None;
-                self.handle();
+        match self.state {
+            WalkState::Idle => {
+                self.since_step += input.delta;
+                if let Some(direction) = input.pressed.last().copied().or(input.held) {
+                    self.start(player, input, direction, 0.0);
+                }
             }
+            WalkState::Turning(elapsed) => self.turn(player, input, elapsed + input.delta),
+            WalkState::Walking(elapsed) => self.walk(player, input, elapsed + input.delta),
+            WalkState::Bumping(elapsed) => self.bump(player, input, elapsed + input.delta),
         }
     }
 
-    fn pre_walk(&mut self) {
-        if self.action_state == ActorAction::Walk1 {
-            self.action_state = ActorAction::Walk2;
+    // Poses the player for the state it is in.
+    fn draw(&self, player: &mut Player) {
+        // Sprite transition for walks:- Walk_i -> Stand
+        // 0..T/2 :- Walk_i
+        // T/2..T :- Stand
+        // Turns use the tap threshold in place of the step time.
+        let (elapsed, duration) = match self.state {
+            WalkState::Idle => (0.0, 0.0),
+            WalkState::Turning(elapsed) => (elapsed, self.tap_to_turn),
+            WalkState::Walking(elapsed) | WalkState::Bumping(elapsed) => (elapsed, self.step_time),
+        };
+
+        player.attrs.action = if elapsed < duration / 2.0 {
+            self.action_state
         } else {
-            self.action_state = ActorAction::Walk1;
+            ActorAction::Stand
+        };
+
+        // The offset towards the next cell. It is folded into `grid_pos` once the step
+        // completes.
+        player.step_offset = match self.state {
+            WalkState::Walking(elapsed) => {
+                let (dx, dy) = player.attrs.direction.to_delta();
+                Vector2::new(dx as f32, dy as f32) * CELL_SIZE * (elapsed / self.step_time).min(1.0)
+            }
+            _ => Vector2::zeros(),
+        };
+    }
+}
+
+impl PlayerBehaviour for Walk {
+    fn run(&mut self, player: &mut Player, input: &PlayerInput, sounds: &mut SoundQueue) -> bool {
+        self.advance(player, input);
+        self.draw(player);
+
+        for sound in self.sounds.drain(..) {
+            sounds.play(sound);
         }
+
+        self.state != WalkState::Idle
     }
+}
 
-    fn map_to_direction(&mut self, key: Event) -> ActorDirection {
-        if key.is_some() {
-            match key.unwrap() {
-                WalkKey::Up => ActorDirection::North,
-                WalkKey::Down => ActorDirection::South,
-                WalkKey::Left => ActorDirection::West,
-                WalkKey::Right => ActorDirection::East,
-            }
-        } else {
-            ActorDirection::None
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::bag::Bag;
+    use crate::map::collision::CollisionMap;
+    use crate::pokemon::party::Party;
+
+    const FRAME: f32 = 1.0 / 60.0;
+
+    fn player() -> Player {
+        let mut attrs = crate::entity::actor::ActorAttrs::new();
+        attrs.direction = ActorDirection::East;
+
+        Player {
+            name: "RED".to_string(),
+            id: 0,
+            money: 0,
+            attrs,
+            sprite_sheet_handle: None,
+            grid_pos: Vector2::new(0, 0),
+            step_offset: Vector2::zeros(),
+            party: Party::default(),
+            bag: Bag::default(),
         }
     }
 
-    fn evaluate(&mut self, player: &mut Player, input: &Read<InputHandler<StringBindings>>) {
-        // wait for any pending key_events and then validate current key_event.
-        if self.key_event.is_none() && self.get_event(input).is_some() {
-            // register a new key_event.
-            self.key_event = self.get_event(input);
-            if self.key_event.is_some() {
-                // set turn-bypass counter.
-                let direction = self.map_to_direction(self.key_event);
-                if direction == player.attrs.direction {
-                    self.active = true;
-                    self.bypass_counter = 0;
-                }
-            }
-        } else {
-            // when the bypass_counter reaches F (capframes), it is set to 0.
-            // indicating that the player's walk momentum is negligible, and any turn operation,
-            // will result only in sprite transitions and not viewport transitions.
-
-            self.bypass_counter += 1;
-            if self.bypass_counter == self.capframes as usize {
-                self.bypass_counter = 0;
-                self.active = false;
+    fn walk() -> Walk {
+        let mut walk = *Walk::new();
+        walk.tap_to_turn = 0.2;
+        walk
+    }
+
+    fn input(held: Option<ActorDirection>, pressed: Vec<ActorDirection>, collision: &CollisionMap, delta: f32) -> PlayerInput<'_> {
+        PlayerInput {
+            held,
+            pressed,
+            collision,
+            delta,
+        }
+    }
+
+    // Holds east for `duration` seconds, then lets go until the walk stops. Returns where the
+    // player ended up and when it reached each cell on the way.
+    fn hold_east(delta: f32, duration: f32) -> (Vector2<i32>, Vec<f32>) {
+        let mut walk = walk();
+        let mut player = player();
+        let collision = CollisionMap::new();
+        let mut sounds = SoundQueue::default();
+        let mut arrivals = Vec::new();
+        let mut time = 0.0;
+
+        let mut busy = true;
+        while time < duration || busy {
+            let held = if time < duration { Some(ActorDirection::East) } else { None };
+            let pressed = if time == 0.0 { vec![ActorDirection::East] } else { Vec::new() };
+            let cell = player.grid_pos;
+
+            time += delta;
+            busy = walk.run(&mut player, &input(held, pressed, &collision, delta), &mut sounds);
+            if player.grid_pos != cell {
+                arrivals.push(time);
             }
         }
+
+        (player.grid_pos, arrivals)
     }
 
+    // One frame of scripted input: the direction held at its end and the ones pressed during it.
+    struct Frame {
+        held: Option<ActorDirection>,
+        pressed: Vec<ActorDirection>,
+    }
 
-}
+    fn held(direction: ActorDirection, frames: usize) -> Vec<Frame> {
+        (0..frames).map(|i| Frame {
+            held: Some(direction),
+            pressed: if i == 0 { vec![direction] } else { Vec::new() },
+        }).collect()
+    }
 
-impl PlayerBehaviour for Walk {
-    fn run(&mut self, player: &mut Player, transform: &mut Transform, input: &Read<InputHandler<StringBindings>>) -> bool {
-        self.evaluate(player, input);
-
-        if self.key_event.is_some() {
-            if self.cycle_completed() {
-                let direction = self.map_to_direction(self.key_event.clone());
-                self.pre_walk();
-                self.set_transition(player, direction);
-            }
+    // A press released within the same frame.
+    fn tap(direction: ActorDirection) -> Vec<Frame> {
+        vec![Frame { held: None, pressed: vec![direction] }]
+    }
+
+    fn idle(frames: usize) -> Vec<Frame> {
+        (0..frames).map(|_| Frame { held: None, pressed: Vec::new() }).collect()
+    }
 
-            self.apply_sprite_transition(player, self.direction.clone());
-            self.apply_camera_transition(transform);
+    // Plays the script at 60 frames a second and returns the state after each frame.
+    fn play(walk: &mut Walk, player: &mut Player, collision: &CollisionMap, script: Vec<Vec<Frame>>) -> Vec<WalkState> {
+        let mut sounds = SoundQueue::default();
+        script.into_iter().flatten()
+            .map(|frame| {
+                walk.run(player, &input(frame.held, frame.pressed, collision, FRAME), &mut sounds);
+                walk.state
+            })
+            .collect()
+    }
+
+    // The states one letter a frame: I for Idle, T for Turning, W for Walking and B for Bumping.
+    fn letters(states: &[WalkState]) -> String {
+        states.iter().map(|state| match state {
+            WalkState::Idle => 'I',
+            WalkState::Turning(_) => 'T',
+            WalkState::Walking(_) => 'W',
+            WalkState::Bumping(_) => 'B',
+        }).collect()
+    }
+
+    fn expected(runs: &[(char, usize)]) -> String {
+        runs.iter().map(|(letter, frames)| letter.to_string().repeat(*frames)).collect()
+    }
+
+    #[test]
+    fn a_step_takes_the_same_time_whatever_the_frame_rate() {
+        let step_time = walk().step_time;
 
-            self.counter += 1;
+        for delta in &[1.0 / 30.0, 1.0 / 60.0, 1.0 / 144.0] {
+            let (cell, arrivals) = hold_east(*delta, 1.1);
 
-            if self.key_event.is_none() || self.counter == get_fps() - 1 {
-                self.handle();
-                self.apply_grid_transition(player);
-                self.counter = 0;
+            assert_eq!(cell, Vector2::new(4, 0), "at {} seconds a frame", delta);
+            for (i, arrival) in arrivals.iter().enumerate() {
+                // A cell is reached on the first frame that ends past its arrival time.
+                let expected = step_time * (i + 1) as f32;
+                assert!(*arrival >= expected - 1e-4 && *arrival < expected + delta + 1e-4,
+                    "cell {} reached at {} instead of {} at {} seconds a frame", i + 1, arrival, expected, delta);
             }
+        }
+    }
 
-            false
-        } else {
-            true
+    #[test]
+    fn a_tap_walks_one_cell_whatever_the_frame_rate() {
+        for delta in &[1.0 / 30.0, 1.0 / 60.0, 1.0 / 144.0] {
+            let (cell, arrivals) = hold_east(*delta, 0.05);
+
+            assert_eq!(cell, Vector2::new(1, 0), "at {} seconds a frame", delta);
+            assert_eq!(arrivals.len(), 1);
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn a_tap_in_another_direction_only_turns() {
+        let (mut walk, mut player) = (walk(), player());
+        let states = play(&mut walk, &mut player, &CollisionMap::new(), vec![tap(ActorDirection::North), idle(20)]);
+
+        // Turning lasts until the tap threshold has passed, 12 frames in, then ends in place.
+        assert_eq!(letters(&states), expected(&[('T', 12), ('I', 9)]));
+        assert_eq!(player.attrs.direction, ActorDirection::North);
+        assert_eq!(player.grid_pos, Vector2::new(0, 0));
+    }
+
+    #[test]
+    fn holding_another_direction_turns_then_walks() {
+        let (mut walk, mut player) = (walk(), player());
+        let states = play(&mut walk, &mut player, &CollisionMap::new(), vec![held(ActorDirection::North, 40)]);
+
+        // A step takes 20 frames after the 12 of the turn, and the next one follows on.
+        assert_eq!(letters(&states), expected(&[('T', 12), ('W', 28)]));
+        assert_eq!(player.attrs.direction, ActorDirection::North);
+        assert_eq!(player.grid_pos, Vector2::new(0, 1));
+    }
+
+    #[test]
+    fn a_direction_pressed_mid_step_is_taken_on_arrival() {
+        let (mut walk, mut player) = (walk(), player());
+        let states = play(&mut walk, &mut player, &CollisionMap::new(), vec![
+            tap(ActorDirection::East),
+            idle(5),
+            tap(ActorDirection::South),
+            idle(40),
+        ]);
+
+        // The step east is kept and the south one follows without a turn, carried by its
+        // momentum.
+        assert_eq!(letters(&states), expected(&[('W', 40), ('I', 7)]));
+        assert_eq!(player.attrs.direction, ActorDirection::South);
+        assert_eq!(player.grid_pos, Vector2::new(1, -1));
+    }
+
+    #[test]
+    fn walking_into_a_blocked_cell_bumps_in_place() {
+        let (mut walk, mut player) = (walk(), player());
+        let mut collision = CollisionMap::new();
+        collision.block((1, 0));
+
+        let mut sounds = SoundQueue::default();
+        let input = input(Some(ActorDirection::East), vec![ActorDirection::East], &collision, FRAME);
+        walk.run(&mut player, &input, &mut sounds);
+        assert!(matches!(walk.state, WalkState::Bumping(_)));
+        assert_eq!(sounds.drain(), vec![Sound::Bump]);
+
+        let states = play(&mut walk, &mut player, &collision, vec![held(ActorDirection::East, 60)]);
+        assert_eq!(letters(&states), expected(&[('B', 60)]));
+        assert_eq!(player.grid_pos, Vector2::new(0, 0));
+        assert_eq!(player.step_offset, Vector2::zeros());
+    }
+}
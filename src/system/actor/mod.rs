@@ -1,2 +1,4 @@
 pub mod player;
+pub mod npc;
+pub mod trainer;
 pub mod behaviour;
\ No newline at end of file
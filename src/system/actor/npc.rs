@@ -0,0 +1,86 @@
+use amethyst::{
+    core::{transform::Transform, math::Vector2, Time},
+    derive::SystemDesc,
+    ecs::prelude::{Join, Read, System, SystemData, Write, WriteStorage, Entities},
+    renderer::SpriteRender,
+    shrev::EventChannel,
+};
+
+use crate::entity::actor::{world_position, CELL_SIZE, ActorAction, StepEvent, npc::Npc};
+
+// Walks npcs through their pending steps, one grid cell at a time.
+#[derive(SystemDesc)]
+pub struct NpcSystem {
+    speed: f32,
+    // Seconds taken by one step.
+    step_time: f32,
+}
+
+impl NpcSystem {
+    pub fn new() -> Self {
+        let mut system = NpcSystem {
+            speed: 3.0,
+            step_time: 0.0,
+        };
+
+        system.step_time = 1.0 / system.speed;
+        system
+    }
+
+    fn step(&self, npc: &mut Npc, transform: &mut Transform, delta: f32) -> bool {
+        let (dx, dy) = npc.attrs.direction.to_delta();
+        npc.elapsed += delta;
+        let progress = (npc.elapsed / self.step_time).min(1.0);
+
+        // Placed along the way from its cell, so that rounding errors do not add up over long walks.
+        let (x, y) = world_position(npc.grid_pos);
+        transform.set_translation_x(x + dx as f32 * CELL_SIZE * progress);
+        transform.set_translation_y(y + dy as f32 * CELL_SIZE * progress);
+
+        // Sprite transition for walks:- Walk_i -> Stand
+        // 0..T/2 :- Walk_i
+        // T/2..T :- Stand
+        npc.attrs.action = if progress < 0.5 {
+            if npc.steps % 2 == 0 { ActorAction::Walk1 } else { ActorAction::Walk2 }
+        } else {
+            ActorAction::Stand
+        };
+
+        if progress < 1.0 {
+            return false;
+        }
+
+        npc.elapsed = 0.0;
+        npc.steps -= 1;
+        npc.grid_pos += Vector2::new(dx, dy);
+        npc.attrs.action = ActorAction::Stand;
+
+        true
+    }
+}
+
+impl<'s> System<'s> for NpcSystem {
+    type SystemData = (
+        WriteStorage<'s, Npc>,
+        WriteStorage<'s, SpriteRender>,
+        WriteStorage<'s, Transform>,
+        Write<'s, EventChannel<StepEvent>>,
+        Read<'s, Time>,
+        Entities<'s>
+    );
+
+    fn run(&mut self, (mut npcs, mut sprites, mut transforms, mut step_events, time, entities): Self::SystemData) {
+        let delta = time.delta_seconds();
+
+        for (npc, sprite, transform, entity) in (&mut npcs, &mut sprites, &mut transforms, &*entities).join() {
+            if npc.steps > 0 && self.step(npc, transform, delta) {
+                step_events.single_write(StepEvent {
+                    entity,
+                    grid_pos: npc.grid_pos,
+                });
+            }
+
+            sprite.sprite_number = npc.attrs.to_sprite_index();
+        }
+    }
+}
@@ -1,30 +1,56 @@
 use amethyst::{
-    core::transform::Transform,
-    derive::SystemDesc,
-    ecs::prelude::{Join, Read, System, SystemData, Write, WriteStorage},
-    input::{InputHandler, StringBindings},
-    renderer::{SpriteRender, camera::Camera},
-    core::math::Vector3
+    core::{transform::Transform, Time},
+    ecs::prelude::{Join, Read, ReadStorage, System, SystemData, World, Write, WriteStorage, Entities},
+    input::{InputEvent, InputHandler, StringBindings},
+    renderer::SpriteRender,
+    shrev::{EventChannel, ReaderId},
 };
 
-use crate::entity::actor::player::Player;
+use crate::audio::SoundQueue;
+use crate::entity::actor::{world_position, ActorDirection, StepEvent, npc::Npc, player::Player};
+use crate::map::{collision::CollisionMap, stream::MapStream};
 use crate::state::Game;
 
+// The actions that move the player, in the order they win when several are held.
+const DIRECTIONS: [(&str, ActorDirection); 4] = [
+    ("left", ActorDirection::West),
+    ("right", ActorDirection::East),
+    ("up", ActorDirection::North),
+    ("down", ActorDirection::South),
+];
+
+// The input of one frame, as seen by the player behaviours.
+pub struct PlayerInput<'a> {
+    // The direction held down at the end of the frame.
+    pub held: Option<ActorDirection>,
+    // Directions pressed during the frame, oldest first. A tap shows up here even when it was
+    // released before the end of the frame.
+    pub pressed: Vec<ActorDirection>,
+    // The cells around the player that it can't step onto, two cells out, so that a step
+    // chained on within the frame is checked too.
+    pub collision: &'a CollisionMap,
+    // Seconds since the last frame.
+    pub delta: f32,
+}
+
 // The run() function returns a boolean value stating whether the behaviour corresponded to the input.
 pub trait PlayerBehaviour {
-    fn run(&mut self, player: &mut Player, transform: &mut Transform, input: &Read<InputHandler<StringBindings>>) -> bool;
+    fn run(&mut self, player: &mut Player, input: &PlayerInput, sounds: &mut SoundQueue) -> bool;
 }
 
 
-#[derive(SystemDesc)]
 pub struct PlayerSystem {
-    behaviours: Vec<Box<dyn PlayerBehaviour + Send + Sync>>
+    behaviours: Vec<Box<dyn PlayerBehaviour + Send + Sync>>,
+    reader: ReaderId<InputEvent<StringBindings>>,
 }
 
 impl PlayerSystem {
-    pub fn new() -> Self {
+    pub fn new(world: &mut World) -> Self {
+        <Self as System<'_>>::SystemData::setup(world);
+
         PlayerSystem {
-            behaviours: Vec::new()
+            behaviours: Vec::new(),
+            reader: world.fetch_mut::<EventChannel<InputEvent<StringBindings>>>().register_reader(),
         }
     }
 
@@ -35,6 +61,30 @@ impl PlayerSystem {
     fn draw(&mut self, player: &Player, sprite_render: &mut SpriteRender) {
         sprite_render.sprite_number = player.attrs.to_sprite_index();
     }
+
+    // A cell is blocked outside of the streamed maps, on solid tiles, and where an npc stands.
+    fn collision_map(player: &Player, stream: &MapStream, npcs: &ReadStorage<Npc>) -> CollisionMap {
+        let mut collision = CollisionMap::new();
+        for dx in -2..=2i32 {
+            for dy in -2..=2i32 {
+                if dx.abs() + dy.abs() > 2 {
+                    continue;
+                }
+
+                let cell = (player.grid_pos[0] + dx, player.grid_pos[1] + dy);
+                if stream.owner(cell).is_none() ||
+                    stream.any_at(cell, |class| class.is_solid()) ||
+                    npcs.join().any(|npc| (npc.grid_pos[0], npc.grid_pos[1]) == cell) {
+                    collision.block(cell);
+                }
+            }
+        }
+        collision
+    }
+
+    fn direction_of(action: &str) -> Option<ActorDirection> {
+        DIRECTIONS.iter().find(|(name, _)| *name == action).map(|(_, direction)| *direction)
+    }
 }
 
 impl<'s> System<'s> for PlayerSystem {
@@ -42,33 +92,66 @@ impl<'s> System<'s> for PlayerSystem {
         WriteStorage<'s, Player>,
         WriteStorage<'s, SpriteRender>,
         WriteStorage<'s, Transform>,
-        WriteStorage<'s, Camera>,
         Read<'s, InputHandler<StringBindings>>,
-        Write<'s, Game>
+        Read<'s, EventChannel<InputEvent<StringBindings>>>,
+        Read<'s, Game>,
+        Read<'s, Time>,
+        Read<'s, MapStream>,
+        ReadStorage<'s, Npc>,
+        Write<'s, EventChannel<StepEvent>>,
+        Write<'s, SoundQueue>,
+        Entities<'s>
     );
 
-    fn run(&mut self, (mut players, mut sprites, mut transforms, mut cameras, input, mut _game): Self::SystemData) {
-        let mut translation: Vector3<f32> = Vector3::new(0.0, 0.0, 2.0);
+    fn run(&mut self, (mut players, mut sprites, mut transforms, input, input_events, game, time, stream, npcs, mut step_events, mut sounds, entities): Self::SystemData) {
+        // Presses are read even while paused, so that they do not move the player afterwards.
+        let pressed: Vec<ActorDirection> = input_events.read(&mut self.reader)
+            .filter_map(|event| match event {
+                InputEvent::ActionPressed(action) => PlayerSystem::direction_of(action),
+                _ => None,
+            })
+            .collect();
 
-        for (transform, _camera) in (&mut transforms, &mut cameras).join() {
-            translation = transform.translation().clone();
-            for (player, sprite) in (&mut players, &mut sprites).join() {
-                for behaviour in &mut self.behaviours {
+        if game.paused {
+            return;
+        }
 
-                    // If the input was handled by a behaviour, skip all other behaviours.
-                    // Only one behaviour is allowed to run at a time.
-                    if behaviour.run(player, transform, &input) {
-                        break;
-                    }
-                }
+        let held = DIRECTIONS.iter()
+            .find(|(action, _)| input.action_is_down(*action).unwrap_or(false))
+            .map(|(_, direction)| *direction);
 
-                self.draw(player, sprite);
+        for (player, sprite, transform, entity) in (&mut players, &mut sprites, &mut transforms, &*entities).join() {
+            let grid_pos = player.grid_pos;
+            let collision = PlayerSystem::collision_map(player, &stream, &npcs);
+            let input = PlayerInput {
+                held,
+                pressed: pressed.clone(),
+                collision: &collision,
+                delta: time.delta_seconds(),
+            };
+
+            for behaviour in &mut self.behaviours {
+
+                // If the input was handled by a behaviour, skip all other behaviours.
+                // Only one behaviour is allowed to run at a time.
+                if behaviour.run(player, &input, &mut sounds) {
+                    break;
+                }
             }
-        }
 
-        for (_player, player_transform) in (&mut players, &mut transforms).join() {
-            player_transform.set_translation_x(translation[0] + 320.0);
-            player_transform.set_translation_y(translation[1] - 320.0);
+            self.draw(player, sprite);
+
+            // The player is drawn on its cell, moved along by the step in progress.
+            let (x, y) = world_position(player.grid_pos);
+            transform.set_translation_x(x + player.step_offset[0]);
+            transform.set_translation_y(y + player.step_offset[1]);
+
+            if player.grid_pos != grid_pos {
+                step_events.single_write(StepEvent {
+                    entity,
+                    grid_pos: player.grid_pos,
+                });
+            }
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,81 @@
+use amethyst::{
+    ecs::prelude::{Join, Read, ReadStorage, System, SystemData, World, Write, Entities},
+    shrev::{EventChannel, ReaderId},
+};
+
+use crate::entity::actor::{StepEvent, npc::Npc, player::Player};
+use crate::map::{collision::CollisionMap, stream::MapStream};
+use crate::state::{Game, Trigger};
+use crate::story::Story;
+
+// Checks whether the player stepped into the line of sight of an undefeated trainer.
+pub struct TrainerSystem {
+    reader: ReaderId<StepEvent>,
+}
+
+impl TrainerSystem {
+    pub fn new(world: &mut World) -> Self {
+        <Self as System<'_>>::SystemData::setup(world);
+        let reader = world.fetch_mut::<EventChannel<StepEvent>>().register_reader();
+
+        TrainerSystem {
+            reader,
+        }
+    }
+
+    // Only the cells a trainer looks over are checked: solid tiles and other npcs block its view.
+    fn sight_map(origin: (i32, i32), delta: (i32, i32), range: u32, stream: &MapStream, npcs: &ReadStorage<Npc>) -> CollisionMap {
+        let mut collision = CollisionMap::new();
+
+        for distance in 1..=range as i32 {
+            let cell = (origin.0 + delta.0 * distance, origin.1 + delta.1 * distance);
+            if stream.any_at(cell, |class| class.is_solid()) ||
+                npcs.join().any(|npc| (npc.grid_pos[0], npc.grid_pos[1]) == cell) {
+                collision.block(cell);
+            }
+        }
+
+        collision
+    }
+}
+
+impl<'s> System<'s> for TrainerSystem {
+    type SystemData = (
+        Read<'s, EventChannel<StepEvent>>,
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, Npc>,
+        Read<'s, MapStream>,
+        Read<'s, Story>,
+        Write<'s, Game>,
+        Entities<'s>
+    );
+
+    fn run(&mut self, (step_events, players, npcs, stream, story, mut game, entities): Self::SystemData) {
+        for event in step_events.read(&mut self.reader) {
+            if players.get(event.entity).is_none() || game.get_trigger().is_some() {
+                continue;
+            }
+
+            let target = (event.grid_pos[0], event.grid_pos[1]);
+
+            for (npc, entity) in (&npcs, &*entities).join() {
+                let trainer = match &npc.trainer {
+                    Some(trainer) => trainer,
+                    None => continue
+                };
+
+                if npc.sight_range == 0 || story.is_trainer_defeated(trainer) {
+                    continue;
+                }
+
+                let origin = (npc.grid_pos[0], npc.grid_pos[1]);
+                let delta = npc.attrs.direction.to_delta();
+                let collision = TrainerSystem::sight_map(origin, delta, npc.sight_range, &stream, &npcs);
+                if collision.line_of_sight(origin, delta, npc.sight_range, target).is_some() {
+                    game.set_trigger(Trigger::TrainerSpotted(entity));
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    audio::{output::Output, AudioSink, Source, SourceHandle, WavFormat},
+    core::Time,
+    ecs::prelude::{Read, ReadExpect, System, Write},
+};
+
+use crate::audio::{music::Music, output::{AudioOutput, DeviceOutput, MutedOutput}, SoundQueue};
+use crate::utils::settings::Settings;
+
+// Fades the music and plays the queued sounds, at the volumes from the settings. Audio files
+// are loaded the first time they are needed.
+pub struct AudioSystem {
+    sources: HashMap<String, SourceHandle>,
+    sinks: Vec<(String, AudioSink)>,
+}
+
+impl AudioSystem {
+    pub fn new() -> Self {
+        AudioSystem {
+            sources: HashMap::new(),
+            sinks: Vec::new(),
+        }
+    }
+
+    fn handle(&mut self, path: String, loader: &Loader, storage: &AssetStorage<Source>) -> SourceHandle {
+        self.sources.entry(path.clone())
+            .or_insert_with(|| loader.load(path, WavFormat, (), storage))
+            .clone()
+    }
+}
+
+impl<'s> System<'s> for AudioSystem {
+    type SystemData = (
+        Write<'s, Music>,
+        Write<'s, SoundQueue>,
+        Read<'s, Settings>,
+        Read<'s, Time>,
+        ReadExpect<'s, Loader>,
+        Read<'s, AssetStorage<Source>>,
+        Option<Read<'s, Output>>,
+    );
+
+    fn run(&mut self, (mut music, mut sounds, settings, time, loader, storage, output): Self::SystemData) {
+        music.advance(time.delta_seconds());
+
+        let channels: Vec<(String, SourceHandle, f32)> = music.channels().into_iter()
+            .map(|channel| {
+                let handle = self.handle(format!("audio/music/{}.wav", channel.track), &loader, &storage);
+                (channel.track, handle, channel.level * settings.music_level())
+            })
+            .collect();
+        let sounds: Vec<SourceHandle> = sounds.drain().into_iter()
+            .map(|sound| self.handle(format!("audio/sound/{}.wav", sound.name()), &loader, &storage))
+            .collect();
+
+        let mut muted = MutedOutput;
+        let mut device;
+        let out: &mut dyn AudioOutput = match &output {
+            Some(output) => {
+                device = DeviceOutput { output, sinks: &mut self.sinks };
+                &mut device
+            }
+            None => &mut muted,
+        };
+
+        // Tracks that are still loading start once they are in.
+        let tracks: Vec<(&str, &Source, f32)> = channels.iter()
+            .filter_map(|(track, handle, volume)| storage.get(handle).map(|source| (track.as_str(), source, *volume)))
+            .collect();
+        out.play_music(&tracks);
+
+        for source in sounds.iter().filter_map(|handle| storage.get(handle)) {
+            out.play_sound(source, settings.sound_level());
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use amethyst::{
+    core::Time,
+    derive::SystemDesc,
+    ecs::prelude::{Join, Read, ReadStorage, System, SystemData, Write, WriteStorage, Entities},
+    ui::UiTransform,
+};
+
+use crate::entity::battle::ball::BallShake;
+use crate::entity::screen::VirtualLayout;
+use crate::state::{Game, Trigger};
+
+#[derive(SystemDesc)]
+pub struct BallShakeSystem;
+
+impl BallShakeSystem {
+    pub fn new() -> Self {
+        BallShakeSystem
+    }
+}
+
+impl<'s> System<'s> for BallShakeSystem {
+    type SystemData = (
+        WriteStorage<'s, BallShake>,
+        ReadStorage<'s, UiTransform>,
+        WriteStorage<'s, VirtualLayout>,
+        Write<'s, Game>,
+        Read<'s, Time>,
+        Entities<'s>
+    );
+
+    fn run(&mut self, (mut shakes, transforms, mut layouts, mut game, time, entities): Self::SystemData) {
+        for (shake, entity) in (&mut shakes, &*entities).join() {
+            if shake.is_finished() {
+                continue;
+            }
+
+            shake.elapsed += time.delta_seconds();
+            if let Some(layout) = VirtualLayout::get_mut(&mut layouts, &transforms, entity) {
+                layout.x = shake.x();
+            }
+
+            if shake.is_finished() {
+                game.kill_entity(entity);
+                game.set_trigger(Trigger::BallShakeEnd);
+            }
+        }
+    }
+}
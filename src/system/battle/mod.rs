@@ -0,0 +1,2 @@
+pub mod transition;
+pub mod ball;
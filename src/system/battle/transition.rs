@@ -0,0 +1,44 @@
+use amethyst::{
+    core::Time,
+    derive::SystemDesc,
+    ecs::prelude::{Join, Read, System, SystemData, Write, WriteStorage, Entities},
+    ui::UiImage,
+};
+
+use crate::entity::battle::transition::BattleTransition;
+use crate::state::{Game, Trigger};
+
+#[derive(SystemDesc)]
+pub struct BattleTransitionSystem;
+
+impl BattleTransitionSystem {
+    pub fn new() -> Self {
+        BattleTransitionSystem
+    }
+}
+
+impl<'s> System<'s> for BattleTransitionSystem {
+    type SystemData = (
+        WriteStorage<'s, BattleTransition>,
+        WriteStorage<'s, UiImage>,
+        Write<'s, Game>,
+        Read<'s, Time>,
+        Entities<'s>
+    );
+
+    fn run(&mut self, (mut transitions, mut images, mut game, time, entities): Self::SystemData) {
+        for (transition, image, entity) in (&mut transitions, &mut images, &*entities).join() {
+            if transition.is_finished() {
+                continue;
+            }
+
+            transition.elapsed += time.delta_seconds();
+            *image = UiImage::SolidColor([0., 0., 0., transition.alpha()]);
+
+            if transition.is_finished() {
+                game.kill_entity(entity);
+                game.set_trigger(Trigger::BattleTransitionEnd);
+            }
+        }
+    }
+}
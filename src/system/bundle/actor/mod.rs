@@ -1,4 +1,6 @@
 mod player;
+mod npc;
+mod trainer;
 
 
 use amethyst::{
@@ -8,6 +10,8 @@ use amethyst::{
 };
 
 use crate::system::bundle::actor::player::PlayerBundle;
+use crate::system::bundle::actor::npc::NpcBundle;
+use crate::system::bundle::actor::trainer::TrainerBundle;
 
 pub struct ActorBundle;
 
@@ -16,6 +20,12 @@ impl<'a, 'b> SystemBundle<'a, 'b> for ActorBundle {
         let player_bundle = PlayerBundle;
         player_bundle.build(world, dispatcher)?;
 
+        let npc_bundle = NpcBundle;
+        npc_bundle.build(world, dispatcher)?;
+
+        let trainer_bundle = TrainerBundle;
+        trainer_bundle.build(world, dispatcher)?;
+
         Ok(())
     }
-}
\ No newline at end of file
+}
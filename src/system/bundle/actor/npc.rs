@@ -0,0 +1,18 @@
+use amethyst::{
+    core::bundle::SystemBundle,
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+
+use crate::system::actor::npc::NpcSystem;
+
+pub struct NpcBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for NpcBundle {
+    fn build(self, _world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        let system = NpcSystem::new();
+
+        dispatcher.add(system, "npc_system", &[]);
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+use amethyst::{
+    core::bundle::SystemBundle,
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+
+use crate::system::actor::trainer::TrainerSystem;
+
+pub struct TrainerBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for TrainerBundle {
+    fn build(self, world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        let system = TrainerSystem::new(world);
+
+        dispatcher.add(system, "trainer_system", &["player_system", "npc_system"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+pub mod music;
+
+
+use amethyst::{
+    core::bundle::SystemBundle,
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+use crate::system::bundle::audio::music::MusicBundle;
+
+pub struct SoundBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for SoundBundle {
+    fn build(self, world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        let music_bundle = MusicBundle;
+        music_bundle.build(world, dispatcher)?;
+
+        Ok(())
+    }
+}
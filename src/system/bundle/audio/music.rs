@@ -0,0 +1,19 @@
+use amethyst::{
+    core::bundle::SystemBundle,
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+
+use crate::system::audio::music::AudioSystem;
+
+pub struct MusicBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for MusicBundle {
+    fn build(self, _world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        let system = AudioSystem::new();
+
+        // After everything that queues sounds.
+        dispatcher.add(system, "audio_system", &["player_system", "talk_dialog_system", "list_widget_system"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+use amethyst::{
+    core::bundle::SystemBundle,
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+
+use crate::system::battle::ball::BallShakeSystem;
+
+pub struct BallShakeBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for BallShakeBundle {
+    fn build(self, _world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        let system = BallShakeSystem::new();
+
+        dispatcher.add(system, "ball_shake_system", &[]);
+        Ok(())
+    }
+}
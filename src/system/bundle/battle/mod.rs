@@ -0,0 +1,23 @@
+pub mod transition;
+pub mod ball;
+
+use amethyst::{
+    core::bundle::SystemBundle,
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+use crate::system::bundle::battle::{ball::BallShakeBundle, transition::BattleTransitionBundle};
+
+pub struct BattleBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for BattleBundle {
+    fn build(self, world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        let transition_bundle = BattleTransitionBundle;
+        transition_bundle.build(world, dispatcher)?;
+
+        let ball_bundle = BallShakeBundle;
+        ball_bundle.build(world, dispatcher)?;
+
+        Ok(())
+    }
+}
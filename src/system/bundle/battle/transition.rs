@@ -0,0 +1,18 @@
+use amethyst::{
+    core::bundle::SystemBundle,
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+
+use crate::system::battle::transition::BattleTransitionSystem;
+
+pub struct BattleTransitionBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for BattleTransitionBundle {
+    fn build(self, _world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        let system = BattleTransitionSystem::new();
+
+        dispatcher.add(system, "battle_transition_system", &[]);
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+use amethyst::{
+    core::bundle::SystemBundle,
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+
+use crate::system::evolution::animation::EvolutionAnimationSystem;
+
+pub struct EvolutionAnimationBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for EvolutionAnimationBundle {
+    fn build(self, _world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        let system = EvolutionAnimationSystem::new();
+
+        dispatcher.add(system, "evolution_animation_system", &[]);
+        Ok(())
+    }
+}
@@ -0,0 +1,19 @@
+pub mod animation;
+
+use amethyst::{
+    core::bundle::SystemBundle,
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+use crate::system::bundle::evolution::animation::EvolutionAnimationBundle;
+
+pub struct EvolutionBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for EvolutionBundle {
+    fn build(self, world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        let animation_bundle = EvolutionAnimationBundle;
+        animation_bundle.build(world, dispatcher)?;
+
+        Ok(())
+    }
+}
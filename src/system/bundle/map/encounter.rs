@@ -0,0 +1,18 @@
+use amethyst::{
+    core::bundle::SystemBundle,
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+
+use crate::system::map::encounter::EncounterSystem;
+
+pub struct EncounterBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for EncounterBundle {
+    fn build(self, world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        let system = EncounterSystem::new(world);
+
+        dispatcher.add(system, "encounter_system", &["player_system", "trainer_system", "warp_system"]);
+        Ok(())
+    }
+}
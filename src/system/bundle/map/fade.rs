@@ -0,0 +1,18 @@
+use amethyst::{
+    core::bundle::SystemBundle,
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+
+use crate::system::map::fade::MapFadeSystem;
+
+pub struct MapFadeBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for MapFadeBundle {
+    fn build(self, _world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        let system = MapFadeSystem::new();
+
+        dispatcher.add(system, "map_fade_system", &[]);
+        Ok(())
+    }
+}
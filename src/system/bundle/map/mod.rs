@@ -0,0 +1,35 @@
+pub mod encounter;
+pub mod warp;
+pub mod fade;
+pub mod tint;
+pub mod weather;
+
+use amethyst::{
+    core::bundle::SystemBundle,
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+use crate::system::bundle::map::{encounter::EncounterBundle, fade::MapFadeBundle, tint::DayTintBundle, warp::WarpBundle, weather::WeatherBundle};
+
+pub struct MapBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for MapBundle {
+    fn build(self, world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        let warp_bundle = WarpBundle;
+        warp_bundle.build(world, dispatcher)?;
+
+        let encounter_bundle = EncounterBundle;
+        encounter_bundle.build(world, dispatcher)?;
+
+        let fade_bundle = MapFadeBundle;
+        fade_bundle.build(world, dispatcher)?;
+
+        let tint_bundle = DayTintBundle;
+        tint_bundle.build(world, dispatcher)?;
+
+        let weather_bundle = WeatherBundle;
+        weather_bundle.build(world, dispatcher)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+use amethyst::{
+    core::bundle::SystemBundle,
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+
+use crate::system::map::tint::DayTintSystem;
+
+pub struct DayTintBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for DayTintBundle {
+    fn build(self, _world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        let system = DayTintSystem::new();
+
+        dispatcher.add(system, "day_tint_system", &["clock_system"]);
+        Ok(())
+    }
+}
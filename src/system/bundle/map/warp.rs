@@ -0,0 +1,19 @@
+use amethyst::{
+    core::bundle::SystemBundle,
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+
+use crate::system::map::warp::WarpSystem;
+
+pub struct WarpBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for WarpBundle {
+    fn build(self, world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        let system = WarpSystem::new(world);
+
+        // Runs before encounters so that a warp is not taken over by a wild battle.
+        dispatcher.add(system, "warp_system", &["player_system", "trainer_system"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+use amethyst::{
+    core::bundle::SystemBundle,
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+
+use crate::system::map::weather::WeatherSystem;
+
+pub struct WeatherBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for WeatherBundle {
+    fn build(self, _world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        let system = WeatherSystem::new();
+
+        dispatcher.add(system, "weather_system", &[]);
+        Ok(())
+    }
+}
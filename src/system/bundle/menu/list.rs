@@ -0,0 +1,18 @@
+use amethyst::{
+    core::bundle::SystemBundle,
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+
+use crate::system::menu::list::ListWidgetSystem;
+
+pub struct ListWidgetBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for ListWidgetBundle {
+    fn build(self, world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        let system = ListWidgetSystem::new(world);
+
+        dispatcher.add(system, "list_widget_system", &["input_system"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+pub mod list;
+
+
+use amethyst::{
+    core::bundle::SystemBundle,
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+use crate::system::bundle::menu::list::ListWidgetBundle;
+
+pub struct MenuBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for MenuBundle {
+    fn build(self, world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        let list_widget_bundle = ListWidgetBundle;
+        list_widget_bundle.build(world, dispatcher)?;
+
+        Ok(())
+    }
+}
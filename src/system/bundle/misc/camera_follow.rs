@@ -0,0 +1,18 @@
+use amethyst::{
+    core::bundle::SystemBundle,
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+
+use crate::system::misc::camera_follow::CameraFollowSystem;
+
+pub struct CameraFollowBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for CameraFollowBundle {
+    fn build(self, _world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        let system = CameraFollowSystem::new();
+
+        dispatcher.add(system, "camera_follow_system", &["player_system", "npc_system"]);
+        Ok(())
+    }
+}
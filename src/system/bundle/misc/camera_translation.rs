@@ -12,7 +12,7 @@ impl<'a, 'b> SystemBundle<'a, 'b> for CameraTranslationBundle  {
     fn build(self, _world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
         let system = CameraTranslationSystem::new();
 
-        dispatcher.add(system, "camera_translation_system", &[]);
+        dispatcher.add(system, "camera_translation_system", &["camera_follow_system"]);
         Ok(())
     }
 }
\ No newline at end of file
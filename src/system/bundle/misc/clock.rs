@@ -0,0 +1,21 @@
+use amethyst::{
+    core::bundle::SystemBundle,
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+
+use crate::clock::Clock;
+use crate::system::misc::clock::ClockSystem;
+
+pub struct ClockBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for ClockBundle {
+    fn build(self, world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        world.insert(Clock::load());
+
+        let system = ClockSystem::new();
+
+        dispatcher.add(system, "clock_system", &[]);
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
 pub mod camera_translation;
+pub mod camera_follow;
+pub mod viewport;
+pub mod clock;
 
 use amethyst::{
     core::bundle::SystemBundle,
@@ -6,15 +9,26 @@ use amethyst::{
     error::Error,
 };
 
-use crate::system::bundle::misc::camera_translation::CameraTranslationBundle;
+use crate::system::bundle::misc::{
+    camera_follow::CameraFollowBundle, camera_translation::CameraTranslationBundle, clock::ClockBundle, viewport::ViewportBundle,
+};
 
 pub struct MiscBundle;
 
 impl<'a, 'b> SystemBundle<'a, 'b> for MiscBundle {
     fn build(self, world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        let camera_follow_bundle = CameraFollowBundle;
+        camera_follow_bundle.build(world, dispatcher)?;
+
         let camera_trans_bundle = CameraTranslationBundle;
         camera_trans_bundle.build(world, dispatcher)?;
 
+        let viewport_bundle = ViewportBundle;
+        viewport_bundle.build(world, dispatcher)?;
+
+        let clock_bundle = ClockBundle;
+        clock_bundle.build(world, dispatcher)?;
+
         Ok(())
     }
 }
@@ -0,0 +1,21 @@
+use amethyst::{
+    core::bundle::SystemBundle,
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+
+use crate::system::misc::viewport::ViewportSystem;
+use crate::utils::screen::VirtualScreen;
+
+pub struct ViewportBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for ViewportBundle {
+    fn build(self, world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        world.insert(VirtualScreen::load());
+
+        let system = ViewportSystem::new();
+
+        dispatcher.add(system, "viewport_system", &["camera_translation_system"]);
+        Ok(())
+    }
+}
@@ -2,6 +2,11 @@ mod actor;
 mod tile;
 mod dialog;
 mod misc;
+mod battle;
+mod map;
+mod menu;
+mod evolution;
+mod audio;
 
 use amethyst::{
     core::bundle::SystemBundle,
@@ -14,6 +19,11 @@ use crate::system::bundle::actor::ActorBundle;
 use crate::system::bundle::tile::TileBundle;
 use crate::system::bundle::dialog::DialogBundle;
 use crate::system::bundle::misc::MiscBundle;
+use crate::system::bundle::battle::BattleBundle;
+use crate::system::bundle::map::MapBundle;
+use crate::system::bundle::menu::MenuBundle;
+use crate::system::bundle::evolution::EvolutionBundle;
+use crate::system::bundle::audio::SoundBundle;
 
 pub struct GameBundle;
 
@@ -33,6 +43,22 @@ impl<'a, 'b> SystemBundle<'a, 'b> for GameBundle {
         let misc_bundle = MiscBundle;
         misc_bundle.build(world, dispatcher)?;
 
+        let battle_bundle = BattleBundle;
+        battle_bundle.build(world, dispatcher)?;
+
+        let map_bundle = MapBundle;
+        map_bundle.build(world, dispatcher)?;
+
+        let menu_bundle = MenuBundle;
+        menu_bundle.build(world, dispatcher)?;
+
+        let evolution_bundle = EvolutionBundle;
+        evolution_bundle.build(world, dispatcher)?;
+
+        // Last, as it runs after the systems that queue sounds.
+        let sound_bundle = SoundBundle;
+        sound_bundle.build(world, dispatcher)?;
+
 
         Ok(())
     }
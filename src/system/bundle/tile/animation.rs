@@ -0,0 +1,18 @@
+use amethyst::{
+    core::bundle::SystemBundle,
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+
+use crate::system::tile::animation::TileAnimationSystem;
+
+pub struct TileAnimationBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for TileAnimationBundle {
+    fn build(self, world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        let system = TileAnimationSystem::new(world);
+
+        dispatcher.add(system, "tile_animation_system", &["player_system"]);
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 pub mod static_tile;
+pub mod animation;
 
 use amethyst::{
     core::bundle::SystemBundle,
@@ -6,11 +7,15 @@ use amethyst::{
     error::Error,
 };
 use crate::system::bundle::tile::static_tile::StaticTileBundle;
+use crate::system::bundle::tile::animation::TileAnimationBundle;
 
 pub struct TileBundle;
 
 impl<'a, 'b> SystemBundle<'a, 'b> for TileBundle {
     fn build(self, world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
+        let animation_bundle = TileAnimationBundle;
+        animation_bundle.build(world, dispatcher)?;
+
         let static_tile_bundle = StaticTileBundle;
         static_tile_bundle.build(world, dispatcher)?;
 
@@ -12,7 +12,7 @@ impl<'a, 'b> SystemBundle<'a, 'b> for StaticTileBundle {
     fn build(self, _world: &mut World, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
         let system = StaticTileSystem::new();
 
-        dispatcher.add(system, "static_tile_system", &[]);
+        dispatcher.add(system, "static_tile_system", &["tile_animation_system"]);
         Ok(())
     }
 }
\ No newline at end of file
@@ -1,44 +1,34 @@
 use amethyst::{
-    core::transform::Transform,
+    core::{transform::Transform, Time},
     derive::SystemDesc,
-    ecs::prelude::{Join, Read, Write, System, SystemData, WriteStorage, Entities},
+    ecs::prelude::{Join, Read, ReadExpect, Write, System, SystemData, WriteStorage, Entities},
     input::{InputHandler, StringBindings},
     ui::{UiText},
 };
-use crate::entity::dialog::talk_dialog::TalkDialog;
-use crate::utils::resolve;
+use crate::audio::{Sound, SoundQueue};
+use crate::entity::dialog::talk_dialog::{TalkDialog, BOX_CENTER_Y};
+use crate::utils::{screen::VirtualScreen, settings::Settings};
 use crate::state::{Game, Trigger};
 
 #[derive(SystemDesc)]
 pub struct TalkDialogSystem {
-    counter: usize,
+    // Seconds since the last character was revealed.
+    elapsed: f32,
     speed: f32,
-    capframes: f32,
     hold: bool,
 }
 
 impl TalkDialogSystem {
     pub fn new() -> Self {
-        let mut system = TalkDialogSystem {
-            counter: 0,
+        TalkDialogSystem {
+            elapsed: 0.0,
             speed: 15.0,
-            capframes: 0.0,
             hold: false,
-        };
-
-        system.capframes = resolve::get_fps() as f32 / system.speed;
-        system
+        }
     }
 
     fn set_text(&self, ui_text: &mut UiText, dialog: &TalkDialog) {
-        ui_text.text = dialog.text[dialog.index.clone()][0..dialog.char_index.clone() + 1].to_string();
-    }
-
-    fn destroy_mesh(&self, entities: &Entities, dialog: &mut TalkDialog) {
-        if dialog.mesh.is_some() {
-            entities.delete(dialog.mesh.unwrap());
-            dialog.mesh = None;
-        }
+        ui_text.text = dialog.text[dialog.index][0..dialog.char_index + 1].to_string();
     }
 }
 
@@ -48,46 +38,54 @@ impl<'s> System<'s> for TalkDialogSystem {
         WriteStorage<'s, UiText>,
         WriteStorage<'s, Transform>,
         Read<'s, InputHandler<StringBindings>>,
+        Read<'s, Settings>,
+        Read<'s, Time>,
         Write<'s, Game>,
+        Write<'s, SoundQueue>,
+        ReadExpect<'s, VirtualScreen>,
         Entities<'s>
     );
 
-    fn run(&mut self, (mut dialogs, mut ui_texts, mut transforms, input, mut game, mut entities): Self::SystemData) {
+    fn run(&mut self, (mut dialogs, mut ui_texts, mut transforms, input, settings, time, mut game, mut sounds, screen, entities): Self::SystemData) {
+        self.speed = settings.text_speed.chars_per_second();
         let should_continue = input.action_is_down("continue").unwrap_or(false) ||
                                     input.action_is_down("cancel").unwrap_or(false);
 
 
-        if should_continue {
-            self.capframes = resolve::get_fps() as f32 / (2.0 * self.speed);
+        // Seconds between two characters. Holding a button reveals them twice as fast.
+        let interval = if should_continue {
+            1.0 / (2.0 * self.speed)
         } else {
-            self.capframes = resolve::get_fps() as f32 / self.speed;
-        }
+            1.0 / self.speed
+        };
 
         for (dialog, ui_text, entity) in (&mut dialogs, &mut ui_texts, &*entities).join() {
-            let is_line_end = dialog.char_index == dialog.text[dialog.index.clone()].len() - 1;
+            let is_line_end = dialog.char_index == dialog.text[dialog.index].len() - 1;
 
             if should_continue && is_line_end {
                 self.hold = true;
             }
 
-            if self.counter >= self.capframes as usize {
-                if dialog.char_index < dialog.text[dialog.index.clone()].len() - 1 {
+            // Slow frames reveal several characters at once, so that text comes in at the same
+            // pace whatever the frame rate.
+            self.elapsed += time.delta_seconds();
+            while self.elapsed >= interval {
+                if dialog.char_index < dialog.text[dialog.index].len() - 1 {
                     dialog.char_index += 1;
                 }
-                self.counter = 0;
+                self.elapsed -= interval;
             }
 
-            self.counter += 1;
-
             if is_line_end && self.hold && !should_continue {
                 self.hold = false;
 
                 dialog.char_index = 0;
+                sounds.play(Sound::Advance);
                 if dialog.index < dialog.text.len() - 1 {
                     dialog.index += 1;
                 } else {
-                    if dialog.mesh.is_some() {
-                        game.kill_entity(dialog.mesh.unwrap());
+                    if let Some(mesh) = dialog.mesh {
+                        game.kill_entity(mesh);
                     }
                     game.kill_entity(entity);
                     game.set_trigger(Trigger::DialogEnd);
@@ -95,11 +93,16 @@ impl<'s> System<'s> for TalkDialogSystem {
             }
 
             self.set_text(ui_text, dialog);
-            if dialog.mesh.is_some() {
+            if let Some(mesh) = dialog.mesh {
                 for (e, trans) in (&*entities, &mut transforms).join() {
-                    if e == dialog.mesh.unwrap() {
-                        let cam_trans = game.camera_trans.clone();
-                        trans.set_translation_xyz(cam_trans[0] + 320.0, cam_trans[1] - 640.0 + 80.0, 3.0);
+                    if e == mesh {
+                        // Along the bottom of the screen, below the camera's top-left corner.
+                        let cam_trans = game.camera_trans;
+                        trans.set_translation_xyz(
+                            cam_trans[0] + screen.width / 2.0,
+                            cam_trans[1] - screen.height + BOX_CENTER_Y,
+                            3.0,
+                        );
                     }
                 }
             }
@@ -0,0 +1,45 @@
+use amethyst::{
+    core::Time,
+    derive::SystemDesc,
+    ecs::prelude::{Join, Read, System, SystemData, Write, WriteStorage},
+    ui::UiImage,
+};
+
+use crate::entity::evolution::animation::EvolutionAnimation;
+use crate::state::{Game, Trigger};
+
+#[derive(SystemDesc)]
+pub struct EvolutionAnimationSystem;
+
+impl EvolutionAnimationSystem {
+    pub fn new() -> Self {
+        EvolutionAnimationSystem
+    }
+}
+
+impl<'s> System<'s> for EvolutionAnimationSystem {
+    type SystemData = (
+        WriteStorage<'s, EvolutionAnimation>,
+        WriteStorage<'s, UiImage>,
+        Write<'s, Game>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (mut animations, mut images, mut game, time): Self::SystemData) {
+        for (animation, image) in (&mut animations, &mut images).join() {
+            if animation.is_finished() {
+                continue;
+            }
+
+            animation.elapsed += time.delta_seconds();
+            if let UiImage::Sprite(render) = image {
+                render.sprite_number = animation.sprite();
+            }
+
+            // The entity is left for the evolution state, which shows the new icon a while.
+            if animation.is_finished() {
+                game.set_trigger(Trigger::EvolutionEnd);
+            }
+        }
+    }
+}
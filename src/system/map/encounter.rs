@@ -0,0 +1,52 @@
+use amethyst::{
+    ecs::prelude::{Read, ReadStorage, System, SystemData, World, Write},
+    shrev::{EventChannel, ReaderId},
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::clock::Clock;
+use crate::entity::actor::{StepEvent, player::Player};
+use crate::map::{encounter::EncounterTable, stream::MapStream};
+use crate::state::{Game, Trigger};
+
+pub struct EncounterSystem {
+    reader: ReaderId<StepEvent>,
+    rng: StdRng,
+}
+
+impl EncounterSystem {
+    pub fn new(world: &mut World) -> Self {
+        <Self as System<'_>>::SystemData::setup(world);
+        let reader = world.fetch_mut::<EventChannel<StepEvent>>().register_reader();
+
+        EncounterSystem {
+            reader,
+            rng: StdRng::from_entropy(),
+        }
+    }
+}
+
+impl<'s> System<'s> for EncounterSystem {
+    type SystemData = (
+        Read<'s, EventChannel<StepEvent>>,
+        ReadStorage<'s, Player>,
+        Read<'s, MapStream>,
+        Read<'s, EncounterTable>,
+        Read<'s, Clock>,
+        Write<'s, Game>
+    );
+
+    fn run(&mut self, (step_events, players, stream, table, clock, mut game): Self::SystemData) {
+        for event in step_events.read(&mut self.reader) {
+            if players.get(event.entity).is_none() || game.get_trigger().is_some() {
+                continue;
+            }
+
+            if stream.any_at((event.grid_pos[0], event.grid_pos[1]), |class| class.is_grass()) {
+                if let Some(encounter) = table.roll(clock.time_of_day(), &mut self.rng) {
+                    game.set_trigger(Trigger::WildEncounter(encounter));
+                }
+            }
+        }
+    }
+}
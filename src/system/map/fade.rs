@@ -0,0 +1,42 @@
+use amethyst::{
+    core::Time,
+    derive::SystemDesc,
+    ecs::prelude::{Join, Read, System, SystemData, Write, WriteStorage},
+    ui::UiImage,
+};
+
+use crate::entity::map::fade::MapFade;
+use crate::state::{Game, Trigger};
+
+#[derive(SystemDesc)]
+pub struct MapFadeSystem;
+
+impl MapFadeSystem {
+    pub fn new() -> Self {
+        MapFadeSystem
+    }
+}
+
+impl<'s> System<'s> for MapFadeSystem {
+    type SystemData = (
+        WriteStorage<'s, MapFade>,
+        WriteStorage<'s, UiImage>,
+        Write<'s, Game>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (mut fades, mut images, mut game, time): Self::SystemData) {
+        for (fade, image) in (&mut fades, &mut images).join() {
+            if fade.is_finished() {
+                continue;
+            }
+
+            fade.elapsed += time.delta_seconds();
+            *image = UiImage::SolidColor([0., 0., 0., fade.alpha()]);
+
+            if fade.is_finished() {
+                game.set_trigger(Trigger::FadeEnd);
+            }
+        }
+    }
+}
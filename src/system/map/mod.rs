@@ -0,0 +1,5 @@
+pub mod encounter;
+pub mod warp;
+pub mod fade;
+pub mod tint;
+pub mod weather;
@@ -0,0 +1,39 @@
+use amethyst::{
+    core::Time,
+    derive::SystemDesc,
+    ecs::prelude::{Join, Read, System, SystemData, WriteStorage},
+    ui::UiImage,
+};
+
+use crate::clock::{Clock, TimeOfDay};
+use crate::entity::map::tint::{DayTint, TINT_BLEND_TIME};
+use crate::map::stream::MapStream;
+
+#[derive(SystemDesc)]
+pub struct DayTintSystem;
+
+impl DayTintSystem {
+    pub fn new() -> Self {
+        DayTintSystem
+    }
+}
+
+impl<'s> System<'s> for DayTintSystem {
+    type SystemData = (
+        WriteStorage<'s, DayTint>,
+        WriteStorage<'s, UiImage>,
+        Read<'s, Clock>,
+        Read<'s, MapStream>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (mut tints, mut images, clock, stream, time): Self::SystemData) {
+        let indoors = stream.current().map(|map| map.layout.indoors).unwrap_or(false);
+        let target = if indoors { TimeOfDay::Day.tint() } else { clock.time_of_day().tint() };
+
+        for (tint, image) in (&mut tints, &mut images).join() {
+            tint.blend(target, time.delta_seconds() / TINT_BLEND_TIME);
+            *image = UiImage::SolidColor(tint.color);
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use amethyst::{
+    ecs::prelude::{Read, ReadStorage, System, SystemData, World, Write},
+    shrev::{EventChannel, ReaderId},
+};
+
+use crate::entity::actor::{StepEvent, player::Player};
+use crate::map::layout::MapLayout;
+use crate::state::{Game, Trigger};
+
+// Reports `Trigger::Warp` when the player finishes a step onto a warp of the current map.
+pub struct WarpSystem {
+    reader: ReaderId<StepEvent>,
+}
+
+impl WarpSystem {
+    pub fn new(world: &mut World) -> Self {
+        <Self as System<'_>>::SystemData::setup(world);
+        let reader = world.fetch_mut::<EventChannel<StepEvent>>().register_reader();
+
+        WarpSystem {
+            reader,
+        }
+    }
+}
+
+impl<'s> System<'s> for WarpSystem {
+    type SystemData = (
+        Read<'s, EventChannel<StepEvent>>,
+        ReadStorage<'s, Player>,
+        Read<'s, MapLayout>,
+        Write<'s, Game>
+    );
+
+    fn run(&mut self, (step_events, players, layout, mut game): Self::SystemData) {
+        for event in step_events.read(&mut self.reader) {
+            if players.get(event.entity).is_none() || game.get_trigger().is_some() {
+                continue;
+            }
+
+            if let Some(warp) = layout.warp_at((event.grid_pos[0], event.grid_pos[1])) {
+                game.set_trigger(Trigger::Warp(warp.clone()));
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use amethyst::{
+    core::Time,
+    ecs::prelude::{Entities, Join, Read, ReadExpect, System, WriteStorage},
+    ui::{Anchor, Stretch, UiImage, UiTransform},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::battle::weather::Weather;
+use crate::entity::map::weather::{self, WeatherLayer, WEATHER_OVERLAY_Z, WEATHER_PARTICLE_Z};
+use crate::entity::screen::VirtualLayout;
+use crate::map::stream::MapStream;
+use crate::utils::screen::VirtualScreen;
+
+// Shows the weather of the current map, swapping it out as the player crosses into a map with
+// other weather.
+pub struct WeatherSystem {
+    shown: Option<Weather>,
+    rng: StdRng,
+}
+
+impl WeatherSystem {
+    pub fn new() -> Self {
+        WeatherSystem {
+            shown: None,
+            rng: StdRng::from_entropy(),
+        }
+    }
+}
+
+impl<'s> System<'s> for WeatherSystem {
+    type SystemData = (
+        WriteStorage<'s, WeatherLayer>,
+        WriteStorage<'s, UiTransform>,
+        WriteStorage<'s, UiImage>,
+        WriteStorage<'s, VirtualLayout>,
+        Read<'s, MapStream>,
+        Read<'s, Time>,
+        ReadExpect<'s, VirtualScreen>,
+        Entities<'s>
+    );
+
+    fn run(&mut self, (mut layers, mut transforms, mut images, mut layouts, stream, time, screen, entities): Self::SystemData) {
+        let current = stream.current().and_then(|map| map.layout.weather);
+
+        if current != self.shown {
+            for (entity, _) in (&*entities, &layers).join() {
+                entities.delete(entity).expect("failed to delete weather");
+            }
+
+            if let Some(color) = current.and_then(weather::overlay) {
+                let transform = UiTransform::new(
+                    "weather_overlay".to_string(),
+                    Anchor::Middle,
+                    Anchor::Middle,
+                    0.,
+                    0.,
+                    WEATHER_OVERLAY_Z,
+                    screen.width,
+                    screen.height,
+                ).with_stretch(Stretch::XY {
+                    x_margin: 0.,
+                    y_margin: 0.,
+                    keep_aspect_ratio: false,
+                });
+
+                entities.build_entity()
+                    .with(transform, &mut transforms)
+                    .with(UiImage::SolidColor(color), &mut images)
+                    .with(WeatherLayer { position: None, velocity: (0., 0.) }, &mut layers)
+                    .build();
+            }
+
+            if let Some(style) = current.and_then(weather::particles) {
+                for i in 0..style.count {
+                    let position = (self.rng.gen_range(0., screen.width), self.rng.gen_range(0., screen.height));
+                    let speed = self.rng.gen_range(0.7, 1.3);
+                    let transform = UiTransform::new(
+                        format!("weather_particle_{}", i),
+                        Anchor::BottomLeft,
+                        Anchor::Middle,
+                        position.0,
+                        position.1,
+                        WEATHER_PARTICLE_Z,
+                        style.size.0,
+                        style.size.1,
+                    );
+
+                    entities.build_entity()
+                        .with(VirtualLayout::of(&transform), &mut layouts)
+                        .with(transform, &mut transforms)
+                        .with(UiImage::SolidColor(style.color), &mut images)
+                        .with(WeatherLayer {
+                            position: Some(position),
+                            velocity: (style.velocity.0 * speed, style.velocity.1 * speed),
+                        }, &mut layers)
+                        .build();
+                }
+            }
+
+            self.shown = current;
+        }
+
+        // Particles leaving one edge of the screen come back in on the other.
+        let delta = time.delta_seconds();
+        for (layer, layout) in (&mut layers, &mut layouts).join() {
+            if let Some((x, y)) = layer.position {
+                let position = (
+                    (x + layer.velocity.0 * delta).rem_euclid(screen.width),
+                    (y + layer.velocity.1 * delta).rem_euclid(screen.height),
+                );
+                layer.position = Some(position);
+                layout.x = position.0;
+                layout.y = position.1;
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use amethyst::{
+    core::Time,
+    ecs::prelude::{Join, Read, System, SystemData, World, Write, WriteStorage, Entities},
+    input::{InputHandler, StringBindings},
+    shrev::EventChannel,
+    ui::UiText,
+};
+
+use crate::audio::{Sound, SoundQueue};
+use crate::entity::menu::list::{ListEvent, ListEventKind, ListWidget};
+use crate::entity::menu::selection::ListInput;
+
+// Seconds an arrow key has to be held before the cursor starts repeating, and between repeats.
+const REPEAT_DELAY: f32 = 0.4;
+const REPEAT_INTERVAL: f32 = 0.1;
+
+// Moves the cursor of active list widgets, reports confirm and cancel presses, and redraws
+// every list.
+pub struct ListWidgetSystem {
+    held: Option<ListInput>,
+    // Seconds the direction has been held for, and when it repeats next.
+    held_time: f32,
+    next_repeat: f32,
+}
+
+impl ListWidgetSystem {
+    pub fn new(world: &mut World) -> Self {
+        <Self as System<'_>>::SystemData::setup(world);
+
+        ListWidgetSystem {
+            held: None,
+            held_time: 0.0,
+            next_repeat: REPEAT_DELAY,
+        }
+    }
+
+    // The direction to move in this frame: once on press, then repeated while held.
+    fn step(&mut self, input: &InputHandler<StringBindings>, delta: f32) -> Option<ListInput> {
+        let direction = if input.action_is_down("up").unwrap_or(false) {
+            Some(ListInput::Up)
+        } else if input.action_is_down("down").unwrap_or(false) {
+            Some(ListInput::Down)
+        } else {
+            None
+        };
+
+        if direction.is_none() || direction != self.held {
+            self.held = direction;
+            self.held_time = 0.0;
+            self.next_repeat = REPEAT_DELAY;
+            return direction;
+        }
+
+        // A slow frame repeats once, not once per interval it covered.
+        self.held_time += delta;
+        if self.held_time < self.next_repeat {
+            return None;
+        }
+        while self.next_repeat <= self.held_time {
+            self.next_repeat += REPEAT_INTERVAL;
+        }
+        direction
+    }
+
+    fn draw(widget: &ListWidget, texts: &mut WriteStorage<UiText>) {
+        for (row, (text, color)) in widget.rows.iter().zip(widget.row_texts()) {
+            if let Some(ui_text) = texts.get_mut(*row) {
+                ui_text.text = text;
+                ui_text.color = color;
+            }
+        }
+
+        let (up, down) = widget.arrows;
+        if let Some(ui_text) = texts.get_mut(up) {
+            ui_text.text = if widget.selection.more_above() { "^" } else { "" }.to_string();
+        }
+        if let Some(ui_text) = texts.get_mut(down) {
+            ui_text.text = if widget.selection.more_below() { "v" } else { "" }.to_string();
+        }
+    }
+}
+
+impl<'s> System<'s> for ListWidgetSystem {
+    type SystemData = (
+        WriteStorage<'s, ListWidget>,
+        WriteStorage<'s, UiText>,
+        Read<'s, InputHandler<StringBindings>>,
+        Write<'s, EventChannel<ListEvent>>,
+        Write<'s, SoundQueue>,
+        Read<'s, Time>,
+        Entities<'s>
+    );
+
+    fn run(&mut self, (mut widgets, mut texts, input, mut events, mut sounds, time, entities): Self::SystemData) {
+        let step = self.step(&input, time.delta_seconds());
+        let confirm = input.action_is_down("continue").unwrap_or(false);
+        let cancel = input.action_is_down("cancel").unwrap_or(false);
+
+        for (widget, list) in (&mut widgets, &*entities).join() {
+            if widget.is_active() {
+                if let Some(direction) = step {
+                    if widget.selection.navigate(direction) {
+                        sounds.play(Sound::Cursor);
+                        events.single_write(ListEvent { list, kind: ListEventKind::Moved(widget.selection.index()) });
+                    }
+                }
+
+                if widget.armed && confirm {
+                    if let Some(index) = widget.selection.confirm() {
+                        events.single_write(ListEvent { list, kind: ListEventKind::Confirmed(index) });
+                    }
+                } else if widget.armed && cancel {
+                    events.single_write(ListEvent { list, kind: ListEventKind::Cancelled });
+                }
+
+                // Keys held from before the list was shown have to be let go first.
+                widget.armed = !confirm && !cancel;
+            }
+
+            ListWidgetSystem::draw(widget, &mut texts);
+        }
+    }
+}
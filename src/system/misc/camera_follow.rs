@@ -0,0 +1,142 @@
+use amethyst::{
+    core::{math::Vector2, transform::Transform, Time},
+    ecs::prelude::{Entities, Join, Read, ReadExpect, System, WriteStorage},
+};
+
+use crate::entity::actor::CELL_SIZE;
+use crate::entity::camera::{framing, CameraFollow};
+use crate::entity::tile::tile::TILE_DROP;
+use crate::map::stream::MapStream;
+use crate::utils::screen::VirtualScreen;
+
+// Moves cameras along with what they follow, keeping the screen inside the map.
+pub struct CameraFollowSystem;
+
+impl CameraFollowSystem {
+    pub fn new() -> Self {
+        CameraFollowSystem
+    }
+
+    // Keeps a screen of `size` centered on `center` between `low` and `high`, or centers it
+    // between them if it does not fit.
+    fn clamp_axis(center: f32, low: f32, high: f32, size: f32) -> f32 {
+        if high - low <= size {
+            (low + high) / 2.0
+        } else {
+            center.max(low + size / 2.0).min(high - size / 2.0)
+        }
+    }
+
+    // Keeps a screen of `width` by `height` centered on `center` inside the map cells `bounds`.
+    fn clamp(
+        center: Vector2<f32>,
+        bounds: Option<((i32, i32), (i32, i32))>,
+        width: f32,
+        height: f32,
+    ) -> Vector2<f32> {
+        let (from, to) = match bounds {
+            Some(bounds) => bounds,
+            None => return center,
+        };
+
+        // The edges of the outermost tiles.
+        let half = CELL_SIZE / 2.0;
+        let left = from.0 as f32 * CELL_SIZE - half;
+        let right = to.0 as f32 * CELL_SIZE + half;
+        let bottom = from.1 as f32 * CELL_SIZE - TILE_DROP - half;
+        let top = to.1 as f32 * CELL_SIZE - TILE_DROP + half;
+
+        Vector2::new(
+            CameraFollowSystem::clamp_axis(center[0], left, right, width),
+            CameraFollowSystem::clamp_axis(center[1], bottom, top, height),
+        )
+    }
+}
+
+impl<'s> System<'s> for CameraFollowSystem {
+    type SystemData = (
+        WriteStorage<'s, CameraFollow>,
+        WriteStorage<'s, Transform>,
+        Read<'s, MapStream>,
+        ReadExpect<'s, VirtualScreen>,
+        Read<'s, Time>,
+        Entities<'s>
+    );
+
+    fn run(&mut self, (mut follows, mut transforms, stream, screen, time, entities): Self::SystemData) {
+        let (width, height) = (screen.width, screen.height);
+
+        for (follow, entity) in (&mut follows, &*entities).join() {
+            let current = match transforms.get(entity) {
+                Some(transform) => {
+                    let translation = transform.translation();
+                    Vector2::new(translation[0] + width / 2.0, translation[1] - height / 2.0)
+                }
+                None => continue,
+            };
+
+            let target = follow.target
+                .and_then(|target| transforms.get(target))
+                .map(|transform| Vector2::new(transform.translation()[0], transform.translation()[1]))
+                .unwrap_or(current);
+
+            let (to, fraction) = follow.advance(target, time.delta_seconds());
+            let to = CameraFollowSystem::clamp(to, stream.bounds(), width, height);
+            let center = current + (to - current) * fraction;
+
+            if let Some(transform) = transforms.get_mut(entity) {
+                let translation = framing(center, width, height);
+                transform.set_translation_x(translation[0]);
+                transform.set_translation_y(translation[1]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDTH: f32 = 160.0;
+    const HEIGHT: f32 = 144.0;
+    // Cells 0 to 19 by 0 to 19: the tile edges are -8 to 312 across and -20 to 300 up.
+    const BOUNDS: ((i32, i32), (i32, i32)) = ((0, 0), (19, 19));
+
+    fn clamp(center: (f32, f32), bounds: ((i32, i32), (i32, i32))) -> (f32, f32) {
+        let clamped = CameraFollowSystem::clamp(Vector2::new(center.0, center.1), Some(bounds), WIDTH, HEIGHT);
+        (clamped[0], clamped[1])
+    }
+
+    #[test]
+    fn centers_stay_put_in_the_middle_of_the_map() {
+        assert_eq!(clamp((150.0, 140.0), BOUNDS), (150.0, 140.0));
+    }
+
+    #[test]
+    fn centers_are_clamped_at_each_edge() {
+        assert_eq!(clamp((0.0, 140.0), BOUNDS), (72.0, 140.0));
+        assert_eq!(clamp((400.0, 140.0), BOUNDS), (232.0, 140.0));
+        assert_eq!(clamp((150.0, -50.0), BOUNDS), (150.0, 52.0));
+        assert_eq!(clamp((150.0, 400.0), BOUNDS), (150.0, 228.0));
+        assert_eq!(clamp((-100.0, -100.0), BOUNDS), (72.0, 52.0));
+        assert_eq!(clamp((500.0, 500.0), BOUNDS), (232.0, 228.0));
+    }
+
+    #[test]
+    fn maps_smaller_than_the_screen_are_centered() {
+        // 5 by 4 cells: -8 to 72 across and -20 to 44 up.
+        let small = ((0, 0), (4, 3));
+        assert_eq!(clamp((0.0, 0.0), small), (32.0, 12.0));
+        assert_eq!(clamp((500.0, -500.0), small), (32.0, 12.0));
+
+        // Only too narrow: the other axis is still clamped.
+        let narrow = ((0, 0), (4, 19));
+        assert_eq!(clamp((500.0, 500.0), narrow), (32.0, 228.0));
+    }
+
+    #[test]
+    fn centers_are_free_without_a_map() {
+        let center = Vector2::new(-500.0, 500.0);
+        assert_eq!(CameraFollowSystem::clamp(center, None, WIDTH, HEIGHT), center);
+    }
+}
@@ -1,5 +1,4 @@
 use amethyst::{
-    prelude::*,
     derive::SystemDesc,
     ecs::prelude::{Join, System, SystemData, WriteStorage, Write},
     renderer::{
@@ -28,7 +27,7 @@ impl<'s> System<'s> for CameraTranslationSystem {
 
     fn run(&mut self, (mut cameras, mut transforms, mut game): Self::SystemData) {
         for (_, transform) in (&mut cameras, &mut transforms).join() {
-            game.camera_trans = transform.translation().clone();
+            game.camera_trans = *transform.translation();
         }
     }
 }
\ No newline at end of file
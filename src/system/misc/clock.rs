@@ -0,0 +1,28 @@
+use amethyst::{
+    core::Time,
+    derive::SystemDesc,
+    ecs::prelude::{Read, System, SystemData, Write},
+};
+
+use crate::clock::Clock;
+
+// Runs the in-game clock. It keeps running in battles and menus.
+#[derive(SystemDesc)]
+pub struct ClockSystem;
+
+impl ClockSystem {
+    pub fn new() -> Self {
+        ClockSystem
+    }
+}
+
+impl<'s> System<'s> for ClockSystem {
+    type SystemData = (
+        Write<'s, Clock>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (mut clock, time): Self::SystemData) {
+        clock.advance(time.delta_seconds());
+    }
+}
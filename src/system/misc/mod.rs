@@ -1 +1,4 @@
-pub mod camera_translation;
\ No newline at end of file
+pub mod camera_translation;
+pub mod camera_follow;
+pub mod viewport;
+pub mod clock;
@@ -0,0 +1,119 @@
+use amethyst::{
+    ecs::prelude::{Entities, Join, ReadExpect, System, WriteStorage},
+    renderer::camera::{Camera, Projection},
+    ui::{Anchor, UiImage, UiText, UiTransform},
+    window::ScreenDimensions,
+};
+
+use crate::entity::screen::{Letterbox, VirtualLayout};
+use crate::utils::screen::VirtualScreen;
+
+const LETTERBOX_COLOR: [f32; 4] = [0., 0., 0., 1.];
+// Above every other UI element, fades included.
+const LETTERBOX_Z: f32 = 100.;
+
+// Fits the virtual screen into the window. Cameras show the virtual screen at a whole number
+// scale with the rest of the window letterboxed, and UI transforms are derived from their
+// layouts in virtual pixels to match.
+pub struct ViewportSystem;
+
+impl ViewportSystem {
+    pub fn new() -> Self {
+        ViewportSystem
+    }
+
+    fn letterbox_transform(side: Letterbox, window: (f32, f32), margins: (f32, f32)) -> UiTransform {
+        let (anchor, width, height) = match side {
+            Letterbox::Left => (Anchor::MiddleLeft, margins.0, window.1),
+            Letterbox::Right => (Anchor::MiddleRight, margins.0, window.1),
+            Letterbox::Top => (Anchor::TopMiddle, window.0, margins.1),
+            Letterbox::Bottom => (Anchor::BottomMiddle, window.0, margins.1),
+        };
+
+        UiTransform::new(format!("letterbox_{:?}", side).to_lowercase(), anchor, anchor, 0., 0., LETTERBOX_Z, width, height)
+    }
+
+    // Scales a layout into the window, keeping its anchor at the same spot of the virtual
+    // screen rather than of the window.
+    fn scale(layout: VirtualLayout, anchor: Anchor, screen: &VirtualScreen, window: (f32, f32)) -> VirtualLayout {
+        let scale = screen.scale(window);
+        let (norm_x, norm_y) = anchor.norm_offset();
+        VirtualLayout {
+            x: layout.x * scale + norm_x * (screen.width * scale - window.0),
+            y: layout.y * scale + norm_y * (screen.height * scale - window.1),
+            width: layout.width * scale,
+            height: layout.height * scale,
+            font_size: layout.font_size.map(|size| size * scale),
+        }
+    }
+}
+
+impl<'s> System<'s> for ViewportSystem {
+    type SystemData = (
+        WriteStorage<'s, Camera>,
+        WriteStorage<'s, UiTransform>,
+        WriteStorage<'s, UiText>,
+        WriteStorage<'s, UiImage>,
+        WriteStorage<'s, Letterbox>,
+        WriteStorage<'s, VirtualLayout>,
+        ReadExpect<'s, VirtualScreen>,
+        ReadExpect<'s, ScreenDimensions>,
+        Entities<'s>
+    );
+
+    fn run(&mut self, (mut cameras, mut transforms, mut texts, mut images, mut letterboxes, mut layouts, screen, dimensions, entities): Self::SystemData) {
+        let window = (dimensions.width(), dimensions.height());
+        let scale = screen.scale(window);
+        let margins = screen.margins(window);
+
+        // The camera's translation stays the top left corner of the virtual screen. What lies
+        // around it is drawn under the letterbox.
+        let (extra_x, extra_y) = (margins.0 / scale, margins.1 / scale);
+        for camera in (&mut cameras).join() {
+            camera.set_projection(Projection::orthographic(
+                -extra_x,
+                screen.width + extra_x,
+                -extra_y,
+                screen.height + extra_y,
+                0.0,
+                20.0,
+            ));
+        }
+
+        if (&letterboxes).join().next().is_none() {
+            for side in Letterbox::ALL.iter() {
+                entities.build_entity()
+                    .with(*side, &mut letterboxes)
+                    .with(ViewportSystem::letterbox_transform(*side, window, margins), &mut transforms)
+                    .with(UiImage::SolidColor(LETTERBOX_COLOR), &mut images)
+                    .build();
+            }
+        }
+
+        for (side, transform) in (&letterboxes, &mut transforms).join() {
+            let sized = ViewportSystem::letterbox_transform(*side, window, margins);
+            transform.width = sized.width;
+            transform.height = sized.height;
+        }
+
+        for (transform, entity, _) in (&mut transforms, &*entities, !&letterboxes).join() {
+            let mut layout = layouts.get(entity).copied().unwrap_or_else(|| VirtualLayout::of(transform));
+            // A text is only scaled once its size is in the layout, so until then it is still
+            // in virtual pixels.
+            if layout.font_size.is_none() {
+                layout.font_size = texts.get(entity).map(|text| text.font_size);
+            }
+
+            let scaled = ViewportSystem::scale(layout, transform.anchor, &screen, window);
+            transform.local_x = scaled.x;
+            transform.local_y = scaled.y;
+            transform.width = scaled.width;
+            transform.height = scaled.height;
+            if let (Some(text), Some(size)) = (texts.get_mut(entity), scaled.font_size) {
+                text.font_size = size;
+            }
+
+            layouts.insert(entity, layout).ok();
+        }
+    }
+}
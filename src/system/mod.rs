@@ -2,4 +2,9 @@ pub mod actor;
 pub mod bundle;
 pub mod dialog;
 pub mod tile;
-pub mod misc;
\ No newline at end of file
+pub mod misc;
+pub mod battle;
+pub mod map;
+pub mod menu;
+pub mod evolution;
+pub mod audio;
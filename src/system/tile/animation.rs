@@ -0,0 +1,53 @@
+use amethyst::{
+    core::Time,
+    ecs::prelude::{Join, Read, System, SystemData, World, WriteStorage},
+    shrev::{EventChannel, ReaderId},
+};
+
+use crate::entity::actor::StepEvent;
+use crate::entity::tile::{tile::Tile, animation::{TileAnimations, TileAnimator}};
+
+// Advances tile animations and writes the current frame into `TileAttrs::state`.
+pub struct TileAnimationSystem {
+    reader: ReaderId<StepEvent>,
+}
+
+impl TileAnimationSystem {
+    pub fn new(world: &mut World) -> Self {
+        <Self as System<'_>>::SystemData::setup(world);
+        let reader = world.fetch_mut::<EventChannel<StepEvent>>().register_reader();
+
+        TileAnimationSystem {
+            reader,
+        }
+    }
+}
+
+impl<'s> System<'s> for TileAnimationSystem {
+    type SystemData = (
+        WriteStorage<'s, Tile>,
+        WriteStorage<'s, TileAnimator>,
+        Read<'s, TileAnimations>,
+        Read<'s, EventChannel<StepEvent>>,
+        Read<'s, Time>
+    );
+
+    fn run(&mut self, (mut tiles, mut animators, animations, step_events, time): Self::SystemData) {
+        for event in step_events.read(&mut self.reader) {
+            for (tile, animator) in (&tiles, &mut animators).join() {
+                if tile.grid_pos != event.grid_pos {
+                    continue;
+                }
+
+                if let Some(frames) = tile.attrs.class().and_then(|c| animations.on_step.get(&c)) {
+                    animator.trigger(frames.clone());
+                }
+            }
+        }
+
+        for (tile, animator) in (&mut tiles, &mut animators).join() {
+            animator.advance(time.delta_seconds());
+            tile.attrs.set_state(animator.state());
+        }
+    }
+}
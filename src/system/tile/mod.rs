@@ -1 +1,2 @@
-pub mod static_tile;
\ No newline at end of file
+pub mod static_tile;
+pub mod animation;
\ No newline at end of file
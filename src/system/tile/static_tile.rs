@@ -1,6 +1,6 @@
 use amethyst::{
     derive::SystemDesc,
-    ecs::prelude::{System, SystemData, WriteStorage},
+    ecs::prelude::{Join, System, SystemData, ReadStorage, WriteStorage},
     renderer::{SpriteRender},
 };
 use crate::entity::tile::tile::Tile;
@@ -19,9 +19,14 @@ impl StaticTileSystem {
 }
 
 impl<'s> System<'s> for StaticTileSystem {
-    type SystemData = WriteStorage<'s, Tile>;
-
-    fn run(&mut self, _data: Self::SystemData) {
+    type SystemData = (
+        ReadStorage<'s, Tile>,
+        WriteStorage<'s, SpriteRender>
+    );
 
+    fn run(&mut self, (tiles, mut sprites): Self::SystemData) {
+        for (tile, sprite) in (&tiles, &mut sprites).join() {
+            self.draw(tile, sprite);
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,57 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::battle::ai::AiTier;
+use crate::pokemon::{library::Library, Pokemon};
+use crate::utils::data::{self, DataError};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrainerPokemon {
+    pub species: String,
+    pub level: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrainerData {
+    pub id: String,
+    pub name: String,
+    pub sprite: String,
+    pub team: Vec<TrainerPokemon>,
+    // Said when the trainer walks up to the player, before the battle.
+    pub intro: Vec<String>,
+    // Said after the trainer is defeated.
+    pub defeat: Vec<String>,
+    #[serde(default)]
+    pub ai: AiTier,
+}
+
+impl TrainerData {
+    pub fn build_team(&self, library: &Library) -> Vec<Pokemon> {
+        self.team.iter()
+            .filter_map(|p| Pokemon::new(library, &p.species, p.level))
+            .collect()
+    }
+}
+
+#[derive(Default)]
+pub struct Trainers {
+    trainers: HashMap<String, TrainerData>,
+}
+
+impl Trainers {
+    pub fn load(path: &Path) -> Result<Self, DataError> {
+        let trainers: Vec<TrainerData> = data::load_ron(path)?;
+
+        Ok(Trainers {
+            trainers: trainers.into_iter().map(|t| (t.id.clone(), t)).collect(),
+        })
+    }
+
+    pub fn get(&self, id: &str) -> Option<&TrainerData> {
+        self.trainers.get(id)
+    }
+}
@@ -0,0 +1,198 @@
+use std::path::PathBuf;
+
+use amethyst::input::{Bindings, Button, StringBindings};
+
+use crate::utils::{data::{self, DataError}, resolve};
+
+// The actions the player can rebind, with the names shown on the controls screen.
+pub const ACTIONS: [(&str, &str); 7] = [
+    ("up", "UP"),
+    ("down", "DOWN"),
+    ("left", "LEFT"),
+    ("right", "RIGHT"),
+    ("continue", "CONFIRM"),
+    ("cancel", "CANCEL"),
+    ("menu", "MENU"),
+];
+
+// Each action keeps its keyboard and gamepad bindings apart, so that binding one does not
+// take the other away.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Device {
+    Keyboard,
+    Gamepad,
+}
+
+impl Device {
+    pub fn of(button: &Button) -> Option<Device> {
+        match button {
+            Button::Key(_) | Button::ScanCode(_) => Some(Device::Keyboard),
+            Button::Controller(_, _) => Some(Device::Gamepad),
+            _ => None,
+        }
+    }
+}
+
+// Bindings written from the controls screen. They replace the shipped config/input.ron when
+// the game starts.
+pub fn path() -> PathBuf {
+    resolve::get_user_path("input.ron")
+}
+
+pub fn write(bindings: &Bindings<StringBindings>) -> Result<(), DataError> {
+    data::save_ron(&path(), bindings)
+}
+
+fn button_label(button: &Button) -> String {
+    match button {
+        Button::Key(key) => format!("{:?}", key),
+        Button::ScanCode(code) => format!("#{}", code),
+        Button::Controller(_, button) => format!("{:?}", button),
+        other => format!("{:?}", other),
+    }.to_uppercase()
+}
+
+// The buttons bound to an action on a device, or "-" if there are none.
+pub fn label(bindings: &Bindings<StringBindings>, action: &str, device: Device) -> String {
+    let labels: Vec<String> = bindings.action_bindings(action)
+        .filter(|combo| combo.iter().all(|button| Device::of(button) == Some(device)))
+        .map(|combo| combo.iter().map(button_label).collect::<Vec<String>>().join("+"))
+        .collect();
+
+    if labels.is_empty() { "-".to_string() } else { labels.join(" ") }
+}
+
+// Binds the button to the action in place of the action's other buttons on the same device.
+// An action that held the button gets those buttons in exchange, so that rebinding never
+// leaves an action without a button to press. When several held it, only one of them gets
+// the exchange, preferring one left with no button on the device.
+pub fn rebind(bindings: &mut Bindings<StringBindings>, action: &str, button: Button) {
+    let device = match Device::of(&button) {
+        Some(device) => device,
+        None => return,
+    };
+
+    let replaced: Vec<Vec<Button>> = bindings.action_bindings(action)
+        .filter(|combo| combo.iter().any(|b| Device::of(b) == Some(device)))
+        .map(|combo| combo.to_vec())
+        .collect();
+    let mut holders: Vec<String> = bindings.actions()
+        .filter(|other| other.as_str() != action && bindings.action_bindings(other.as_str()).any(|combo| combo.contains(&button)))
+        .cloned()
+        .collect();
+    holders.sort();
+
+    for combo in &replaced {
+        remove(bindings, action, combo);
+    }
+    for other in &holders {
+        let taken: Vec<Vec<Button>> = bindings.action_bindings(other.as_str())
+            .filter(|combo| combo.contains(&button))
+            .map(|combo| combo.to_vec())
+            .collect();
+        for combo in &taken {
+            remove(bindings, other, combo);
+        }
+    }
+
+    let heir = holders.iter()
+        .find(|other| !is_bound(bindings, other, device))
+        .or_else(|| holders.first());
+    if let Some(other) = heir {
+        for combo in replaced.iter().filter(|combo| !combo.contains(&button)) {
+            insert(bindings, other, combo.clone());
+        }
+    }
+
+    insert(bindings, action, vec![button]);
+}
+
+fn is_bound(bindings: &Bindings<StringBindings>, action: &str, device: Device) -> bool {
+    bindings.action_bindings(action)
+        .any(|combo| combo.iter().all(|button| Device::of(button) == Some(device)))
+}
+
+fn insert(bindings: &mut Bindings<StringBindings>, action: &str, combo: Vec<Button>) {
+    if let Err(e) = bindings.insert_action_binding(action.to_string(), combo) {
+        eprintln!("failed to bind {}: {}", action, e);
+    }
+}
+
+fn remove(bindings: &mut Bindings<StringBindings>, action: &str, combo: &[Button]) {
+    if let Err(e) = bindings.remove_action_binding(action, combo) {
+        eprintln!("failed to unbind {}: {}", action, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amethyst::input::{ControllerButton, VirtualKeyCode};
+
+    const BOUND_ACTIONS: [&str; 3] = ["continue", "cancel", "menu"];
+
+    fn bindings() -> Bindings<StringBindings> {
+        let mut bindings = Bindings::new();
+        for (action, key, button) in &[
+            ("continue", VirtualKeyCode::Z, ControllerButton::A),
+            ("cancel", VirtualKeyCode::X, ControllerButton::B),
+            ("menu", VirtualKeyCode::Return, ControllerButton::Start),
+        ] {
+            insert(&mut bindings, action, vec![Button::Key(*key)]);
+            insert(&mut bindings, action, vec![Button::Controller(0, *button)]);
+        }
+        bindings
+    }
+
+    #[test]
+    fn taking_another_actions_button_swaps_them() {
+        let mut bindings = bindings();
+        rebind(&mut bindings, "continue", Button::Key(VirtualKeyCode::X));
+
+        assert_eq!(label(&bindings, "continue", Device::Keyboard), "X");
+        assert_eq!(label(&bindings, "cancel", Device::Keyboard), "Z");
+        assert_eq!(label(&bindings, "menu", Device::Keyboard), "RETURN");
+    }
+
+    #[test]
+    fn rebinding_keeps_the_other_device() {
+        let mut bindings = bindings();
+        rebind(&mut bindings, "cancel", Button::Controller(0, ControllerButton::Start));
+
+        assert_eq!(label(&bindings, "cancel", Device::Gamepad), "START");
+        assert_eq!(label(&bindings, "menu", Device::Gamepad), "B");
+        assert_eq!(label(&bindings, "cancel", Device::Keyboard), "X");
+    }
+
+    #[test]
+    fn a_free_button_replaces_the_actions_own() {
+        let mut bindings = bindings();
+        rebind(&mut bindings, "menu", Button::Key(VirtualKeyCode::Space));
+
+        assert_eq!(label(&bindings, "menu", Device::Keyboard), "SPACE");
+        assert_eq!(label(&bindings, "continue", Device::Keyboard), "Z");
+        assert_eq!(label(&bindings, "cancel", Device::Keyboard), "X");
+    }
+
+    #[test]
+    fn only_one_of_several_holders_gets_the_old_button() {
+        let mut bindings = bindings();
+        insert(&mut bindings, "menu", vec![Button::Key(VirtualKeyCode::X)]);
+        rebind(&mut bindings, "continue", Button::Key(VirtualKeyCode::X));
+
+        assert_eq!(label(&bindings, "continue", Device::Keyboard), "X");
+        assert_eq!(label(&bindings, "cancel", Device::Keyboard), "Z");
+        assert_eq!(label(&bindings, "menu", Device::Keyboard), "RETURN");
+    }
+
+    #[test]
+    fn no_action_is_left_without_a_button() {
+        let mut bindings = bindings();
+        for (action, key) in &[("continue", VirtualKeyCode::X), ("menu", VirtualKeyCode::Z), ("cancel", VirtualKeyCode::Return)] {
+            rebind(&mut bindings, action, Button::Key(*key));
+            for other in &BOUND_ACTIONS {
+                assert_ne!(label(&bindings, other, Device::Keyboard), "-", "{} lost its key", other);
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use std::{
+    fmt,
+    fs::{self, File},
+    io,
+    path::Path,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+#[derive(Debug)]
+pub enum DataError {
+    Io(io::Error),
+    Parse(ron::de::Error),
+    Serialize(ron::ser::Error),
+}
+
+impl fmt::Display for DataError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DataError::Io(e) => write!(f, "io error: {}", e),
+            DataError::Parse(e) => write!(f, "parse error: {}", e),
+            DataError::Serialize(e) => write!(f, "serialize error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for DataError {}
+
+impl From<io::Error> for DataError {
+    fn from(e: io::Error) -> Self {
+        DataError::Io(e)
+    }
+}
+
+impl From<ron::de::Error> for DataError {
+    fn from(e: ron::de::Error) -> Self {
+        DataError::Parse(e)
+    }
+}
+
+impl From<ron::ser::Error> for DataError {
+    fn from(e: ron::ser::Error) -> Self {
+        DataError::Serialize(e)
+    }
+}
+
+// Data files are plain RON and are loaded without going through the amethyst asset loader,
+// so that they can be used outside of a running game.
+pub fn load_ron<T: DeserializeOwned>(path: &Path) -> Result<T, DataError> {
+    let file = File::open(path)?;
+    Ok(ron::de::from_reader(file)?)
+}
+
+// Writes a value as pretty RON, creating the parent directory if needed.
+pub fn save_ron<T: Serialize>(path: &Path, value: &T) -> Result<(), DataError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let text = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())?;
+    fs::write(path, text)?;
+    Ok(())
+}
@@ -12,17 +12,17 @@ pub fn display_dbg(world: &mut World) {
         Anchor::TopRight,
         Anchor::MiddleRight,
         -0.,
-        -30.,
+        -8.,
         1.,
-        200.,
-        30.,
+        80.,
+        12.,
     );
 
     let text = UiText::new(
         font.clone(),
         "Development".to_string(),
         [1.0, 1.0, 1.0, 1.0],
-        30.,
+        10.,
     );
 
 
@@ -1,2 +1,6 @@
 pub mod resolve;
-pub mod debug;
\ No newline at end of file
+pub mod debug;
+pub mod data;
+pub mod settings;
+pub mod screen;
+pub mod controls;
@@ -3,7 +3,13 @@ use amethyst::{
     renderer::{Texture, SpriteSheet, ImageFormat, SpriteSheetFormat},
     assets::{AssetStorage, Handle, Loader},
     ui::{TtfFormat, FontAsset},
+    utils::application_root_dir,
 };
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::utils::data;
 
 
 pub fn load_texture_handle(world: &mut World, name: String) -> Handle<Texture> {
@@ -49,6 +55,67 @@ pub fn load_font_handle(world: &mut World) -> Handle<FontAsset> {
                                          &font_storage)
 }
 
+pub fn get_data_path(name: &str) -> PathBuf {
+    let app_root = application_root_dir().expect("failed to resolve application root");
+    app_root.join("./assets/data").join(name)
+}
+
+pub fn get_texture_path(name: &str) -> PathBuf {
+    let app_root = application_root_dir().expect("failed to resolve application root");
+    app_root.join("./assets/texture").join(name)
+}
+
+// Files written by the game, such as the save file and settings, live apart from the shipped
+// assets and config.
+pub fn get_user_path(name: &str) -> PathBuf {
+    let app_root = application_root_dir().expect("failed to resolve application root");
+    app_root.join("./user").join(name)
+}
+
+#[derive(Deserialize)]
+pub struct TimingConfig {
+    #[serde(default = "TimingConfig::default_fps")]
+    pub fps: usize,
+    // Seconds a new direction has to be held for the player to walk that way rather than only
+    // turn to face it.
+    #[serde(default = "TimingConfig::default_tap_to_turn")]
+    pub tap_to_turn: f32,
+}
+
+impl TimingConfig {
+    fn default_fps() -> usize {
+        60
+    }
+
+    fn default_tap_to_turn() -> f32 {
+        0.2
+    }
+}
+
+impl Default for TimingConfig {
+    fn default() -> Self {
+        TimingConfig {
+            fps: TimingConfig::default_fps(),
+            tap_to_turn: TimingConfig::default_tap_to_turn(),
+        }
+    }
+}
+
+// The timing config, or the defaults if it can't be read.
+pub fn get_timing() -> TimingConfig {
+    let path = get_config_path("timing.ron");
+    data::load_ron(&path).unwrap_or_else(|e| {
+        eprintln!("failed to load {}: {}", path.display(), e);
+        TimingConfig::default()
+    })
+}
+
+// The frame rate cap.
 pub fn get_fps() -> usize {
-    60
-}
\ No newline at end of file
+    get_timing().fps.max(1)
+}
+
+pub fn get_config_path(name: &str) -> PathBuf {
+    let app_root = application_root_dir().expect("failed to resolve application root");
+    app_root.join("./config").join(name)
+}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{data, resolve};
+
+// The fixed resolution the game is laid out in, the size of a handheld screen. Cameras, dialogs
+// and UI layouts work in these units, and the viewport system scales them up to the window by
+// whole numbers so that pixels stay square. What is left of the window is letterboxed.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VirtualScreen {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Default for VirtualScreen {
+    fn default() -> Self {
+        VirtualScreen {
+            width: 240.,
+            height: 160.,
+        }
+    }
+}
+
+impl VirtualScreen {
+    // Falls back to the default resolution when the config can't be read.
+    pub fn load() -> VirtualScreen {
+        let path = resolve::get_config_path("screen.ron");
+        data::load_ron(&path).unwrap_or_else(|e| {
+            eprintln!("failed to load {}: {}", path.display(), e);
+            VirtualScreen::default()
+        })
+    }
+
+    // Window pixels per virtual pixel: the largest whole number that fits, and never less than
+    // one. A window smaller than the virtual screen shows only part of it.
+    pub fn scale(&self, window: (f32, f32)) -> f32 {
+        (window.0 / self.width).min(window.1 / self.height).floor().max(1.0)
+    }
+
+    // The size of the letterbox on each side, in window pixels.
+    pub fn margins(&self, window: (f32, f32)) -> (f32, f32) {
+        let scale = self.scale(window);
+        (
+            ((window.0 - self.width * scale) / 2.0).max(0.0),
+            ((window.1 - self.height * scale) / 2.0).max(0.0),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scale_is_the_largest_whole_number_that_fits() {
+        let screen = VirtualScreen::default();
+
+        assert_eq!(screen.scale((720., 480.)), 3.);
+        assert_eq!(screen.scale((1000., 480.)), 3.);
+        assert_eq!(screen.scale((719., 480.)), 2.);
+        assert_eq!(screen.scale((100., 100.)), 1.);
+    }
+
+    #[test]
+    fn the_rest_of_the_window_is_letterboxed() {
+        let screen = VirtualScreen::default();
+
+        assert_eq!(screen.margins((720., 480.)), (0., 0.));
+        assert_eq!(screen.margins((1000., 500.)), (140., 10.));
+        assert_eq!(screen.margins((100., 100.)), (0., 0.));
+    }
+}
@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{data::{self, DataError}, resolve};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub enum TextSpeed {
+    Slow,
+    #[default]
+    Mid,
+    Fast,
+}
+
+impl TextSpeed {
+    pub const ALL: [TextSpeed; 3] = [TextSpeed::Slow, TextSpeed::Mid, TextSpeed::Fast];
+
+    // Characters revealed per second by the dialog typewriter.
+    pub fn chars_per_second(&self) -> f32 {
+        match self {
+            TextSpeed::Slow => 8.0,
+            TextSpeed::Mid => 15.0,
+            TextSpeed::Fast => 30.0,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TextSpeed::Slow => "SLOW",
+            TextSpeed::Mid => "MID",
+            TextSpeed::Fast => "FAST",
+        }
+    }
+}
+
+// Volumes are steps from 0, muted, to MAX_VOLUME.
+pub const MAX_VOLUME: u8 = 10;
+
+// Player preferences, kept in the user directory and shared by every save.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Settings {
+    #[serde(default)]
+    pub text_speed: TextSpeed,
+    #[serde(default = "Settings::default_volume")]
+    pub music_volume: u8,
+    #[serde(default = "Settings::default_volume")]
+    pub sound_volume: u8,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            text_speed: TextSpeed::default(),
+            music_volume: Settings::default_volume(),
+            sound_volume: Settings::default_volume(),
+        }
+    }
+}
+
+impl Settings {
+    fn default_volume() -> u8 {
+        7
+    }
+
+    // The volumes as fractions of full volume.
+    pub fn music_level(&self) -> f32 {
+        self.music_volume.min(MAX_VOLUME) as f32 / MAX_VOLUME as f32
+    }
+
+    pub fn sound_level(&self) -> f32 {
+        self.sound_volume.min(MAX_VOLUME) as f32 / MAX_VOLUME as f32
+    }
+
+    pub fn path() -> PathBuf {
+        resolve::get_user_path("settings.ron")
+    }
+
+    // Falls back to the defaults when there is no settings file or it can't be read.
+    pub fn load() -> Settings {
+        let path = Settings::path();
+        if !path.exists() {
+            return Settings::default();
+        }
+
+        data::load_ron(&path).unwrap_or_else(|e| {
+            eprintln!("failed to load {}: {}", path.display(), e);
+            Settings::default()
+        })
+    }
+
+    pub fn write(&self) -> Result<(), DataError> {
+        data::save_ron(&Settings::path(), self)
+    }
+}